@@ -50,6 +50,7 @@ use crate::opt::auth::Root;
 use crate::opt::IntoEndpoint;
 use crate::sql::Array;
 use crate::sql::Query;
+use crate::sql::Role;
 use crate::sql::Statement;
 use crate::sql::Statements;
 use crate::sql::Strand;
@@ -510,6 +511,10 @@ async fn router(
 		Method::Export | Method::Import => unreachable!(),
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
+			// Check the permissions, as the export includes the token and login secrets
+			if !session.au.is_db() || !session.au.check_role(Role::Owner) {
+				return Err(crate::Error::Db(crate::err::Error::QueryPermissions));
+			}
 			let (tx, rx) = channel::new::<Vec<u8>>(1);
 			let ns = session.ns.clone().unwrap_or_default();
 			let db = session.db.clone().unwrap_or_default();
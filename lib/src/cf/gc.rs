@@ -0,0 +1,232 @@
+use crate::cf::Change;
+use crate::err::Error;
+use crate::key::cf;
+use crate::key::hs;
+use crate::key::ts;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::sql::id::Id;
+use crate::vs::Versionstamp;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// gc_db deletes all expired change feed entries in the given database.
+//
+// Versionstamps do not contain a time, so the timestamp samples which
+// are saved by the datastore are used to find the latest versionstamp
+// which was allocated before the expiry of each change feed. All change
+// feed entries up to and including that versionstamp are removed. Any
+// entries belonging to tables without a change feed are removed too,
+// along with the expired history entries of the records of each table.
+pub async fn gc_db(tx: &mut Transaction, ns: &str, db: &str, ts: u64) -> Result<(), Error> {
+	// Fetch the database change feed expiry
+	let dbc = tx.get_db(ns, db).await?.changefeed.map(|v| v.expiry);
+	// Fetch the table change feed expiries
+	let tbs = tx.all_tb(ns, db).await?;
+	let exp: HashMap<&str, Option<Duration>> = tbs
+		.iter()
+		.map(|tb| (tb.name.as_str(), tb.changefeed.as_ref().map(|v| v.expiry).or(dbc)))
+		.collect();
+	// Fetch the saved timestamp samples
+	let beg = ts::prefix(ns, db);
+	let end = ts::suffix(ns, db);
+	let samples: Vec<(u64, Versionstamp)> = tx
+		.getr(beg..end, u32::MAX)
+		.await?
+		.into_iter()
+		.filter_map(|(k, v)| {
+			let k = ts::Ts::decode(&k).ok()?;
+			let v: Versionstamp = v.as_slice().try_into().ok()?;
+			Some((k.ts, v))
+		})
+		.collect();
+	// Find the latest sample taken before an expiry
+	let watermark = |expiry: Duration| -> Option<(u64, Versionstamp)> {
+		let cutoff = ts.saturating_sub(expiry.as_millis() as u64);
+		samples.iter().rev().find(|(t, _)| *t <= cutoff).copied()
+	};
+	// Compute the versionstamp watermark of each table
+	let marks: HashMap<&str, Option<Versionstamp>> = exp
+		.iter()
+		.map(|(tb, exp)| {
+			let mark = match exp {
+				Some(exp) => watermark(*exp).map(|(_, vs)| vs),
+				None => Some([0xff; 10]),
+			};
+			(*tb, mark)
+		})
+		.collect();
+	// Delete the expired change feed entries
+	let beg = cf::prefix(ns, db);
+	let end = cf::suffix(ns, db);
+	let mut nxt: Option<Key> = None;
+	loop {
+		// Get records batch
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+		};
+		// Get total results
+		let n = res.len();
+		// Exit when settled
+		if n == 0 {
+			break;
+		}
+		// Loop over results
+		for (i, (k, _)) in res.into_iter().enumerate() {
+			// Ready the next
+			if n == i + 1 {
+				nxt = Some(k.clone());
+			}
+			// Decode the change feed key
+			let dec = cf::Cf::decode(&k)?;
+			// Entries of removed tables are always expired
+			let mark = marks.get(dec.tb).copied().unwrap_or(Some([0xff; 10]));
+			if let Some(mark) = mark {
+				if dec.vs <= mark {
+					tx.del(k).await?;
+				}
+			}
+		}
+	}
+	// Delete the expired history entries of the records
+	for (tb, exp) in exp.iter() {
+		let cutoff = exp.map(|exp| ts.saturating_sub(exp.as_millis() as u64));
+		gc_history(tx, ns, db, tb, cutoff).await?;
+	}
+	// Delete any timestamp samples which are no longer needed
+	let keep = match exp.values().flatten().chain(dbc.iter()).max() {
+		Some(exp) => watermark(*exp).map(|(t, _)| t).unwrap_or(0),
+		None => ts,
+	};
+	for (t, _) in samples.iter().filter(|(t, _)| *t < keep) {
+		tx.del(ts::new(ns, db, *t)).await?;
+	}
+	Ok(())
+}
+
+// gc_history deletes the history entries of the records of a table, which
+// were committed before the cutoff time, or all of them if the table no
+// longer has a change feed. The latest entry of each record before the
+// cutoff is kept, as it is the value of the record up to its next change,
+// unless the record was deleted by it.
+async fn gc_history(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	cutoff: Option<u64>,
+) -> Result<(), Error> {
+	let beg = hs::prefix(ns, db, tb);
+	let end = hs::suffix(ns, db, tb);
+	// The latest entry before the cutoff of the last record
+	let mut last: Option<(Id, Key)> = None;
+	let mut nxt: Option<Key> = None;
+	loop {
+		// Get records batch
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+		};
+		// Get total results
+		let n = res.len();
+		// Exit when settled
+		if n == 0 {
+			break;
+		}
+		// Loop over results, in versionstamp order for each record
+		for (i, (k, v)) in res.into_iter().enumerate() {
+			// Ready the next
+			if n == i + 1 {
+				nxt = Some(k.clone());
+			}
+			let cutoff = match cutoff {
+				Some(cutoff) => cutoff,
+				None => {
+					tx.del(k).await?;
+					continue;
+				}
+			};
+			let dec = hs::Hs::decode(&k)?;
+			let chg: Change = v.into();
+			if chg.ts > cutoff {
+				continue;
+			}
+			// An earlier entry of the same record is superseded
+			if let Some((id, key)) = last.take() {
+				if id == dec.id {
+					tx.del(key).await?;
+				}
+			}
+			match chg.val {
+				// The record did not exist after this change
+				None => tx.del(k).await?,
+				Some(_) => last = Some((dec.id, k)),
+			}
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::kvs::Datastore;
+	use crate::sql::value::Value;
+	use crate::vs::u64_to_versionstamp;
+
+	#[tokio::test]
+	async fn gc_history_keeps_latest_entry_before_cutoff() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let one: Id = "one".into();
+		let two: Id = "two".into();
+		let changes = [
+			(&one, 1, 10, Some("a")),
+			(&one, 2, 20, Some("b")),
+			(&one, 3, 40, Some("c")),
+			(&two, 1, 10, Some("a")),
+			(&two, 2, 20, None),
+		];
+		for (id, vs, ts, val) in changes {
+			let key = hs::new("ns", "db", "tb", id, u64_to_versionstamp(vs));
+			let chg = Change {
+				ts,
+				new: false,
+				val: val.map(Value::from),
+			};
+			tx.set(key, chg).await.unwrap();
+		}
+		gc_history(&mut tx, "ns", "db", "tb", Some(30)).await.unwrap();
+		let res = tx.scan(hs::prefix("ns", "db", "tb")..hs::suffix("ns", "db", "tb"), 10).await;
+		let res: Vec<(Id, u64)> = res
+			.unwrap()
+			.into_iter()
+			.map(|(k, v)| (hs::Hs::decode(&k).unwrap().id, Change::from(v).ts))
+			.collect();
+		assert_eq!(res, vec![(one.clone(), 20), (one, 40)]);
+		// Without a change feed, the whole history is removed
+		gc_history(&mut tx, "ns", "db", "tb", None).await.unwrap();
+		let res = tx.scan(hs::prefix("ns", "db", "tb")..hs::suffix("ns", "db", "tb"), 10).await;
+		assert!(res.unwrap().is_empty());
+		tx.cancel().await.unwrap();
+	}
+}
@@ -0,0 +1,166 @@
+use crate::err::Error;
+use crate::key::hs;
+use crate::key::thing;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::sql::datetime::Datetime;
+use crate::sql::id::Id;
+use crate::sql::value::Value;
+use derive::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Change is the value of a record after a committed transaction, along
+// with the time of the commit, and whether the record was created by it.
+// A value of None means that the record was deleted.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub(crate) struct Change {
+	pub ts: u64,
+	pub new: bool,
+	pub val: Option<Value>,
+}
+
+// History is the state of the records of a table at a point in time,
+// which is reconstructed from the history entries of the records, which
+// are written along with the change feed entries of the table.
+//
+// Only the records which changed after the point in time are held, as
+// every other record still has the same value as it had at that time.
+// A record which changed after the point in time, but which has no
+// earlier history entries, must have been created after that time.
+// Otherwise its value at that time is unknown, as the record predates
+// the change feed, so the history can not be reconstructed, and an
+// error is returned.
+pub(crate) struct History {
+	changed: BTreeMap<Key, (Id, Option<Value>)>,
+}
+
+impl History {
+	// at reconstructs the records of a table, which changed after the
+	// given datetime, as they were at that datetime. If a record id is
+	// given, then only the history of that record is read.
+	//
+	// Each history entry holds the time at which its transaction was
+	// committed, so the changes made up to the datetime are exactly
+	// those with a commit time which is not after the datetime.
+	pub(crate) async fn at(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: Option<&Id>,
+		at: &Datetime,
+	) -> Result<Self, Error> {
+		// Fetch the change feed expiry of the table
+		let dbc = tx.get_db(ns, db).await?.changefeed;
+		let tbc = match tx.get_tb(ns, db, tb).await {
+			Ok(v) => v.changefeed,
+			Err(Error::TbNotFound {
+				..
+			}) => None,
+			Err(e) => return Err(e),
+		};
+		let expiry = match tbc.or(dbc) {
+			Some(v) => v.expiry,
+			None => {
+				return Err(Error::VersionNotRetained {
+					table: tb.to_owned(),
+				})
+			}
+		};
+		// Check that the history has not yet expired
+		let expired = || Error::VersionExpired {
+			table: tb.to_owned(),
+			version: at.to_string(),
+		};
+		let at = at.timestamp_millis().max(0) as u64;
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		let now = now.as_millis() as u64;
+		if at < now.saturating_sub(expiry.as_millis() as u64) {
+			return Err(expired());
+		}
+		// Only scan the history of the record, or of the table
+		let (beg, end) = match id {
+			Some(id) => (hs::id_prefix(ns, db, tb, id), hs::id_suffix(ns, db, tb, id)),
+			None => (hs::prefix(ns, db, tb), hs::suffix(ns, db, tb)),
+		};
+		// The latest value of each record up to the datetime
+		let mut before: BTreeMap<Key, Option<Value>> = BTreeMap::new();
+		// The records changed after the datetime, and whether they were created
+		let mut after: BTreeMap<Key, (Id, bool)> = BTreeMap::new();
+		let mut nxt: Option<Key> = None;
+		loop {
+			// Get records batch
+			let res = match nxt {
+				None => {
+					let min = beg.clone();
+					let max = end.clone();
+					tx.scan(min..max, 1000).await?
+				}
+				Some(ref mut beg) => {
+					beg.push(0x00);
+					let min = beg.clone();
+					let max = end.clone();
+					tx.scan(min..max, 1000).await?
+				}
+			};
+			// Get total results
+			let n = res.len();
+			// Exit when settled
+			if n == 0 {
+				break;
+			}
+			// Loop over results, in versionstamp order for each record
+			for (i, (k, v)) in res.into_iter().enumerate() {
+				// Ready the next
+				if n == i + 1 {
+					nxt = Some(k.clone());
+				}
+				let dec = hs::Hs::decode(&k)?;
+				let chg: Change = v.into();
+				let key = thing::new(ns, db, tb, &dec.id).encode()?;
+				match chg.ts <= at {
+					true => {
+						before.insert(key, chg.val);
+					}
+					false => {
+						after.entry(key).or_insert((dec.id, chg.new));
+					}
+				}
+			}
+		}
+		// Keep the records which changed after the datetime
+		let mut changed = BTreeMap::new();
+		for (key, (id, new)) in after {
+			let val = match before.remove(&key) {
+				Some(val) => val,
+				// The record did not exist yet
+				None if new => None,
+				// The value of the record is no longer known
+				None => return Err(expired()),
+			};
+			changed.insert(key, (id, val));
+		}
+		Ok(Self {
+			changed,
+		})
+	}
+
+	// changed returns the value of the record with the given key at the
+	// point in time, if the record has changed since then. The inner value
+	// is None if the record did not exist at the point in time.
+	pub(crate) fn changed(&self, key: &[u8]) -> Option<Option<&Value>> {
+		self.changed.get(key).map(|(_, v)| v.as_ref())
+	}
+
+	// into_records returns the records within a range of keys which changed
+	// after the point in time, and which existed at that time, in key order.
+	pub(crate) fn into_records(self, rng: Range<Key>) -> impl Iterator<Item = (Id, Value)> {
+		self.changed
+			.into_iter()
+			.filter(move |(k, _)| rng.contains(k))
+			.filter_map(|(_, (id, v))| v.map(|v| (id, v)))
+	}
+}
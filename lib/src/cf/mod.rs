@@ -0,0 +1,21 @@
+//! cf is a module for change feeds.
+//! Writes to any table, or any database, which has a CHANGEFEED defined are
+//! buffered in the transaction, and are then stored under versionstamped
+//! `cf` keys when the transaction commits. The change feeds can then be read
+//! in versionstamp order using the SHOW CHANGES statement. Change feed entries
+//! which are older than the CHANGEFEED expiry are removed by the garbage
+//! collector, which is run periodically by the datastore. The value of each
+//! changed record is also stored under a history `hs` key of the record, with
+//! the time of the commit, which is used to read tables as they were at a point
+//! in time, using a SELECT statement with a VERSION clause.
+mod gc;
+mod history;
+mod mutations;
+mod reader;
+mod writer;
+
+pub(crate) use self::gc::*;
+pub(crate) use self::history::{Change, History};
+pub(crate) use self::mutations::*;
+pub(crate) use self::reader::read;
+pub(crate) use self::writer::Writer;
@@ -0,0 +1,118 @@
+use crate::sql::array::Array;
+use crate::sql::object::Object;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use crate::vs::to_u128_be;
+use crate::vs::try_to_u64_be;
+use crate::vs::Versionstamp;
+use derive::Store;
+use serde::{Deserialize, Serialize};
+
+// Mutation is a single mutation to a table.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub enum TableMutation {
+	// Although the Value is supposed to contain a field "id" of Thing,
+	// we do include it in the first field for convenience.
+	Set(Thing, Value),
+	Del(Thing),
+	// A record which did not exist before it was set. This is appended
+	// after the other variants, so that stored change feeds keep their
+	// encoding, and it is shown as an update when reading the changes.
+	Create(Thing, Value),
+}
+
+// TableMutations is the list of mutations made to a single table
+// within a single transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct TableMutations(pub String, pub Vec<TableMutation>);
+
+impl TableMutations {
+	pub fn new(tb: String) -> Self {
+		Self(tb, Vec::new())
+	}
+}
+
+// DatabaseMutation is the list of table mutations made to a single
+// database within a single transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct DatabaseMutation(pub Vec<TableMutations>);
+
+// ChangeSet is the set of changes made to a database at a single versionstamp.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct ChangeSet(pub Versionstamp, pub DatabaseMutation);
+
+impl TableMutation {
+	pub fn into_value(self) -> Value {
+		let mut h = Object::default();
+		match self {
+			TableMutation::Set(_, v) | TableMutation::Create(_, v) => {
+				h.insert("update".to_owned(), v);
+			}
+			TableMutation::Del(t) => {
+				let mut o = Object::default();
+				o.insert("id".to_owned(), Value::Thing(t));
+				h.insert("delete".to_owned(), Value::Object(o));
+			}
+		};
+		Value::Object(h)
+	}
+}
+
+impl DatabaseMutation {
+	pub fn into_value(self) -> Value {
+		let mut changes = Vec::<Value>::new();
+		for tbs in self.0 {
+			for t in tbs.1 {
+				changes.push(t.into_value());
+			}
+		}
+		Value::Array(Array::from(changes))
+	}
+}
+
+impl ChangeSet {
+	pub fn into_value(self) -> Value {
+		let mut m = Object::default();
+		// Versionstamps are allocated from a per-database counter, so
+		// the trailing two bytes are always zero and we expose them as
+		// the same integer that is accepted by SHOW CHANGES ... SINCE.
+		let vs = match try_to_u64_be(self.0) {
+			Ok(v) => Value::from(v),
+			Err(_) => Value::from(to_u128_be(self.0)),
+		};
+		m.insert("versionstamp".to_owned(), vs);
+		m.insert("changes".to_owned(), self.1.into_value());
+		Value::Object(m)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Value;
+
+	#[test]
+	fn serialization() {
+		let cs = ChangeSet(
+			[0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+			DatabaseMutation(vec![TableMutations(
+				"mytb".to_string(),
+				vec![
+					TableMutation::Set(
+						Thing::from(("mytb".to_string(), "tobie".to_string())),
+						Value::from("v1"),
+					),
+					TableMutation::Del(Thing::from(("mytb".to_string(), "tobie".to_string()))),
+				],
+			)]),
+		);
+		let enc: Vec<u8> = cs.clone().into();
+		let dec = ChangeSet::from(enc);
+		assert_eq!(cs, dec);
+		let v = cs.into_value();
+		assert_eq!(
+			v.to_string(),
+			"{ changes: [{ update: 'v1' }, { delete: { id: mytb:tobie } }], versionstamp: 1 }"
+		);
+	}
+}
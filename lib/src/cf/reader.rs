@@ -0,0 +1,87 @@
+use crate::cf::{ChangeSet, DatabaseMutation, TableMutations};
+use crate::err::Error;
+use crate::key::cf;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::vs;
+
+// read reads the change feed for a specific database or a specific table,
+// starting from a specific versionstamp.
+//
+// The limit parameter is the maximum number of change sets to return.
+// If the limit is not specified, all the change sets are returned.
+//
+// The change sets are returned in ascending versionstamp order, with the
+// mutations of each table at a given versionstamp grouped into a single
+// change set.
+pub async fn read(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: Option<&str>,
+	start: Option<u64>,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet>, Error> {
+	// Compute the range of keys to scan
+	let beg = match start {
+		Some(v) => cf::ts_prefix(ns, db, vs::u64_to_versionstamp(v)),
+		None => cf::prefix(ns, db),
+	};
+	let end = cf::suffix(ns, db);
+	// Compute the maximum number of change sets
+	let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+	// Store the change sets
+	let mut out: Vec<ChangeSet> = vec![];
+	let mut nxt: Option<Key> = None;
+	// Start processing
+	'scan: loop {
+		// Get records batch
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				tx.scan(min..max, 1000).await?
+			}
+		};
+		// Get total results
+		let n = res.len();
+		// Exit when settled
+		if n == 0 {
+			break;
+		}
+		// Loop over results
+		for (i, (k, v)) in res.into_iter().enumerate() {
+			// Ready the next
+			if n == i + 1 {
+				nxt = Some(k.clone());
+			}
+			// Decode the change feed key
+			let dec = cf::Cf::decode(&k)?;
+			// Skip any other tables if a table was specified
+			if let Some(tb) = tb {
+				if dec.tb != tb {
+					continue;
+				}
+			}
+			// Decode the table mutations
+			let muts: TableMutations = v.into();
+			// Group the mutations by versionstamp
+			match out.last_mut() {
+				Some(ChangeSet(vs, DatabaseMutation(m))) if *vs == dec.vs => m.push(muts),
+				_ => {
+					if out.len() >= limit {
+						break 'scan;
+					}
+					out.push(ChangeSet(dec.vs, DatabaseMutation(vec![muts])));
+				}
+			}
+		}
+	}
+	Ok(out)
+}
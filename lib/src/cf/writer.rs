@@ -0,0 +1,97 @@
+use crate::cf::{TableMutation, TableMutations};
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+// ChangeKey identifies the table whose changes are being buffered.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ChangeKey {
+	pub ns: String,
+	pub db: String,
+	pub tb: String,
+}
+
+// Writer is a helper for writing table mutations within a transaction.
+// Mutations are buffered in memory until the transaction is committed,
+// at which point they are written under a single versionstamp per database.
+#[derive(Default)]
+pub struct Writer {
+	buf: BTreeMap<ChangeKey, TableMutations>,
+}
+
+impl Writer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Check if there are any buffered changes
+	pub fn is_empty(&self) -> bool {
+		self.buf.is_empty()
+	}
+
+	/// Record a change to a record in a table.
+	///
+	/// A [`Value::None`] value means the record was deleted.
+	pub fn update(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: Thing,
+		v: Cow<'_, Value>,
+		new: bool,
+	) {
+		let key = ChangeKey {
+			ns: ns.to_owned(),
+			db: db.to_owned(),
+			tb: tb.to_owned(),
+		};
+		let mutation = match (v.is_some(), new) {
+			(true, true) => TableMutation::Create(id, v.into_owned()),
+			(true, false) => TableMutation::Set(id, v.into_owned()),
+			(false, _) => TableMutation::Del(id),
+		};
+		self.buf.entry(key).or_insert_with(|| TableMutations::new(tb.to_owned())).1.push(mutation);
+	}
+
+	/// Take all of the buffered changes, leaving the writer empty.
+	///
+	/// Changes are returned ordered by namespace, database and table,
+	/// so that changes to the same database are adjacent.
+	pub fn drain(&mut self) -> Vec<(ChangeKey, TableMutations)> {
+		std::mem::take(&mut self.buf).into_iter().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn buffers_changes_per_table() {
+		let mut w = Writer::new();
+		assert!(w.is_empty());
+		let a = Thing::from(("a".to_string(), "1".to_string()));
+		let b = Thing::from(("b".to_string(), "1".to_string()));
+		w.update("ns", "db", "b", b.clone(), Cow::Owned(Value::from("x")), true);
+		w.update("ns", "db", "a", a.clone(), Cow::Owned(Value::from("y")), false);
+		w.update("ns", "db", "a", a.clone(), Cow::Owned(Value::None), false);
+		let out = w.drain();
+		assert!(w.is_empty());
+		assert_eq!(out.len(), 2);
+		assert_eq!(out[0].0.tb, "a");
+		assert_eq!(
+			out[0].1,
+			TableMutations(
+				"a".to_string(),
+				vec![TableMutation::Set(a.clone(), Value::from("y")), TableMutation::Del(a)]
+			)
+		);
+		assert_eq!(out[1].0.tb, "b");
+		assert_eq!(
+			out[1].1,
+			TableMutations("b".to_string(), vec![TableMutation::Create(b, Value::from("x"))])
+		);
+	}
+}
@@ -12,6 +12,11 @@ pub static MAX_COMPUTATION_DEPTH: Lazy<u8> = Lazy::new(|| {
 	option_env!("SURREAL_MAX_COMPUTATION_DEPTH").and_then(|s| s.parse::<u8>().ok()).unwrap_or(120)
 });
 
+/// Specifies the maximum number of terms a prefix or a fuzzy term of a full-text search can expand to.
+pub static MAX_TERM_EXPANSIONS: Lazy<usize> = Lazy::new(|| {
+	option_env!("SURREAL_MAX_TERM_EXPANSIONS").and_then(|s| s.parse::<usize>().ok()).unwrap_or(50)
+});
+
 /// Specifies the names of parameters which can not be specified in a query.
 pub const PROTECTED_PARAM_NAMES: &[&str] = &["auth", "scope", "token", "session"];
 
@@ -1,3 +1,5 @@
+use crate::sql::Role;
+
 /// The authentication level for a datastore execution context.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
 pub enum Level {
@@ -16,10 +18,10 @@ pub enum Auth {
 	No,
 	/// Specifies that the user is authenticated with full root permissions
 	Kv,
-	/// Specifies that the user is has full permissions for a particular Namespace
-	Ns(String),
-	/// Specifies that the user is has full permissions for a particular Namespace and Database
-	Db(String, String),
+	/// Specifies that the user is has the permissions of a role for a particular Namespace
+	Ns(String, Role),
+	/// Specifies that the user is has the permissions of a role for a particular Namespace and Database
+	Db(String, String, Role),
 	/// Specifies that the user is has full permissions for a particular Namespace, Database, and Scope
 	Sc(String, String, String),
 }
@@ -50,8 +52,8 @@ impl Auth {
 		match self {
 			Auth::No => true,
 			Auth::Sc(_, _, _) => true,
-			Auth::Db(_, _) => false,
-			Auth::Ns(_) => false,
+			Auth::Db(_, _, _) => false,
+			Auth::Ns(_, _) => false,
 			Auth::Kv => false,
 		}
 	}
@@ -60,9 +62,17 @@ impl Auth {
 		match self {
 			Auth::No => matches!(level, Level::No),
 			Auth::Sc(_, _, _) => matches!(level, Level::No | Level::Sc),
-			Auth::Db(_, _) => matches!(level, Level::No | Level::Sc | Level::Db),
-			Auth::Ns(_) => matches!(level, Level::No | Level::Sc | Level::Db | Level::Ns),
+			Auth::Db(_, _, _) => matches!(level, Level::No | Level::Sc | Level::Db),
+			Auth::Ns(_, _) => matches!(level, Level::No | Level::Sc | Level::Db | Level::Ns),
 			Auth::Kv => true,
 		}
 	}
+	/// Checks whether the current authentication grants the permissions of the role
+	pub fn check_role(&self, role: Role) -> bool {
+		match self {
+			Auth::Db(_, _, v) => *v >= role,
+			Auth::Ns(_, v) => *v >= role,
+			_ => true,
+		}
+	}
 }
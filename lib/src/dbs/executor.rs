@@ -236,8 +236,10 @@ impl<'a> Executor<'a> {
 						match &*opt.auth {
 							Auth::No => self.set_ns(&mut ctx, &mut opt, ns).await,
 							Auth::Kv => self.set_ns(&mut ctx, &mut opt, ns).await,
-							Auth::Ns(v) if v == ns => self.set_ns(&mut ctx, &mut opt, ns).await,
-							Auth::Db(v, _) if v == ns => self.set_ns(&mut ctx, &mut opt, ns).await,
+							Auth::Ns(v, _) if v == ns => self.set_ns(&mut ctx, &mut opt, ns).await,
+							Auth::Db(v, _, _) if v == ns => {
+								self.set_ns(&mut ctx, &mut opt, ns).await
+							}
 							_ => {
 								opt.set_ns(None);
 								return Err(Error::NsNotAllowed {
@@ -250,8 +252,10 @@ impl<'a> Executor<'a> {
 						match &*opt.auth {
 							Auth::No => self.set_db(&mut ctx, &mut opt, db).await,
 							Auth::Kv => self.set_db(&mut ctx, &mut opt, db).await,
-							Auth::Ns(_) => self.set_db(&mut ctx, &mut opt, db).await,
-							Auth::Db(_, v) if v == db => self.set_db(&mut ctx, &mut opt, db).await,
+							Auth::Ns(_, _) => self.set_db(&mut ctx, &mut opt, db).await,
+							Auth::Db(_, v, _) if v == db => {
+								self.set_db(&mut ctx, &mut opt, db).await
+							}
 							_ => {
 								opt.set_db(None);
 								return Err(Error::DbNotAllowed {
@@ -1,3 +1,4 @@
+use crate::cf::History;
 use crate::ctx::Context;
 use crate::dbs::Iterable;
 use crate::dbs::Iterator;
@@ -8,10 +9,12 @@ use crate::err::Error;
 use crate::idx::planner::plan::Plan;
 use crate::key::graph;
 use crate::key::thing;
+use crate::sql::datetime::Datetime;
 use crate::sql::dir::Dir;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use crate::sql::{Edges, Range, Table};
+use async_recursion::async_recursion;
 use std::ops::Bound;
 
 impl Iterable {
@@ -23,6 +26,10 @@ impl Iterable {
 		ite: &mut Iterator,
 	) -> Result<(), Error> {
 		if ctx.is_ok() {
+			// Historical reads are served from the retained table history
+			if let Some(v) = stm.version() {
+				return self.iterate_version(ctx, opt, stm, &v.0, ite).await;
+			}
 			match self {
 				Iterable::Value(v) => Self::iterate_value(ctx, opt, stm, v, ite).await,
 				Iterable::Thing(v) => Self::iterate_thing(ctx, opt, stm, v, ite).await?,
@@ -41,6 +48,181 @@ impl Iterable {
 		Ok(())
 	}
 
+	// Boxed, so that historical reads don't grow the stack of recursive queries
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	async fn iterate_version(
+		self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &Statement<'_>,
+		at: &Datetime,
+		ite: &mut Iterator,
+	) -> Result<(), Error> {
+		match self {
+			Iterable::Value(v) => Self::iterate_value(ctx, opt, stm, v, ite).await,
+			Iterable::Thing(v) => Self::iterate_thing_version(ctx, opt, stm, v, at, ite).await?,
+			Iterable::Table(v) => {
+				let v = Range {
+					tb: v.0,
+					beg: Bound::Unbounded,
+					end: Bound::Unbounded,
+				};
+				Self::iterate_range_version(ctx, opt, stm, v, at, ite).await?
+			}
+			Iterable::Range(v) => Self::iterate_range_version(ctx, opt, stm, v, at, ite).await?,
+			_ => {
+				return Err(Error::FeatureNotYetImplemented {
+					feature: "Selecting graph edges with a VERSION clause",
+				})
+			}
+		}
+		Ok(())
+	}
+
+	async fn iterate_thing_version(
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &Statement<'_>,
+		v: Thing,
+		at: &Datetime,
+		ite: &mut Iterator,
+	) -> Result<(), Error> {
+		// Clone transaction
+		let txn = ctx.try_clone_transaction()?;
+		// Check that the table exists
+		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+		// Fetch the history of the record
+		let his =
+			History::at(&mut *txn.lock().await, opt.ns(), opt.db(), &v.tb, Some(&v.id), at).await?;
+		// Fetch the data at the version
+		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id).encode()?;
+		let val = match his.changed(&key) {
+			// The record has changed since the version
+			Some(val) => val.cloned().unwrap_or(Value::None),
+			// The record is unchanged since the version
+			None => match txn.clone().lock().await.get(key).await? {
+				Some(v) => Value::from(v),
+				None => Value::None,
+			},
+		};
+		// Create a new operable value
+		let val = Operable::Value(val);
+		let mut child_ctx = Context::new(ctx);
+		child_ctx.add_thing(&v);
+		// Process the document record
+		ite.process(&child_ctx, opt, stm, val).await;
+		// Everything ok
+		Ok(())
+	}
+
+	async fn iterate_range_version(
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &Statement<'_>,
+		v: Range,
+		at: &Datetime,
+		ite: &mut Iterator,
+	) -> Result<(), Error> {
+		// Clone transaction
+		let txn = ctx.try_clone_transaction()?;
+		// Check that the table exists
+		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+		// Fetch the history of the table
+		let his = History::at(&mut *txn.lock().await, opt.ns(), opt.db(), &v.tb, None, at).await?;
+		// Prepare the range start key
+		let beg = match &v.beg {
+			Bound::Unbounded => thing::prefix(opt.ns(), opt.db(), &v.tb),
+			Bound::Included(id) => thing::new(opt.ns(), opt.db(), &v.tb, id).encode().unwrap(),
+			Bound::Excluded(id) => {
+				let mut key = thing::new(opt.ns(), opt.db(), &v.tb, id).encode().unwrap();
+				key.push(0x00);
+				key
+			}
+		};
+		// Prepare the range end key
+		let end = match &v.end {
+			Bound::Unbounded => thing::suffix(opt.ns(), opt.db(), &v.tb),
+			Bound::Excluded(id) => thing::new(opt.ns(), opt.db(), &v.tb, id).encode().unwrap(),
+			Bound::Included(id) => {
+				let mut key = thing::new(opt.ns(), opt.db(), &v.tb, id).encode().unwrap();
+				key.push(0x00);
+				key
+			}
+		};
+		// Prepare the next holder key
+		let mut nxt: Option<Vec<u8>> = None;
+		// Loop until no more keys
+		loop {
+			// Check if the context is finished
+			if ctx.is_done() {
+				break;
+			}
+			// Get the next 1000 key-value entries
+			let res = match nxt {
+				None => {
+					let min = beg.clone();
+					let max = end.clone();
+					txn.clone().lock().await.scan(min..max, 1000).await?
+				}
+				Some(ref mut beg) => {
+					beg.push(0x00);
+					let min = beg.clone();
+					let max = end.clone();
+					txn.clone().lock().await.scan(min..max, 1000).await?
+				}
+			};
+			// If there are key-value entries then fetch them
+			if !res.is_empty() {
+				// Get total results
+				let n = res.len();
+				// Loop over results
+				for (i, (k, v)) in res.into_iter().enumerate() {
+					// Check the context
+					if ctx.is_done() {
+						break;
+					}
+					// Ready the next
+					if n == i + 1 {
+						nxt = Some(k.clone());
+					}
+					// Skip records which have changed since the version
+					if his.changed(&k).is_some() {
+						continue;
+					}
+					// Parse the data from the store
+					let key: crate::key::thing::Thing = (&k).into();
+					let val: crate::sql::value::Value = (&v).into();
+					let rid = Thing::from((key.tb, key.id));
+					let mut ctx = Context::new(ctx);
+					ctx.add_thing(&rid);
+					// Create a new operable value
+					let val = Operable::Value(val);
+					// Process the record
+					ite.process(&ctx, opt, stm, val).await;
+				}
+				continue;
+			}
+			break;
+		}
+		// Process the records which have changed since the version
+		for (id, val) in his.into_records(beg..end) {
+			// Check the context
+			if ctx.is_done() {
+				break;
+			}
+			let rid = Thing::from((v.tb.clone(), id));
+			let mut ctx = Context::new(ctx);
+			ctx.add_thing(&rid);
+			// Create a new operable value
+			let val = Operable::Value(val);
+			// Process the record
+			ite.process(&ctx, opt, stm, val).await;
+		}
+		// Everything ok
+		Ok(())
+	}
+
 	async fn iterate_value(
 		ctx: &Context<'_>,
 		opt: &Options,
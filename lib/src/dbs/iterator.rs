@@ -12,12 +12,13 @@ use crate::sql::range::Range;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
-use crate::sql::Object;
+use crate::sql::{Object, Orders};
 use async_recursion::async_recursion;
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::mem;
+use std::sync::Arc;
 
 pub(crate) enum Iterable {
 	Value(Value),
@@ -54,6 +55,10 @@ pub(crate) struct Iterator {
 	error: Option<Error>,
 	// Iterator output results
 	results: Vec<Value>,
+	// Iterator first ordered results
+	top: Option<Top>,
+	// Iterator input is in output order
+	ordered: bool,
 	// Iterator input values
 	entries: Vec<Iterable>,
 }
@@ -85,6 +90,8 @@ impl Iterator {
 		self.setup_limit(&cancel_ctx, opt, stm).await?;
 		// Process the query START clause
 		self.setup_start(&cancel_ctx, opt, stm).await?;
+		// Process the query ORDER clause
+		self.setup_order(stm);
 		// Process any EXPLAIN clause
 		let explanation = self.output_explain(&cancel_ctx, opt, stm)?;
 		// Process prepared values
@@ -139,6 +146,25 @@ impl Iterator {
 		Ok(())
 	}
 
+	#[inline]
+	fn setup_order(&mut self, stm: &Statement<'_>) {
+		if let Some(orders) = stm.order() {
+			// Check if the records are iterated in the order of an index
+			self.ordered = !stm.parallel()
+				&& matches!(self.entries.as_slice(), [Iterable::Index(_, p)] if p.is_ordered());
+			// Otherwise keep only the first records if there is a limit
+			if !self.ordered
+				&& stm.group().is_none()
+				&& stm.split().is_none()
+				&& !orders.iter().any(|o| o.random)
+			{
+				if let Some(l) = self.limit {
+					self.top = Some(Top::new(orders.clone(), l + self.start.unwrap_or(0)));
+				}
+			}
+		}
+	}
+
 	#[inline]
 	async fn output_split(
 		&mut self,
@@ -267,33 +293,12 @@ impl Iterator {
 		_opt: &Options,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
-		if let Some(orders) = stm.order() {
+		if let Some(top) = self.top.take() {
+			// The first results are already sorted
+			self.results = top.into_sorted_vec();
+		} else if let Some(orders) = stm.order() {
 			// Sort the full result set
-			self.results.sort_by(|a, b| {
-				// Loop over each order clause
-				for order in orders.iter() {
-					// Reverse the ordering if DESC
-					let o = match order.random {
-						true => {
-							let a = rand::random::<f64>();
-							let b = rand::random::<f64>();
-							a.partial_cmp(&b)
-						}
-						false => match order.direction {
-							true => a.compare(b, order, order.collate, order.numeric),
-							false => b.compare(a, order, order.collate, order.numeric),
-						},
-					};
-					//
-					match o {
-						Some(Ordering::Greater) => return Ordering::Greater,
-						Some(Ordering::Equal) => continue,
-						Some(Ordering::Less) => return Ordering::Less,
-						None => continue,
-					}
-				}
-				Ordering::Equal
-			})
+			self.results.sort_by(|a, b| compare(orders, a, b))
 		}
 		Ok(())
 	}
@@ -427,8 +432,9 @@ impl Iterator {
 	) -> Result<(), Error> {
 		// Prevent deep recursion
 		let opt = &opt.dive(4)?;
-		// Check if iterating in parallel
-		match stm.parallel() {
+		// Check if iterating in parallel, as
+		// historical reads are always sequential
+		match stm.parallel() && stm.version().is_none() {
 			// Run statements sequentially
 			false => {
 				// Process all prepared values
@@ -539,10 +545,13 @@ impl Iterator {
 				self.run.cancel();
 				return;
 			}
-			Ok(v) => self.results.push(v),
+			Ok(v) => match &mut self.top {
+				Some(top) => top.push(v),
+				None => self.results.push(v),
+			},
 		}
 		// Check if we can exit
-		if stm.group().is_none() && stm.order().is_none() {
+		if stm.group().is_none() && (stm.order().is_none() || self.ordered) {
 			if let Some(l) = self.limit {
 				if let Some(s) = self.start {
 					if self.results.len() == l + s {
@@ -555,3 +564,95 @@ impl Iterator {
 		}
 	}
 }
+
+/// Compare two results using the ORDER BY clause
+fn compare(orders: &Orders, a: &Value, b: &Value) -> Ordering {
+	// Loop over each order clause
+	for order in orders.iter() {
+		// Reverse the ordering if DESC
+		let o = match order.random {
+			true => {
+				let a = rand::random::<f64>();
+				let b = rand::random::<f64>();
+				a.partial_cmp(&b)
+			}
+			false => match order.direction {
+				true => a.compare(b, order, order.collate, order.numeric),
+				false => b.compare(a, order, order.collate, order.numeric),
+			},
+		};
+		//
+		match o {
+			Some(Ordering::Greater) => return Ordering::Greater,
+			Some(Ordering::Equal) => continue,
+			Some(Ordering::Less) => return Ordering::Less,
+			None => continue,
+		}
+	}
+	Ordering::Equal
+}
+
+/// Keeps the first results of an ORDER BY clause in a bounded heap,
+/// so that the full result set does not have to be kept and sorted.
+struct Top {
+	orders: Arc<Orders>,
+	heap: BinaryHeap<TopValue>,
+	size: usize,
+	count: usize,
+}
+
+impl Top {
+	fn new(orders: Orders, size: usize) -> Self {
+		Self {
+			orders: Arc::new(orders),
+			heap: BinaryHeap::with_capacity(size + 1),
+			size,
+			count: 0,
+		}
+	}
+
+	fn push(&mut self, v: Value) {
+		self.heap.push(TopValue {
+			orders: self.orders.clone(),
+			n: self.count,
+			v,
+		});
+		self.count += 1;
+		// Remove the last result
+		if self.heap.len() > self.size {
+			self.heap.pop();
+		}
+	}
+
+	fn into_sorted_vec(self) -> Vec<Value> {
+		self.heap.into_sorted_vec().into_iter().map(|t| t.v).collect()
+	}
+}
+
+struct TopValue {
+	orders: Arc<Orders>,
+	// The position of the result, which keeps
+	// equal results in their original order
+	n: usize,
+	v: Value,
+}
+
+impl Ord for TopValue {
+	fn cmp(&self, other: &Self) -> Ordering {
+		compare(&self.orders, &self.v, &other.v).then(self.n.cmp(&other.n))
+	}
+}
+
+impl PartialOrd for TopValue {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for TopValue {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for TopValue {}
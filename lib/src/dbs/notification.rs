@@ -1,9 +1,10 @@
 use crate::sql::Value;
+use derive::Store;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Store)]
 pub struct Notification {
 	pub id: Uuid,
 	pub action: Action,
@@ -3,6 +3,7 @@ use crate::dbs::Auth;
 use crate::dbs::Level;
 use crate::dbs::Notification;
 use crate::err::Error;
+use crate::sql::Role;
 use channel::Sender;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -361,6 +362,14 @@ impl Options {
 		Ok(())
 	}
 
+	/// Check whether the role of the authentication is ok
+	pub fn check_role(&self, role: Role) -> Result<(), Error> {
+		if !self.auth.check_role(role) {
+			return Err(Error::QueryPermissions);
+		}
+		Ok(())
+	}
+
 	/// Check whether the necessary NS / DB options have been set
 	pub fn needs(&self, level: Level) -> Result<(), Error> {
 		if self.ns.is_none() && matches!(level, Level::Ns | Level::Db) {
@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::dbs::Auth;
 use crate::sql::value::Value;
+use crate::sql::Role;
 use std::sync::Arc;
 
 /// Specifies the current session information when processing a query.
@@ -43,7 +44,7 @@ impl Session {
 	{
 		Session {
 			ns: Some(ns.clone().into()),
-			au: Arc::new(Auth::Ns(ns.into())),
+			au: Arc::new(Auth::Ns(ns.into(), Role::Owner)),
 			..Session::default()
 		}
 	}
@@ -55,7 +56,7 @@ impl Session {
 		Session {
 			ns: Some(ns.clone().into()),
 			db: Some(db.clone().into()),
-			au: Arc::new(Auth::Db(ns.into(), db.into())),
+			au: Arc::new(Auth::Db(ns.into(), db.into(), Role::Owner)),
 			..Session::default()
 		}
 	}
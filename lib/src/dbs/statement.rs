@@ -16,6 +16,7 @@ use crate::sql::statements::relate::RelateStatement;
 use crate::sql::statements::select::SelectStatement;
 use crate::sql::statements::show::ShowStatement;
 use crate::sql::statements::update::UpdateStatement;
+use crate::sql::version::Version;
 use std::fmt;
 
 #[derive(Clone, Debug)]
@@ -195,6 +196,14 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	/// Returns any VERSION clause if specified
+	#[inline]
+	pub fn version(&self) -> Option<&Version> {
+		match self {
+			Statement::Select(v) => v.version.as_ref(),
+			_ => None,
+		}
+	}
 	/// Returns any PARALLEL clause if specified
 	#[inline]
 	#[allow(dead_code)]
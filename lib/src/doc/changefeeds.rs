@@ -0,0 +1,43 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::doc::Document;
+use crate::err::Error;
+
+impl<'a> Document<'a> {
+	pub async fn changefeeds(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		_stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Clone transaction
+		let txn = ctx.try_clone_transaction()?;
+		// Get the table for the record
+		let tb = self.tb(opt, &txn).await?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Get the database for the record
+		let db = run.add_and_cache_db(opt.ns(), opt.db(), opt.strict).await?;
+		// Check if changefeeds are enabled
+		if db.changefeed.is_some() || tb.changefeed.is_some() {
+			// Get the record id
+			let rid = self.id.as_ref().unwrap();
+			// Create the changefeed entry
+			run.record_change(
+				opt.ns(),
+				opt.db(),
+				&tb.name,
+				rid,
+				self.current.clone(),
+				self.is_new(),
+			);
+		}
+		// Carry on
+		Ok(())
+	}
+}
@@ -4,9 +4,10 @@ use crate::dbs::Statement;
 use crate::doc::Document;
 use crate::err::Error;
 use crate::idx::ft::FtIndex;
+use crate::idx::mtree::MTree;
 use crate::idx::IndexKeyBase;
 use crate::sql::array::Array;
-use crate::sql::index::Index;
+use crate::sql::index::{Index, MTreeParams, Weights};
 use crate::sql::scoring::Scoring;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Ident, Thing, Value};
@@ -60,7 +61,9 @@ impl<'a> Document<'a> {
 						sc,
 						hl,
 						order,
-					} => ic.index_full_text(&mut run, az, *order, sc, *hl).await?,
+						wt,
+					} => ic.index_full_text(&mut run, az, *order, sc, *hl, wt.as_ref()).await?,
+					Index::MTree(p) => ic.index_mtree(&mut run, p).await?,
 				};
 			}
 		}
@@ -184,10 +187,11 @@ impl<'a> IndexOperation<'a> {
 		order: u32,
 		scoring: &Scoring,
 		hl: bool,
+		wt: Option<&Weights>,
 	) -> Result<(), Error> {
 		let ikb = IndexKeyBase::new(self.opt, self.ix);
 		let az = run.get_az(self.opt.ns(), self.opt.db(), az.as_str()).await?;
-		let mut ft = FtIndex::new(run, az, ikb, order, scoring, hl).await?;
+		let mut ft = FtIndex::new(run, az, ikb, order, scoring, hl, wt).await?;
 		if let Some(n) = &self.n {
 			// TODO: Apply the analyzer
 			ft.index_document(run, self.rid, n).await
@@ -195,4 +199,18 @@ impl<'a> IndexOperation<'a> {
 			ft.remove_document(run, self.rid).await
 		}
 	}
+
+	async fn index_mtree(&self, run: &mut kvs::Transaction, p: &MTreeParams) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut mt = MTree::new(run, ikb, p).await?;
+		// Remove the old vector of the single column
+		if let Some(v) = self.o.as_ref().and_then(|o| o.first()) {
+			mt.remove_document(run, self.rid, v).await?;
+		}
+		// Index the new vector of the single column
+		if let Some(v) = self.n.as_ref().and_then(|n| n.first()) {
+			mt.index_document(run, self.rid, v).await?;
+		}
+		mt.finish(run).await
+	}
 }
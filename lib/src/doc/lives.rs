@@ -22,57 +22,52 @@ impl<'a> Document<'a> {
 		let txn = ctx.try_clone_transaction()?;
 		// Get the record id
 		let rid = self.id.as_ref().unwrap();
-		// Check if we can send notifications
-		if let Some(chn) = &opt.sender {
-			// Clone the sending channel
-			let chn = chn.clone();
-			// Loop through all index statements
-			for lv in self.lv(opt, &txn).await?.iter() {
-				// Create a new statement
-				let lq = Statement::from(lv);
-				// Check LIVE SELECT where condition
-				if self.check(ctx, opt, &lq).await.is_err() {
-					continue;
+		// Get the id of this node
+		let nd = opt.id()?;
+		// Loop through all live query statements
+		for lv in self.lv(opt, &txn).await?.iter() {
+			// Check if the live query belongs to this node
+			let local = nd == lv.node.0;
+			// Check if we can send notifications
+			if local && opt.sender.is_none() {
+				continue;
+			}
+			// Create a new statement
+			let lq = Statement::from(lv);
+			// Check LIVE SELECT where condition
+			if self.check(ctx, opt, &lq).await.is_err() {
+				continue;
+			}
+			// Check what type of data change this is
+			let not = if stm.is_delete() {
+				// Create a DELETE notification
+				let thing = (*rid).clone();
+				Notification {
+					id: lv.id.0,
+					action: Action::Delete,
+					result: Value::Thing(thing),
+				}
+			} else if self.is_new() {
+				// Create a CREATE notification
+				Notification {
+					id: lv.id.0,
+					action: Action::Create,
+					result: self.pluck(ctx, opt, &lq).await?,
+				}
+			} else {
+				// Create a UPDATE notification
+				Notification {
+					id: lv.id.0,
+					action: Action::Update,
+					result: self.pluck(ctx, opt, &lq).await?,
 				}
-				// Check what type of data change this is
-				if stm.is_delete() {
-					// Send a DELETE notification
-					if opt.id()? == lv.node.0 {
-						let thing = (*rid).clone();
-						chn.send(Notification {
-							id: lv.id.0,
-							action: Action::Delete,
-							result: Value::Thing(thing),
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
-					}
-				} else if self.is_new() {
-					// Send a CREATE notification
-					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.0,
-							action: Action::Create,
-							result: self.pluck(ctx, opt, &lq).await?,
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
-					}
-				} else {
-					// Send a UPDATE notification
-					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.0,
-							action: Action::Update,
-							result: self.pluck(ctx, opt, &lq).await?,
-						})
-						.await?;
-					} else {
-						// TODO: Send to storage
-					}
-				};
+			};
+			// Send the notification
+			match (local, &opt.sender) {
+				// Send the notification to this node
+				(true, Some(chn)) => chn.send(not).await?,
+				// Queue the notification for the node of the live query
+				_ => txn.lock().await.set_nq(lv.node.0, not).await?,
 			}
 		}
 		// Carry on
@@ -58,6 +58,10 @@ pub enum Error {
 	#[error("Record id or key is too large")]
 	TxKeyTooLarge,
 
+	/// The versionstamp stored in the datastore is not valid
+	#[error("The stored versionstamp is invalid")]
+	InvalidVersionstamp,
+
 	/// The value exceeds a limit set by the KV store
 	#[error("Record or value is too large")]
 	TxValueTooLarge,
@@ -146,6 +150,33 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The table does not retain the history needed to read it at a version
+	#[error("Unable to select from table '{table}' with a VERSION clause, as the table does not retain its history. Define a CHANGEFEED on the table or database to retain its history")]
+	VersionNotRetained {
+		table: String,
+	},
+
+	/// The version is older than the history which is retained for the table
+	#[error("Unable to select from table '{table}' at {version}, as it is older than the retained history of the table")]
+	VersionExpired {
+		table: String,
+		version: String,
+	},
+
+	/// A backup can only be restored into an empty datastore
+	#[error("Unable to restore the backup, as the datastore is not empty")]
+	RestoreNotEmpty,
+
+	/// The datastore can not be written to while a backup is being restored
+	#[error("Unable to write to the datastore, as a backup is being restored")]
+	RestoreInProgress,
+
+	/// The backup which is being restored is not valid
+	#[error("Unable to restore the backup, as it is not valid: {message}")]
+	InvalidBackup {
+		message: String,
+	},
+
 	/// There was an error with the provided JavaScript code
 	#[error("Problem with embedded script function. {message}")]
 	InvalidScript {
@@ -212,6 +243,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested root login does not exist
+	#[error("The root login '{value}' does not exist")]
+	KlNotFound {
+		value: String,
+	},
+
 	/// The requested namespace login does not exist
 	#[error("The namespace login '{value}' does not exist")]
 	NlNotFound {
@@ -469,6 +506,29 @@ pub enum Error {
 		value: String,
 	},
 
+	/// Phrases are matched using the term offsets, which are only stored with HIGHLIGHTS
+	#[error("A phrase can only be matched by a full-text index defined with HIGHLIGHTS")]
+	PhraseWithoutHighlights,
+
+	/// The query planner did not find a vector index able to support the KNN <|k|> operator on a given expression
+	#[error("There was no suitable vector index supporting the expression '{value}'")]
+	NoIndexFoundForKnn {
+		value: String,
+	},
+
+	/// The value is not a vector of numbers
+	#[error("Incorrect vector value. Expected an array of numbers, but found '{current}'")]
+	InvalidVectorValue {
+		current: String,
+	},
+
+	/// The vector does not have the dimension of the vector index
+	#[error("Incorrect vector dimension ({current}). Expected a vector of {expected} dimension.")]
+	InvalidVectorDimension {
+		current: usize,
+		expected: usize,
+	},
+
 	/// Represents an error when analyzing a value
 	#[error("A value can't be analyzed: {0}")]
 	AnalyzerError(String),
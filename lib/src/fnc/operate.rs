@@ -177,6 +177,16 @@ pub(crate) async fn matches(ctx: &Context<'_>, e: &Expression) -> Result<Value,
 	Ok(Value::Bool(false))
 }
 
+pub(crate) async fn knn(ctx: &Context<'_>, e: &Expression) -> Result<Value, Error> {
+	if let Some(thg) = ctx.thing() {
+		if let Some(exe) = ctx.get_query_executor(&thg.tb) {
+			// Check the nearest neighbours
+			return exe.knn(thg, e);
+		}
+	}
+	Ok(Value::Bool(false))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,7 +1,26 @@
 use crate::ctx::Context;
 use crate::err::Error;
+use crate::idx::ft::analyzer::Analyzers;
+use crate::sql::paths::{DB, NS};
 use crate::sql::Value;
 
+pub async fn analyze(ctx: &Context<'_>, (az, val): (String, Value)) -> Result<Value, Error> {
+	if let Some(session) = ctx.value("session") {
+		let ns = match session.pick(NS.as_ref()) {
+			Value::Strand(ns) => ns.0,
+			_ => return Err(Error::NsEmpty),
+		};
+		let db = match session.pick(DB.as_ref()) {
+			Value::Strand(db) => db.0,
+			_ => return Err(Error::DbEmpty),
+		};
+		let txn = ctx.try_clone_transaction()?;
+		let mut run = txn.lock().await;
+		return Analyzers::analyze(&mut run, &ns, &db, &az, val).await;
+	}
+	Ok(Value::None)
+}
+
 pub async fn score(ctx: &Context<'_>, (match_ref,): (Value,)) -> Result<Value, Error> {
 	if let Some(thg) = ctx.thing() {
 		if let Some(exe) = ctx.get_query_executor(&thg.tb) {
@@ -1,6 +1,9 @@
 pub mod base;
 pub mod clear;
+mod jwks;
 pub mod parse;
+pub mod refresh;
+pub mod revoke;
 pub mod signin;
 pub mod signup;
 pub mod token;
@@ -0,0 +1,260 @@
+use crate::cnf::SERVER_NAME;
+use crate::dbs::Auth;
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::token::{Claims, HEADER};
+use crate::iam::verify::{DUD, KEY};
+use crate::kvs::Datastore;
+use crate::sql::Value;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Validation};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Exchanges a scope token for a new token, when the scope has a refresh
+/// period, and the token expired less than the refresh period ago. The
+/// exchanged token is revoked, so that each token is only refreshed once.
+pub async fn refresh(
+	kvs: &Datastore,
+	session: &mut Session,
+	token: String,
+) -> Result<Option<String>, Error> {
+	// Decode the token without verifying
+	let data = decode::<Claims>(&token, &KEY, &DUD)?;
+	// Only scope tokens can be refreshed
+	match &data.claims {
+		Claims {
+			ns: Some(ns),
+			db: Some(db),
+			sc: Some(sc),
+			tk: None,
+			id: Some(id),
+			jti: Some(jti),
+			exp: Some(exp),
+			..
+		} => {
+			// Log the decoded authentication claims
+			trace!("Refreshing token for scope `{}`", sc);
+			// Create a new writeable transaction
+			let mut tx = kvs.transaction(true, false).await?;
+			// Get the scope
+			let sv = tx.get_sc(ns, db, sc).await?;
+			// Check that the scope allows refreshing
+			let refresh = match sv.refresh {
+				Some(v) => v,
+				None => {
+					tx.cancel().await?;
+					return Err(Error::InvalidAuth);
+				}
+			};
+			// Verify the token, which may have expired
+			let key = DecodingKey::from_secret(sv.code.as_ref());
+			let mut validation = Validation::new(jsonwebtoken::Algorithm::HS512);
+			validation.validate_exp = false;
+			if let Err(e) = decode::<Claims>(&token, &key, &validation) {
+				tx.cancel().await?;
+				return Err(e.into());
+			}
+			// Check that the token is within the refresh period
+			let until = exp.saturating_add(refresh.secs() as i64);
+			if until < Utc::now().timestamp() {
+				trace!("The refresh period of the authentication token has passed");
+				tx.cancel().await?;
+				return Err(Error::InvalidAuth);
+			}
+			// Check that the token, or its subject, has not been revoked
+			if super::revoke::is_revoked(&mut tx, &data.claims).await? {
+				trace!("The authentication token has been revoked");
+				tx.cancel().await?;
+				return Err(Error::InvalidAuth);
+			}
+			// Revoke the exchanged token
+			super::revoke::add(&mut tx, &data.claims, jti, until).await?;
+			tx.commit().await?;
+			// Parse the record id
+			let rid = crate::sql::thing(id)?;
+			// Create the authentication key
+			let key = EncodingKey::from_secret(sv.code.as_ref());
+			// Create the authentication claim
+			let val = Claims {
+				iss: Some(SERVER_NAME.to_owned()),
+				jti: Some(Uuid::new_v4().to_string()),
+				iat: Some(Utc::now().timestamp()),
+				nbf: Some(Utc::now().timestamp()),
+				exp: Some(
+					match sv.session {
+						Some(v) => Utc::now() + Duration::from_std(v.0).unwrap(),
+						_ => Utc::now() + Duration::hours(1),
+					}
+					.timestamp(),
+				),
+				ns: Some(ns.to_owned()),
+				db: Some(db.to_owned()),
+				sc: Some(sc.to_owned()),
+				id: Some(id.to_owned()),
+				..Claims::default()
+			};
+			// Create the authentication token
+			let enc = encode(&HEADER, &val, &key);
+			// Log the success
+			debug!("Refreshed token for scope `{}`", sc);
+			// Set the authentication on the session
+			session.tk = Some(val.into());
+			session.ns = Some(ns.to_owned());
+			session.db = Some(db.to_owned());
+			session.sc = Some(sc.to_owned());
+			session.sd = Some(Value::from(rid));
+			session.au = Arc::new(Auth::Sc(ns.to_owned(), db.to_owned(), sc.to_owned()));
+			// Check the authentication token
+			match enc {
+				// The auth token was created successfully
+				Ok(tk) => Ok(Some(tk)),
+				// There was an error creating the token
+				_ => Err(Error::InvalidAuth),
+			}
+		}
+		// This is not a refreshable token
+		_ => Err(Error::InvalidAuth),
+	}
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+
+	use super::*;
+	use crate::iam::revoke::{revoke, revoke_all, revoke_record};
+	use crate::iam::signin::signin;
+	use crate::iam::verify::token;
+	use crate::sql::Object;
+
+	async fn setup(opts: &str) -> Datastore {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		let sql = format!(
+			"
+			DEFINE SCOPE user SESSION 1h {opts} SIGNIN (SELECT * FROM user WHERE name = $user);
+			CREATE user:test SET name = 'test';
+			"
+		);
+		for res in dbs.execute(&sql, &ses, None).await.unwrap() {
+			assert!(res.result.is_ok());
+		}
+		dbs
+	}
+
+	async fn signin_to_scope(dbs: &Datastore) -> String {
+		let mut vars = Object::default();
+		vars.insert("NS".to_owned(), "test".into());
+		vars.insert("DB".to_owned(), "test".into());
+		vars.insert("SC".to_owned(), "user".into());
+		vars.insert("user".to_owned(), "test".into());
+		let mut ses = Session::default();
+		signin(dbs, &None, &mut ses, vars).await.unwrap().unwrap()
+	}
+
+	#[tokio::test]
+	async fn refresh_scope_token() {
+		let dbs = setup("REFRESH 1w").await;
+		let tk = signin_to_scope(&dbs).await;
+		// The token is exchanged for a new token
+		let mut ses = Session::default();
+		let new = refresh(&dbs, &mut ses, tk.clone()).await.unwrap().unwrap();
+		assert_ne!(new, tk);
+		assert_eq!(ses.au.as_ref(), &Auth::Sc("test".into(), "test".into(), "user".into()));
+		assert_eq!(ses.sd, Some(crate::sql::thing("user:test").unwrap().into()));
+		// The exchanged token is revoked
+		let mut ses = Session::default();
+		assert!(matches!(refresh(&dbs, &mut ses, tk.clone()).await, Err(Error::InvalidAuth)));
+		assert!(matches!(token(&dbs, &mut ses, tk).await, Err(Error::InvalidAuth)));
+		// The new token is valid until it is revoked
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, new.clone()).await.is_ok());
+		revoke(&dbs, &ses).await.unwrap();
+		let mut ses = Session::default();
+		assert!(matches!(token(&dbs, &mut ses, new.clone()).await, Err(Error::InvalidAuth)));
+		assert!(matches!(refresh(&dbs, &mut ses, new).await, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn revoke_scope_tokens_on_other_devices() {
+		let dbs = setup("REFRESH 1w").await;
+		// The user signs in on two devices
+		let one = signin_to_scope(&dbs).await;
+		let two = signin_to_scope(&dbs).await;
+		// The user logs out of all devices from the first device
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, one.clone()).await.is_ok());
+		revoke_all(&dbs, &ses).await.unwrap();
+		// The token of the second device can no longer be used or refreshed
+		let mut ses = Session::default();
+		assert!(matches!(token(&dbs, &mut ses, two.clone()).await, Err(Error::InvalidAuth)));
+		assert!(matches!(refresh(&dbs, &mut ses, two).await, Err(Error::InvalidAuth)));
+		// Nor can the token of the first device
+		assert!(matches!(token(&dbs, &mut ses, one).await, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn revoke_scope_tokens_of_record() {
+		let dbs = setup("REFRESH 1w").await;
+		let tk = signin_to_scope(&dbs).await;
+		let id = crate::sql::thing("user:test").unwrap();
+		// A scope user can not revoke the tokens of a record
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, tk.clone()).await.is_ok());
+		let res = revoke_record(&dbs, &ses, "user", &id).await;
+		assert!(matches!(res, Err(Error::QueryPermissions)));
+		// An owner of the database can revoke the tokens of a record
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		revoke_record(&dbs, &ses, "user", &id).await.unwrap();
+		let mut ses = Session::default();
+		assert!(matches!(token(&dbs, &mut ses, tk.clone()).await, Err(Error::InvalidAuth)));
+		assert!(matches!(refresh(&dbs, &mut ses, tk).await, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn refresh_scope_token_without_refresh_period() {
+		let dbs = setup("").await;
+		let tk = signin_to_scope(&dbs).await;
+		let mut ses = Session::default();
+		assert!(matches!(refresh(&dbs, &mut ses, tk).await, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn refresh_expired_scope_token() {
+		let dbs = setup("REFRESH 1d").await;
+		let mut tx = dbs.transaction(false, false).await.unwrap();
+		let sv = tx.get_sc("test", "test", "user").await.unwrap();
+		let sign = |exp: Duration| {
+			let val = Claims {
+				jti: Some(Uuid::new_v4().to_string()),
+				exp: Some((Utc::now() - exp).timestamp()),
+				ns: Some("test".to_owned()),
+				db: Some("test".to_owned()),
+				sc: Some("user".to_owned()),
+				id: Some("user:test".to_owned()),
+				..Claims::default()
+			};
+			encode(&HEADER, &val, &EncodingKey::from_secret(sv.code.as_ref())).unwrap()
+		};
+		// An expired token is refreshed within the refresh period
+		let mut ses = Session::default();
+		assert!(refresh(&dbs, &mut ses, sign(Duration::hours(1))).await.is_ok());
+		// An expired token is not refreshed after the refresh period
+		let mut ses = Session::default();
+		let res = refresh(&dbs, &mut ses, sign(Duration::days(2))).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+		// A token signed with another key is not refreshed
+		let val = Claims {
+			jti: Some(Uuid::new_v4().to_string()),
+			exp: Some(Utc::now().timestamp()),
+			ns: Some("test".to_owned()),
+			db: Some("test".to_owned()),
+			sc: Some("user".to_owned()),
+			id: Some("user:test".to_owned()),
+			..Claims::default()
+		};
+		let tk = encode(&HEADER, &val, &EncodingKey::from_secret(b"invalid")).unwrap();
+		let res = refresh(&dbs, &mut ses, tk).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+	}
+}
@@ -0,0 +1,334 @@
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::token::Claims;
+use crate::kvs::Datastore;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::sql::Object;
+use crate::sql::Part;
+use crate::sql::Role;
+use crate::sql::Thing;
+use crate::sql::Value;
+use chrono::Utc;
+
+/// The number of revoked tokens which are checked in each transaction
+const BATCH: u32 = 1000;
+
+/// The number of seconds for which a namespace or database login token is valid
+const LOGIN_EXPIRY: i64 = 3600;
+
+/// Revokes the token which the session was authenticated with, so that
+/// the token can no longer be used to authenticate, or to refresh the
+/// session, even though it has not yet expired.
+pub async fn revoke(kvs: &Datastore, session: &Session) -> Result<(), Error> {
+	// Check if the session was authenticated with a token
+	let claims = match claims(session) {
+		Some(v) => v,
+		None => return Ok(()),
+	};
+	// Tokens without an id can not be revoked
+	let jti = match &claims.jti {
+		Some(v) => v,
+		None => return Ok(()),
+	};
+	// Create a new writeable transaction
+	let mut tx = kvs.transaction(true, false).await?;
+	// Tokens without an expiry are revoked forever, and scope
+	// tokens can be refreshed after they have expired
+	let until = match claims.exp {
+		Some(exp) => exp.saturating_add(refresh(&mut tx, &claims).await?),
+		None => i64::MAX,
+	};
+	// Store the revoked token
+	add(&mut tx, &claims, jti, until).await?;
+	tx.commit().await
+}
+
+/// Revokes every token which was issued, up until now, to the record or
+/// login which the session was authenticated as, so that a user can log
+/// out of all of their devices, including any compromised devices.
+pub async fn revoke_all(kvs: &Datastore, session: &Session) -> Result<(), Error> {
+	// Check if the session was authenticated with a token
+	let claims = match claims(session) {
+		Some(v) => v,
+		None => return Ok(()),
+	};
+	// Tokens without a subject can only be revoked one by one
+	let id = match &claims.id {
+		Some(v) => v,
+		None => return Ok(()),
+	};
+	// Create a new writeable transaction
+	let mut tx = kvs.transaction(true, false).await?;
+	// Store the revoked subject
+	subject(&mut tx, &claims, id).await?;
+	tx.commit().await
+}
+
+/// Revokes every token which was issued, up until now, to a record of a
+/// scope in the selected database, so that an owner of the database can
+/// log a user out of all of their devices.
+pub async fn revoke_record(
+	kvs: &Datastore,
+	session: &Session,
+	sc: &str,
+	id: &Thing,
+) -> Result<(), Error> {
+	// Check the permissions
+	if !session.au.is_db() || !session.au.check_role(Role::Owner) {
+		return Err(Error::QueryPermissions);
+	}
+	// Check the selected namespace and database
+	let ns = session.ns.as_ref().ok_or(Error::NsEmpty)?;
+	let db = session.db.as_ref().ok_or(Error::DbEmpty)?;
+	// The claims of the tokens which are revoked
+	let claims = Claims {
+		ns: Some(ns.to_owned()),
+		db: Some(db.to_owned()),
+		sc: Some(sc.to_owned()),
+		..Claims::default()
+	};
+	// Create a new writeable transaction
+	let mut tx = kvs.transaction(true, false).await?;
+	// Store the revoked subject
+	subject(&mut tx, &claims, &id.to_raw()).await?;
+	tx.commit().await
+}
+
+/// Returns the claims of the token which the session was authenticated with
+fn claims(session: &Session) -> Option<Claims> {
+	let tk = session.tk.as_ref()?;
+	// Picks the first of the names of a claim which is set
+	let pick = |names: &[&str]| {
+		names.iter().map(|v| tk.pick(&[Part::from(*v)])).find(|v| !v.is_none_or_null())
+	};
+	let str = |names: &[&str]| pick(names).map(|v| v.to_raw_string());
+	let int = |names: &[&str]| match pick(names) {
+		Some(Value::Number(v)) => Some(v.as_int()),
+		_ => None,
+	};
+	Some(Claims {
+		iat: int(&["iat"]),
+		exp: int(&["exp"]),
+		jti: str(&["jti"]),
+		ns: str(&["NS", "ns", "https://surrealdb.com/ns", "https://surrealdb.com/namespace"]),
+		db: str(&["DB", "db", "https://surrealdb.com/db", "https://surrealdb.com/database"]),
+		sc: str(&["SC", "sc", "https://surrealdb.com/sc", "https://surrealdb.com/scope"]),
+		tk: str(&["TK", "tk", "https://surrealdb.com/tk", "https://surrealdb.com/token"]),
+		id: str(&["ID", "id", "https://surrealdb.com/id", "https://surrealdb.com/record"]),
+		..Claims::default()
+	})
+}
+
+/// Returns the namespace, database, scope and token which a token was
+/// issued for, so that the revocations of tokens from different tenants,
+/// or from different issuers, are kept apart.
+fn tenant(claims: &Claims) -> [&str; 4] {
+	[
+		claims.ns.as_deref().unwrap_or_default(),
+		claims.db.as_deref().unwrap_or_default(),
+		claims.sc.as_deref().unwrap_or_default(),
+		claims.tk.as_deref().unwrap_or_default(),
+	]
+}
+
+/// Returns for how many seconds after its expiry a token can be refreshed
+async fn refresh(tx: &mut Transaction, claims: &Claims) -> Result<i64, Error> {
+	if let Claims {
+		ns: Some(ns),
+		db: Some(db),
+		sc: Some(sc),
+		tk: None,
+		..
+	} = claims
+	{
+		if let Ok(sv) = tx.get_sc(ns, db, sc).await {
+			if let Some(v) = sv.refresh {
+				return Ok(v.secs() as i64);
+			}
+		}
+	}
+	Ok(0)
+}
+
+/// Adds a token to the revocation list, until the specified timestamp
+/// after which the token can no longer be used anyway.
+pub(crate) async fn add(
+	tx: &mut Transaction,
+	claims: &Claims,
+	jti: &str,
+	until: i64,
+) -> Result<(), Error> {
+	let [ns, db, sc, tk] = tenant(claims);
+	tx.set(crate::key::rv::new(ns, db, sc, tk, jti), Value::from(until)).await
+}
+
+/// Revokes the tokens issued to the subject up until now. The revocation
+/// is kept until all of those tokens can no longer be used anyway, which
+/// is not known for tokens which were issued by another issuer.
+async fn subject(tx: &mut Transaction, claims: &Claims, id: &str) -> Result<(), Error> {
+	let now = Utc::now().timestamp();
+	let until = match (&claims.sc, &claims.tk) {
+		// Tokens issued by another issuer
+		(_, Some(_)) => i64::MAX,
+		// Tokens issued when signing in to a scope
+		(Some(sc), None) => {
+			let [ns, db, _, _] = tenant(claims);
+			let sv = tx.get_sc(ns, db, sc).await?;
+			let session = sv.session.map(|v| v.secs() as i64).unwrap_or(LOGIN_EXPIRY);
+			now.saturating_add(session).saturating_add(refresh(tx, claims).await?)
+		}
+		// Tokens issued when signing in to a namespace or database
+		(None, None) => now.saturating_add(LOGIN_EXPIRY),
+	};
+	let mut val = Object::default();
+	val.insert("at".to_owned(), now.into());
+	val.insert("until".to_owned(), until.into());
+	let [ns, db, sc, tk] = tenant(claims);
+	tx.set(crate::key::rs::new(ns, db, sc, tk, id), Value::from(val)).await
+}
+
+/// Checks if a token has been revoked, either by itself, or because it
+/// was issued to a subject whose tokens were revoked after it was issued.
+/// A token issued in the same second as such a revocation is revoked too.
+pub(crate) async fn is_revoked(tx: &mut Transaction, claims: &Claims) -> Result<bool, Error> {
+	let [ns, db, sc, tk] = tenant(claims);
+	// Check the revocation list for the token
+	if let Some(jti) = &claims.jti {
+		if tx.exi(crate::key::rv::new(ns, db, sc, tk, jti)).await? {
+			return Ok(true);
+		}
+	}
+	// Check when the tokens of the subject were revoked
+	if let Some(id) = &claims.id {
+		if let Some(v) = tx.get(crate::key::rs::new(ns, db, sc, tk, id)).await? {
+			if let Value::Object(v) = Value::from(v) {
+				if let Some(Value::Number(at)) = v.get("at") {
+					return Ok(claims.iat.map_or(true, |iat| iat <= at.to_int()));
+				}
+			}
+		}
+	}
+	Ok(false)
+}
+
+/// Removes the revoked tokens and subjects which can no longer be used at
+/// the specified timestamp in seconds. The revocations are checked in
+/// batches, each batch in its own separate transaction.
+pub(crate) async fn gc_all_at(kvs: &Datastore, ts: i64) -> Result<(), Error> {
+	gc_range_at(kvs, crate::key::rv::prefix(), crate::key::rv::suffix(), ts).await?;
+	gc_range_at(kvs, crate::key::rs::prefix(), crate::key::rs::suffix(), ts).await
+}
+
+async fn gc_range_at(kvs: &Datastore, mut beg: Key, end: Key, ts: i64) -> Result<(), Error> {
+	loop {
+		// Create a new writeable transaction
+		let mut tx = kvs.transaction(true, false).await?;
+		// Get the next batch of revocations
+		let res = tx.scan(beg.clone()..end.clone(), BATCH).await?;
+		// Continue after the last key of the batch
+		let nxt = match res.last() {
+			Some((k, _)) if res.len() as u32 == BATCH => Some(k.clone()),
+			_ => None,
+		};
+		// Remove the revocations which are no longer needed
+		for (k, v) in res {
+			if until(Value::from(v)) < ts {
+				tx.del(k).await?;
+			}
+		}
+		tx.commit().await?;
+		// Process the next batch
+		match nxt {
+			Some(mut k) => {
+				k.push(0x00);
+				beg = k;
+			}
+			None => return Ok(()),
+		}
+	}
+}
+
+/// Returns the timestamp until which a revocation is needed
+fn until(v: Value) -> i64 {
+	match v {
+		Value::Number(v) => v.as_int(),
+		Value::Object(v) => match v.get("until") {
+			Some(Value::Number(v)) => v.to_int(),
+			_ => i64::MAX,
+		},
+		_ => i64::MAX,
+	}
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+
+	use super::*;
+
+	fn claims(ns: &str, jti: &str, iat: i64) -> Claims {
+		Claims {
+			ns: Some(ns.to_owned()),
+			db: Some("test".to_owned()),
+			tk: Some("test".to_owned()),
+			id: Some("user:test".to_owned()),
+			jti: Some(jti.to_owned()),
+			iat: Some(iat),
+			..Claims::default()
+		}
+	}
+
+	#[tokio::test]
+	async fn revoked_tokens_are_removed_once_unusable() {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let mut tx = dbs.transaction(true, false).await.unwrap();
+		add(&mut tx, &claims("test", "expired", 0), "expired", 1_000).await.unwrap();
+		add(&mut tx, &claims("test", "current", 0), "current", 3_000).await.unwrap();
+		tx.commit().await.unwrap();
+		// Tick at a timestamp in milliseconds
+		dbs.tick_at(2_000_000).await.unwrap();
+		let mut tx = dbs.transaction(false, false).await.unwrap();
+		assert!(!is_revoked(&mut tx, &claims("test", "expired", 0)).await.unwrap());
+		assert!(is_revoked(&mut tx, &claims("test", "current", 0)).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn revoked_tokens_are_removed_in_batches() {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let mut tx = dbs.transaction(true, false).await.unwrap();
+		for i in 0..BATCH * 2 + 1 {
+			let jti = format!("{i:05}");
+			add(&mut tx, &claims("test", &jti, 0), &jti, 1_000).await.unwrap();
+		}
+		tx.commit().await.unwrap();
+		gc_all_at(&dbs, 2_000).await.unwrap();
+		let mut tx = dbs.transaction(false, false).await.unwrap();
+		let beg = crate::key::rv::prefix();
+		let end = crate::key::rv::suffix();
+		assert!(tx.scan(beg..end, u32::MAX).await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn revoked_tokens_are_kept_apart_by_tenant() {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let mut tx = dbs.transaction(true, false).await.unwrap();
+		add(&mut tx, &claims("one", "shared", 0), "shared", i64::MAX).await.unwrap();
+		tx.commit().await.unwrap();
+		let mut tx = dbs.transaction(false, false).await.unwrap();
+		assert!(is_revoked(&mut tx, &claims("one", "shared", 0)).await.unwrap());
+		assert!(!is_revoked(&mut tx, &claims("two", "shared", 0)).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn revoked_subjects_revoke_tokens_issued_before() {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let now = Utc::now().timestamp();
+		let mut tx = dbs.transaction(true, false).await.unwrap();
+		subject(&mut tx, &claims("test", "", 0), "user:test").await.unwrap();
+		tx.commit().await.unwrap();
+		let mut tx = dbs.transaction(false, false).await.unwrap();
+		assert!(is_revoked(&mut tx, &claims("test", "old", now - 60)).await.unwrap());
+		assert!(!is_revoked(&mut tx, &claims("test", "new", now + 60)).await.unwrap());
+		assert!(!is_revoked(&mut tx, &claims("other", "old", now - 60)).await.unwrap());
+	}
+}
@@ -12,6 +12,7 @@ use argon2::Argon2;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{encode, EncodingKey};
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub async fn signin(
 	kvs: &Datastore,
@@ -84,7 +85,7 @@ pub async fn signin(
 					let user = user.to_raw_string();
 					let pass = pass.to_raw_string();
 					// Attempt to signin to namespace
-					super::signin::su(configured_root, session, user, pass)?;
+					super::signin::su(kvs, configured_root, session, user, pass).await?;
 					Ok(None)
 				}
 				// There is no username or password
@@ -126,6 +127,7 @@ pub async fn sc(
 								// Create the authentication claim
 								let val = Claims {
 									iss: Some(SERVER_NAME.to_owned()),
+									jti: Some(Uuid::new_v4().to_string()),
 									iat: Some(Utc::now().timestamp()),
 									nbf: Some(Utc::now().timestamp()),
 									exp: Some(
@@ -199,6 +201,7 @@ pub async fn db(
 					// Create the authentication claim
 					let val = Claims {
 						iss: Some(SERVER_NAME.to_owned()),
+						jti: Some(Uuid::new_v4().to_string()),
 						iat: Some(Utc::now().timestamp()),
 						nbf: Some(Utc::now().timestamp()),
 						exp: Some((Utc::now() + Duration::hours(1)).timestamp()),
@@ -213,7 +216,7 @@ pub async fn db(
 					session.tk = Some(val.into());
 					session.ns = Some(ns.to_owned());
 					session.db = Some(db.to_owned());
-					session.au = Arc::new(Auth::Db(ns, db));
+					session.au = Arc::new(Auth::Db(ns, db, dl.role()));
 					// Check the authentication token
 					match enc {
 						// The auth token was created successfully
@@ -253,6 +256,7 @@ pub async fn ns(
 					// Create the authentication claim
 					let val = Claims {
 						iss: Some(SERVER_NAME.to_owned()),
+						jti: Some(Uuid::new_v4().to_string()),
 						iat: Some(Utc::now().timestamp()),
 						nbf: Some(Utc::now().timestamp()),
 						exp: Some((Utc::now() + Duration::hours(1)).timestamp()),
@@ -265,7 +269,7 @@ pub async fn ns(
 					// Set the authentication on the session
 					session.tk = Some(val.into());
 					session.ns = Some(ns.to_owned());
-					session.au = Arc::new(Auth::Ns(ns));
+					session.au = Arc::new(Auth::Ns(ns, nl.role()));
 					// Check the authentication token
 					match enc {
 						// The auth token was created successfully
@@ -283,19 +287,32 @@ pub async fn ns(
 	}
 }
 
-pub fn su(
+pub async fn su(
+	kvs: &Datastore,
 	configured_root: &Option<Root<'_>>,
 	session: &mut Session,
 	user: String,
 	pass: String,
 ) -> Result<(), Error> {
-	// Attempt to verify the root user
+	// Attempt to verify the configured root user
 	if let Some(root) = configured_root {
 		if user == root.username && pass == root.password {
 			session.au = Arc::new(Auth::Kv);
 			return Ok(());
 		}
 	}
+	// Create a new readonly transaction
+	let mut tx = kvs.transaction(false, false).await?;
+	// Check if the supplied root login exists
+	if let Ok(kl) = tx.get_kl(&user).await {
+		// Compute the hash and verify the password
+		let hash = PasswordHash::new(&kl.hash).unwrap();
+		// Attempt to verify the password using Argon2
+		if Argon2::default().verify_password(pass.as_ref(), &hash).is_ok() {
+			session.au = Arc::new(Auth::Kv);
+			return Ok(());
+		}
+	}
 	// The specified user login does not exist
 	Err(Error::InvalidAuth)
 }
@@ -9,6 +9,7 @@ use crate::sql::Value;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{encode, EncodingKey};
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub async fn signup(
 	kvs: &Datastore,
@@ -64,6 +65,7 @@ pub async fn sc(
 								// Create the authentication claim
 								let val = Claims {
 									iss: Some(SERVER_NAME.to_owned()),
+									jti: Some(Uuid::new_v4().to_string()),
 									iat: Some(Utc::now().timestamp()),
 									nbf: Some(Utc::now().timestamp()),
 									exp: Some(
@@ -16,6 +16,8 @@ pub struct Claims {
 	pub exp: Option<i64>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub iss: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub jti: Option<String>,
 	#[serde(alias = "ns")]
 	#[serde(alias = "NS")]
 	#[serde(rename = "NS")]
@@ -51,6 +53,13 @@ pub struct Claims {
 	#[serde(alias = "https://surrealdb.com/record")]
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub id: Option<String>,
+	#[serde(alias = "rl")]
+	#[serde(alias = "RL")]
+	#[serde(rename = "RL")]
+	#[serde(alias = "https://surrealdb.com/rl")]
+	#[serde(alias = "https://surrealdb.com/roles")]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rl: Option<Vec<String>>,
 }
 
 impl From<Claims> for Value {
@@ -61,6 +70,10 @@ impl From<Claims> for Value {
 		if let Some(iss) = v.iss {
 			out.insert("iss".to_string(), iss.into());
 		}
+		// Add jti field if set
+		if let Some(jti) = v.jti {
+			out.insert("jti".to_string(), jti.into());
+		}
 		// Add iat field if set
 		if let Some(iat) = v.iat {
 			out.insert("iat".to_string(), iat.into());
@@ -93,6 +106,10 @@ impl From<Claims> for Value {
 		if let Some(id) = v.id {
 			out.insert("ID".to_string(), id.into());
 		}
+		// Add RL field if set
+		if let Some(rl) = v.rl {
+			out.insert("RL".to_string(), rl.into());
+		}
 		// Return value
 		out.into()
 	}
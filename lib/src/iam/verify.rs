@@ -5,13 +5,18 @@ use crate::iam::token::Claims;
 use crate::iam::TOKEN;
 use crate::kvs::Datastore;
 use crate::sql::Algorithm;
+use crate::sql::Role;
 use crate::sql::Value;
 use chrono::Utc;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 
-fn config(algo: Algorithm, code: String) -> Result<(DecodingKey, Validation), Error> {
+async fn config(
+	algo: Algorithm,
+	code: String,
+	token: &str,
+) -> Result<(DecodingKey, Validation), Error> {
 	match algo {
 		Algorithm::Hs256 => Ok((
 			DecodingKey::from_secret(code.as_ref()),
@@ -65,12 +70,37 @@ fn config(algo: Algorithm, code: String) -> Result<(DecodingKey, Validation), Er
 			DecodingKey::from_rsa_pem(code.as_ref())?,
 			Validation::new(jsonwebtoken::Algorithm::RS512),
 		)),
+		Algorithm::Jwks => super::jwks::config(&code, token).await,
 	}
 }
 
-static KEY: Lazy<DecodingKey> = Lazy::new(|| DecodingKey::from_secret(&[]));
+/// Returns the role granted by the roles in the claims of a token. A token
+/// without an `RL` claim grants the role of an owner, in the same way as the
+/// logins which were defined before roles were introduced.
+fn role(rl: Option<Vec<String>>) -> Result<Role, Error> {
+	let rl = match rl {
+		Some(rl) => rl,
+		None => return Ok(Role::Owner),
+	};
+	let mut out = Role::Viewer;
+	for v in rl {
+		let v = match v.to_uppercase().as_str() {
+			"VIEWER" => Role::Viewer,
+			"EDITOR" => Role::Editor,
+			"OWNER" => Role::Owner,
+			_ => {
+				trace!("The 'RL' field in the authentication token was invalid");
+				return Err(Error::InvalidAuth);
+			}
+		};
+		out = out.max(v);
+	}
+	Ok(out)
+}
 
-static DUD: Lazy<Validation> = Lazy::new(|| {
+pub(super) static KEY: Lazy<DecodingKey> = Lazy::new(|| DecodingKey::from_secret(&[]));
+
+pub(super) static DUD: Lazy<Validation> = Lazy::new(|| {
 	let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
 	validation.insecure_disable_signature_validation();
 	validation.validate_nbf = false;
@@ -101,6 +131,16 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			return Err(Error::InvalidAuth);
 		}
 	}
+	// Check if the auth token has been revoked
+	if token.claims.jti.is_some() || token.claims.id.is_some() {
+		// Create a new readonly transaction
+		let mut tx = kvs.transaction(false, false).await?;
+		// Check the revocation lists for the token
+		if super::revoke::is_revoked(&mut tx, &token.claims).await? {
+			trace!("The authentication token has been revoked");
+			return Err(Error::InvalidAuth);
+		}
+	}
 	// Check the token authentication claims
 	match token.claims {
 		// Check if this is scope token authentication
@@ -123,7 +163,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			};
 			// Get the scope token
 			let de = tx.get_st(&ns, &db, &sc, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = config(de.kind, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -153,7 +193,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			let id = crate::sql::thing(&id)?;
 			// Get the scope
 			let de = tx.get_sc(&ns, &db, &sc).await?;
-			let cf = config(Algorithm::Hs512, de.code)?;
+			let cf = config(Algorithm::Hs512, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -172,15 +212,18 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			ns: Some(ns),
 			db: Some(db),
 			tk: Some(tk),
+			rl,
 			..
 		} => {
 			// Log the decoded authentication claims
 			trace!("Authenticating to database `{}` with token `{}`", db, tk);
+			// Get the role granted by the token
+			let rl = role(rl)?;
 			// Create a new readonly transaction
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the database token
 			let de = tx.get_dt(&ns, &db, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = config(de.kind, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -189,7 +232,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			session.tk = Some(value);
 			session.ns = Some(ns.to_owned());
 			session.db = Some(db.to_owned());
-			session.au = Arc::new(Auth::Db(ns, db));
+			session.au = Arc::new(Auth::Db(ns, db, rl));
 			Ok(())
 		}
 		// Check if this is database authentication
@@ -205,7 +248,8 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the database login
 			let de = tx.get_dl(&ns, &db, &id).await?;
-			let cf = config(Algorithm::Hs512, de.code)?;
+			let rl = de.role();
+			let cf = config(Algorithm::Hs512, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -214,22 +258,25 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			session.tk = Some(value);
 			session.ns = Some(ns.to_owned());
 			session.db = Some(db.to_owned());
-			session.au = Arc::new(Auth::Db(ns, db));
+			session.au = Arc::new(Auth::Db(ns, db, rl));
 			Ok(())
 		}
 		// Check if this is namespace token authentication
 		Claims {
 			ns: Some(ns),
 			tk: Some(tk),
+			rl,
 			..
 		} => {
 			// Log the decoded authentication claims
 			trace!("Authenticating to namespace `{}` with token `{}`", ns, tk);
+			// Get the role granted by the token
+			let rl = role(rl)?;
 			// Create a new readonly transaction
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the namespace token
 			let de = tx.get_nt(&ns, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = config(de.kind, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -237,7 +284,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			// Set the session
 			session.tk = Some(value);
 			session.ns = Some(ns.to_owned());
-			session.au = Arc::new(Auth::Ns(ns));
+			session.au = Arc::new(Auth::Ns(ns, rl));
 			Ok(())
 		}
 		// Check if this is namespace authentication
@@ -252,7 +299,8 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the namespace login
 			let de = tx.get_nl(&ns, &id).await?;
-			let cf = config(Algorithm::Hs512, de.code)?;
+			let rl = de.role();
+			let cf = config(Algorithm::Hs512, de.code, auth).await?;
 			// Verify the token
 			decode::<Claims>(auth, &cf.0, &cf.1)?;
 			// Log the success
@@ -260,10 +308,59 @@ pub async fn token(kvs: &Datastore, session: &mut Session, auth: String) -> Resu
 			// Set the session
 			session.tk = Some(value);
 			session.ns = Some(ns.to_owned());
-			session.au = Arc::new(Auth::Ns(ns));
+			session.au = Arc::new(Auth::Ns(ns, rl));
 			Ok(())
 		}
 		// There was an auth error
 		_ => Err(Error::InvalidAuth),
 	}
 }
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+
+	use super::*;
+	use chrono::Duration;
+	use jsonwebtoken::{encode, EncodingKey, Header};
+
+	fn sign(rl: Option<Vec<&str>>) -> String {
+		let claims = Claims {
+			exp: Some((Utc::now() + Duration::hours(1)).timestamp()),
+			ns: Some("test".to_owned()),
+			db: Some("test".to_owned()),
+			tk: Some("test".to_owned()),
+			rl: rl.map(|v| v.into_iter().map(String::from).collect()),
+			..Claims::default()
+		};
+		let header = Header::new(jsonwebtoken::Algorithm::HS512);
+		let key = EncodingKey::from_secret("secret".as_ref());
+		format!("Bearer {}", encode(&header, &claims, &key).unwrap())
+	}
+
+	#[tokio::test]
+	async fn token_grants_the_role_in_its_claims() {
+		let dbs = Datastore::new("memory").await.unwrap();
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		let sql = "DEFINE TOKEN test ON DATABASE TYPE HS512 VALUE 'secret'";
+		let res = dbs.execute(sql, &ses, None).await.unwrap();
+		assert!(res[0].result.is_ok());
+		// A token without roles grants the role of an owner
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, sign(None)).await.is_ok());
+		assert_eq!(ses.au.as_ref(), &Auth::Db("test".into(), "test".into(), Role::Owner));
+		// A token with an empty list of roles only grants the role of a viewer
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, sign(Some(vec![]))).await.is_ok());
+		assert_eq!(ses.au.as_ref(), &Auth::Db("test".into(), "test".into(), Role::Viewer));
+		// A token grants the most permissive of its roles
+		let mut ses = Session::default();
+		assert!(token(&dbs, &mut ses, sign(Some(vec!["viewer", "EDITOR"]))).await.is_ok());
+		assert_eq!(ses.au.as_ref(), &Auth::Db("test".into(), "test".into(), Role::Editor));
+		// A token with an unknown role is invalid
+		let mut ses = Session::default();
+		assert!(matches!(
+			token(&dbs, &mut ses, sign(Some(vec!["ADMIN"]))).await,
+			Err(Error::InvalidAuth)
+		));
+	}
+}
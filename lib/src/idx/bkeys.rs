@@ -70,8 +70,27 @@ impl BKeys for FstKeys {
 		}
 	}
 
-	fn collect_with_prefix(&self, _prefix_key: &Key) -> VecDeque<(Key, Payload)> {
-		panic!("Not supported!")
+	fn collect_with_prefix(&self, prefix_key: &Key) -> VecDeque<(Key, Payload)> {
+		let mut r = VecDeque::new();
+		let mut s = self.map.range().ge(prefix_key).into_stream();
+		while let Some((k, p)) = s.next() {
+			if !k.starts_with(prefix_key) {
+				break;
+			}
+			if self.deletions.get(k).is_none() && self.additions.get(k).is_none() {
+				r.push_back((k.to_vec(), p));
+			}
+		}
+		// The additions which are not compiled yet
+		if let Some(additions) = self.additions.get_raw_descendant(prefix_key) {
+			for (k, p) in additions.iter() {
+				if k.starts_with(prefix_key) {
+					r.push_back((k.clone(), *p));
+				}
+			}
+			r.make_contiguous().sort();
+		}
+		r
 	}
 
 	fn insert(&mut self, key: Key, payload: Payload) {
@@ -613,15 +632,14 @@ mod tests {
 		}
 	}
 
-	#[tokio::test]
-	async fn test_tries_keys_collect_with_prefix() {
-		let mut keys = TrieKeys::default();
+	fn test_keys_collect_with_prefix<BK: BKeys>(mut keys: BK) {
 		keys.insert("apple".into(), 1);
 		keys.insert("applicant".into(), 2);
 		keys.insert("application".into(), 3);
 		keys.insert("applicative".into(), 4);
 		keys.insert("banana".into(), 5);
 		keys.insert("blueberry".into(), 6);
+		keys.compile();
 		keys.insert("the".into(), 7);
 		keys.insert("these".into(), 11);
 		keys.insert("theses".into(), 12);
@@ -666,6 +684,21 @@ mod tests {
 			let r = keys.collect_with_prefix(&"zz".into());
 			check_keys(r, vec![]);
 		}
+
+		{
+			let r = keys.collect_with_prefix(&vec![]);
+			assert_eq!(r.len(), 12);
+		}
+	}
+
+	#[test]
+	fn test_fst_keys_collect_with_prefix() {
+		test_keys_collect_with_prefix(FstKeys::default())
+	}
+
+	#[test]
+	fn test_trie_keys_collect_with_prefix() {
+		test_keys_collect_with_prefix(TrieKeys::default())
 	}
 
 	fn test_keys_split<BK: BKeys>(mut keys: BK) {
@@ -4,6 +4,7 @@ use crate::idx::SerdeState;
 use crate::kvs::{Key, Transaction};
 use crate::sql::{Object, Value};
 use async_trait::async_trait;
+use fst::Automaton;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -646,6 +647,81 @@ where
 		Err(Error::CorruptedIndex)
 	}
 
+	/// Collect, in key order, at most `limit` keys accepted by the automaton.
+	/// The children which can't contain any accepted key are not visited.
+	pub(super) async fn search_by_automaton<BK, A>(
+		&self,
+		tx: &mut Transaction,
+		aut: &A,
+		limit: usize,
+	) -> Result<Vec<(Key, Payload)>, Error>
+	where
+		BK: BKeys + Serialize + DeserializeOwned,
+		A: Automaton,
+	{
+		enum Visit {
+			// A node, along with the keys bounding its keys
+			Node(NodeId, Option<Key>, Option<Key>),
+			Key(Key, Payload),
+		}
+		let mut res = Vec::new();
+		let mut stack = Vec::new();
+		if let Some(node_id) = self.state.root {
+			stack.push(Visit::Node(node_id, None, None));
+		}
+		while let Some(visit) = stack.pop() {
+			if res.len() >= limit {
+				break;
+			}
+			match visit {
+				Visit::Key(key, payload) => res.push((key, payload)),
+				Visit::Node(node_id, low, high) => {
+					// Every key of the node starts with the common prefix of its bounds
+					let prefix = match (&low, &high) {
+						(Some(l), Some(h)) => {
+							&l[..l.iter().zip(h.iter()).take_while(|(a, b)| a == b).count()]
+						}
+						_ => &[],
+					};
+					if !can_match(aut, prefix) {
+						continue;
+					}
+					let stored = self.keys.load_node::<BK>(tx, node_id).await?;
+					let mut keys = stored.node.keys().collect_with_prefix(&Key::new());
+					keys.make_contiguous().sort();
+					match stored.node {
+						Node::Leaf(_) => {
+							// The keys are pushed in reverse order on the stack
+							for (k, p) in keys.into_iter().rev() {
+								if is_match(aut, &k) {
+									stack.push(Visit::Key(k, p));
+								}
+							}
+						}
+						Node::Internal(_, children) => {
+							let mut high = high;
+							for (i, child_id) in children.into_iter().enumerate().rev() {
+								let low = if i > 0 {
+									keys.get(i - 1).map(|(k, _)| k.clone())
+								} else {
+									low.clone()
+								};
+								stack.push(Visit::Node(child_id, low.clone(), high));
+								if let Some((k, p)) = i.checked_sub(1).and_then(|i| keys.get(i)) {
+									if is_match(aut, k) {
+										stack.push(Visit::Key(k.clone(), *p));
+									}
+								}
+								high = low;
+							}
+						}
+					}
+				}
+			}
+		}
+		Ok(res)
+	}
+
 	pub(super) async fn statistics<BK>(&self, tx: &mut Transaction) -> Result<Statistics, Error>
 	where
 		BK: BKeys + Serialize + DeserializeOwned,
@@ -694,6 +770,28 @@ where
 	}
 }
 
+fn can_match<A: Automaton>(aut: &A, prefix: &[u8]) -> bool {
+	let mut state = aut.start();
+	for b in prefix {
+		if !aut.can_match(&state) {
+			return false;
+		}
+		state = aut.accept(&state, *b);
+	}
+	aut.can_match(&state)
+}
+
+fn is_match<A: Automaton>(aut: &A, key: &[u8]) -> bool {
+	let mut state = aut.start();
+	for b in key {
+		if !aut.can_match(&state) {
+			return false;
+		}
+		state = aut.accept(&state, *b);
+	}
+	aut.is_match(&state)
+}
+
 pub(super) struct StoredNode<BK>
 where
 	BK: BKeys,
@@ -723,6 +821,8 @@ mod tests {
 	};
 	use crate::idx::SerdeState;
 	use crate::kvs::{Datastore, Key, Transaction};
+	use fst::automaton::Str;
+	use fst::Automaton;
 	use rand::prelude::SliceRandom;
 	use rand::thread_rng;
 	use serde::de::DeserializeOwned;
@@ -974,6 +1074,53 @@ mod tests {
 		);
 	}
 
+	async fn test_btree_search_by_automaton<BK>(default_minimum_degree: u32)
+	where
+		BK: BKeys + Serialize + DeserializeOwned + Default,
+	{
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut t = BTree::new(TestKeyProvider {}, State::new(default_minimum_degree));
+		let mut terms: Vec<&str> = REAL_WORLD_TERMS.to_vec();
+		terms.sort();
+		terms.dedup();
+		let mut samples = terms.clone();
+		samples.shuffle(&mut thread_rng());
+		insertions_test::<_, BK, _>(&mut tx, &mut t, samples.len(), |i| {
+			(samples[i].as_bytes().to_vec(), i as Payload)
+		})
+		.await;
+		tx.commit().await.unwrap();
+		let mut tx = ds.transaction(false, false).await.unwrap();
+
+		for prefix in ["", "th", "the", "o", "fox", "z"] {
+			let aut = Str::new(prefix).starts_with();
+			let res = t.search_by_automaton::<BK, _>(&mut tx, &aut, usize::MAX).await.unwrap();
+			let res: Vec<String> =
+				res.into_iter().map(|(k, _)| String::from_utf8(k).unwrap()).collect();
+			let expected: Vec<&str> =
+				terms.iter().filter(|t| t.starts_with(prefix)).copied().collect();
+			assert_eq!(res, expected, "{prefix}");
+		}
+		// The number of keys is limited
+		let aut = Str::new("").starts_with();
+		let res = t.search_by_automaton::<BK, _>(&mut tx, &aut, 3).await.unwrap();
+		let res: Vec<&[u8]> = res.iter().map(|(k, _)| k.as_slice()).collect();
+		assert_eq!(res, vec!["and".as_bytes(), "animals".as_bytes(), "brown".as_bytes()]);
+	}
+
+	#[test(tokio::test)]
+	async fn test_btree_fst_keys_search_by_automaton() {
+		test_btree_search_by_automaton::<FstKeys>(2).await;
+		test_btree_search_by_automaton::<FstKeys>(100).await;
+	}
+
+	#[test(tokio::test)]
+	async fn test_btree_trie_keys_search_by_automaton() {
+		test_btree_search_by_automaton::<TrieKeys>(2).await;
+		test_btree_search_by_automaton::<TrieKeys>(100).await;
+	}
+
 	// This is the examples from the chapter B-Trees in CLRS:
 	// https://en.wikipedia.org/wiki/Introduction_to_Algorithms
 	const CLRS_EXAMPLE: [(&str, Payload); 23] = [
@@ -0,0 +1,231 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::idx::SerdeState;
+use crate::key;
+use crate::kvs::{Key, Transaction};
+use crate::sql::statements::{DefineIndexStatement, UpdateStatement};
+use crate::sql::{Object, Thing, Value, Values};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The number of records which are indexed in each transaction of a build
+const BATCH_SIZE: u32 = 1000;
+/// The duration for which a node holds a build once it has claimed it
+const LEASE_DURATION: Duration = Duration::from_secs(30);
+
+/// The progress of an index which is being built in the background
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IndexBuild {
+	/// The number of records which have been indexed
+	processed: u64,
+	/// The key of the last record which has been indexed
+	cursor: Option<Key>,
+	/// The error which caused the build to fail
+	error: Option<String>,
+	/// The node which is running the build
+	owner: Option<Uuid>,
+	/// The timestamp in milliseconds at which the claim of the owner expires
+	lease: u64,
+}
+
+impl SerdeState for IndexBuild {}
+
+impl From<IndexBuild> for Value {
+	fn from(build: IndexBuild) -> Self {
+		let status = match (&build.error, &build.cursor) {
+			(Some(_), _) => "failed",
+			(None, Some(_)) => "building",
+			(None, None) => "pending",
+		};
+		let mut res = Object::default();
+		res.insert("status".to_owned(), Value::from(status));
+		res.insert("processed".to_owned(), Value::from(build.processed));
+		if let Some(error) = build.error {
+			res.insert("error".to_owned(), Value::from(error));
+		}
+		Value::from(res)
+	}
+}
+
+impl IndexBuild {
+	/// Queue a build of the index, discarding the progress of any previous build
+	pub(crate) async fn queue(
+		tx: &mut Transaction,
+		opt: &Options,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = key::ib::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+		tx.set(key, Self::default().try_to_val()?).await
+	}
+
+	/// Cancel the pending build of the index, if any
+	pub(crate) async fn cancel(
+		tx: &mut Transaction,
+		opt: &Options,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = key::ib::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+		tx.del(key).await
+	}
+
+	/// Check if the build has failed. A failed build is not retried
+	/// until the index is rebuilt, and the index is not used meanwhile.
+	pub(crate) fn is_failed(&self) -> bool {
+		self.error.is_some()
+	}
+
+	/// Mark the build of the index as failed
+	pub(crate) async fn fail(
+		tx: &mut Transaction,
+		opt: &Options,
+		tb: &str,
+		ix: &str,
+		error: &Error,
+	) -> Result<(), Error> {
+		let key = key::ib::new(opt.ns(), opt.db(), tb, ix);
+		if let Some(val) = tx.get(key.clone()).await? {
+			let mut build = Self::try_from_val(val)?;
+			build.error = Some(error.to_string());
+			tx.set(key, build.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+
+	/// Claim the build of the index for the node, at the specified timestamp
+	/// in milliseconds, so that the build is only run by one node at a time.
+	/// Returns false if the build is finished or failed, or if it is held by
+	/// another node whose claim has not yet expired.
+	pub(crate) async fn claim(
+		tx: &mut Transaction,
+		opt: &Options,
+		tb: &str,
+		ix: &str,
+		now: u64,
+	) -> Result<bool, Error> {
+		let key = key::ib::new(opt.ns(), opt.db(), tb, ix);
+		let mut build = match tx.get(key.clone()).await? {
+			Some(val) => Self::try_from_val(val)?,
+			None => return Ok(false),
+		};
+		if build.is_failed() || !build.claimable(opt.id()?, now) {
+			return Ok(false);
+		}
+		build.owner = Some(opt.id()?);
+		build.lease = now.saturating_add(LEASE_DURATION.as_millis() as u64);
+		tx.set(key, build.try_to_val()?).await?;
+		Ok(true)
+	}
+
+	/// Check if the build can be claimed by the node
+	fn claimable(&self, nd: Uuid, now: u64) -> bool {
+		match self.owner {
+			Some(owner) => owner == nd || self.lease < now,
+			None => true,
+		}
+	}
+
+	/// Retrieve the pending builds of the indexes of a table, by index name
+	pub(crate) async fn all(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Vec<(String, Self)>, Error> {
+		let beg = key::ib::prefix(ns, db, tb);
+		let end = key::ib::suffix(ns, db, tb);
+		let mut res = vec![];
+		for (k, v) in tx.getr(beg..end, u32::MAX).await? {
+			let ix = key::ib::Ib::decode(&k)?.ix.to_owned();
+			res.push((ix, Self::try_from_val(v)?));
+		}
+		Ok(res)
+	}
+
+	/// Retrieve the indexes of a table which are not being built, and which
+	/// can therefore be used to resolve queries
+	pub(crate) async fn ready(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Arc<[DefineIndexStatement]>, Error> {
+		let ixs = tx.all_ix(ns, db, tb).await?;
+		let builds = Self::all(tx, ns, db, tb).await?;
+		if builds.is_empty() {
+			return Ok(ixs);
+		}
+		Ok(ixs.iter().filter(|ix| !builds.iter().any(|(n, _)| ix.name.0 == *n)).cloned().collect())
+	}
+
+	/// Index the next batch of records of the table, at the specified
+	/// timestamp in milliseconds, renewing the claim of the node on the
+	/// build. Returns true once there is nothing left to index, or once
+	/// the build has been claimed by another node.
+	pub(crate) async fn next_batch(
+		ctx: &Context<'_>,
+		opt: &Options,
+		tb: &str,
+		ix: &str,
+		now: u64,
+	) -> Result<bool, Error> {
+		// Clone transaction
+		let txn = ctx.try_clone_transaction()?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// The build may have been cancelled meanwhile
+		let key = key::ib::new(opt.ns(), opt.db(), tb, ix);
+		let mut build = match run.get(key.clone()).await? {
+			Some(val) => Self::try_from_val(val)?,
+			None => return Ok(true),
+		};
+		if build.is_failed() || build.owner != Some(opt.id()?) {
+			return Ok(true);
+		}
+		// Renew the claim on the build
+		build.lease = now.saturating_add(LEASE_DURATION.as_millis() as u64);
+		// Get the next batch of records
+		let beg = match build.cursor.take() {
+			None => key::thing::prefix(opt.ns(), opt.db(), tb),
+			Some(mut k) => {
+				k.push(0x00);
+				k
+			}
+		};
+		let end = key::thing::suffix(opt.ns(), opt.db(), tb);
+		let res = run.scan(beg..end, BATCH_SIZE).await?;
+		// Every record has been indexed
+		if res.is_empty() {
+			run.del(key).await?;
+			return Ok(true);
+		}
+		let mut things = Vec::with_capacity(res.len());
+		for (k, _) in res.iter() {
+			let id = key::thing::Thing::decode(k)?.id;
+			things.push(Value::from(Thing::from((tb, id))));
+		}
+		// Save the progress of the build
+		build.processed += res.len() as u64;
+		build.cursor = res.into_iter().last().map(|(k, _)| k);
+		run.set(key, build.try_to_val()?).await?;
+		// Release the transaction
+		drop(run);
+		// Force queries to run
+		let opt = &opt.new_with_force(true);
+		// Don't process field queries
+		let opt = &opt.new_with_fields(false);
+		// Don't process event queries
+		let opt = &opt.new_with_events(false);
+		// Don't process table queries
+		let opt = &opt.new_with_tables(false);
+		// Update the index data
+		let stm = UpdateStatement {
+			what: Values(things),
+			..UpdateStatement::default()
+		};
+		stm.compute(ctx, opt).await?;
+		Ok(false)
+	}
+}
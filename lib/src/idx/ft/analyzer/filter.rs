@@ -1,9 +1,11 @@
 use crate::err::Error;
+use crate::idx::ft::analyzer::stopwords::stopwords;
 use crate::idx::ft::analyzer::tokenizer::Tokens;
-use crate::sql::filter::Filter as SqlFilter;
+use crate::sql::filter::{Filter as SqlFilter, Stopwords};
 use crate::sql::language::Language;
 use deunicode::deunicode;
 use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::{HashMap, HashSet};
 
 pub(super) enum Filter {
 	Stemmer(Stemmer),
@@ -12,6 +14,9 @@ pub(super) enum Filter {
 	EdgeNgram(u16, u16),
 	Lowercase,
 	Uppercase,
+	Stopwords(HashSet<String>),
+	Synonyms(HashMap<String, Vec<String>>),
+	Mapper(HashMap<String, String>),
 }
 
 impl From<SqlFilter> for Filter {
@@ -20,6 +25,7 @@ impl From<SqlFilter> for Filter {
 			SqlFilter::Ascii => Filter::Ascii,
 			SqlFilter::EdgeNgram(min, max) => Filter::EdgeNgram(min, max),
 			SqlFilter::Lowercase => Filter::Lowercase,
+			SqlFilter::Mapper(m) => Filter::Mapper(m.into_iter().collect()),
 			SqlFilter::Ngram(min, max) => Filter::Ngram(min, max),
 			SqlFilter::Snowball(l) => {
 				let a = match l {
@@ -43,6 +49,11 @@ impl From<SqlFilter> for Filter {
 				};
 				Filter::Stemmer(a)
 			}
+			SqlFilter::Stopwords(s) => Filter::Stopwords(match s {
+				Stopwords::Language(l) => stopwords(&l).iter().map(|w| w.to_string()).collect(),
+				Stopwords::Custom(w) => w.into_iter().collect(),
+			}),
+			SqlFilter::Synonyms(s) => Filter::Synonyms(s.into_iter().collect()),
 			SqlFilter::Uppercase => Filter::Uppercase,
 		}
 	}
@@ -78,6 +89,9 @@ impl Filter {
 			Filter::Ngram(min, max) => Self::ngram(c, *min, *max),
 			Filter::Stemmer(s) => Self::stem(s, c),
 			Filter::Uppercase => Self::uppercase(c),
+			Filter::Stopwords(s) => Self::stopwords(s, c),
+			Filter::Synonyms(s) => Self::synonyms(s, c),
+			Filter::Mapper(m) => Self::mapper(m, c),
 		}
 	}
 
@@ -112,6 +126,41 @@ impl Filter {
 		Self::check_term(c, s.stem(&c.to_lowercase()).into())
 	}
 
+	#[inline]
+	fn stopwords(s: &HashSet<String>, c: &str) -> FilterResult {
+		if s.contains(c) {
+			FilterResult::Ignore
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
+	#[inline]
+	fn synonyms(s: &HashMap<String, Vec<String>>, c: &str) -> FilterResult {
+		if let Some(synonyms) = s.get(c) {
+			// The original term is kept, and the synonyms share its position
+			let mut terms = Vec::with_capacity(synonyms.len() + 1);
+			terms.push(Term::Unchanged);
+			for s in synonyms {
+				if s.ne(c) && !s.is_empty() {
+					terms.push(Term::NewTerm(s.clone()));
+				}
+			}
+			FilterResult::Terms(terms)
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
+	#[inline]
+	fn mapper(m: &HashMap<String, String>, c: &str) -> FilterResult {
+		if let Some(s) = m.get(c) {
+			Self::check_term(c, s.clone())
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
 	#[inline]
 	fn ngram(c: &str, min: u16, max: u16) -> FilterResult {
 		let min = min as usize;
@@ -184,8 +233,7 @@ mod tests {
 
 	#[test]
 	fn test_arabic_stemmer() {
-		let input =
-			"الكلاب تحب الجري في الحديقة، لكن كلبي الصغير يفضل النوم في سريره بدلاً من الجري";
+		let input = "الكلاب تحب الجري في الحديقة، لكن كلبي الصغير يفضل النوم في سريره بدلاً من الجري";
 		let output = vec![
 			"كلاب", "تحب", "الجر", "في", "حديق", "لكن", "كلب", "صغير", "يفضل", "نوم", "في", "سرير",
 			"بدل", "من", "الجر",
@@ -731,4 +779,41 @@ mod tests {
 			&["āl", "āle", "ia", "iac", "es", "est"],
 		);
 	}
+
+	#[test]
+	fn test_stopwords() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,stopwords(english);",
+			"The quick brown fox jumps over the lazy dog",
+			&["quick", "brown", "fox", "jumps", "lazy", "dog"],
+		);
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,stopwords(fr);",
+			"Le chat dort sur le canapé",
+			&["chat", "dort", "canapé"],
+		);
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,stopwords(['quick', 'lazy']);",
+			"The quick brown fox jumps over the lazy dog",
+			&["the", "brown", "fox", "jumps", "over", "the", "dog"],
+		);
+	}
+
+	#[test]
+	fn test_synonyms() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,synonyms({ car: ['automobile', 'auto'], fast: 'quick' });",
+			"Fast car",
+			&["fast", "quick", "car", "automobile", "auto"],
+		);
+	}
+
+	#[test]
+	fn test_mapper() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,mapper({ ran: 'run', running: 'run', 'better': '' });",
+			"Ran running better runs",
+			&["run", "run", "runs"],
+		);
+	}
 }
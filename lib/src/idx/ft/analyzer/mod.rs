@@ -1,8 +1,11 @@
+use crate::cnf::MAX_TERM_EXPANSIONS;
 use crate::err::Error;
-use crate::idx::ft::analyzer::tokenizer::{Tokenizer, Tokens};
+use crate::idx::ft::analyzer::tokenizer::{Segmenter, Tokenizer, Tokens};
 use crate::idx::ft::doclength::DocLength;
-use crate::idx::ft::offsets::{Offset, OffsetRecords};
+use crate::idx::ft::fields::FieldsFrequencies;
+use crate::idx::ft::offsets::{Offset, OffsetRecords, Position};
 use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::query::Expansion;
 use crate::idx::ft::terms::{TermId, Terms};
 use crate::kvs::Transaction;
 use crate::sql::statements::DefineAnalyzerStatement;
@@ -13,25 +16,68 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 
 mod filter;
+mod stopwords;
 mod tokenizer;
 
 pub(crate) struct Analyzers {}
 
 impl Analyzers {
 	pub(crate) const LIKE: &'static str = "like";
+
+	/// Run the given analyzer on a value, and return the resulting tokens along with
+	/// their offsets and positions. An array returns the tokens of each of its values.
+	pub(crate) async fn analyze(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		az: &str,
+		val: Value,
+	) -> Result<Value, Error> {
+		let a: Analyzer = tx.get_az(ns, db, az).await?.try_into()?;
+		let mut inputs = vec![];
+		a.analyze_value(&val, &mut inputs)?;
+		let mut res = Vec::with_capacity(inputs.len());
+		for (i, tks) in inputs.iter().enumerate() {
+			let mut r = Vec::with_capacity(tks.list().len());
+			for (tk, pos) in tks.list().iter().zip(tks.positions()) {
+				let o = tk.new_offset(i as u32, pos);
+				r.push(Value::from(map! {
+					String::from("token") => Value::from(tks.get_token_string(tk)?),
+					String::from("start") => Value::from(o.start),
+					String::from("end") => Value::from(o.end),
+					String::from("position") => Value::from(o.position),
+				}));
+			}
+			res.push(Value::from(r));
+		}
+		Ok(match val {
+			Value::Array(_) => Value::from(res),
+			_ => res.pop().unwrap_or_else(|| Value::from(Array::new())),
+		})
+	}
 }
 
 pub(super) struct Analyzer {
 	t: Option<Vec<SqlTokenizer>>,
+	s: Vec<Segmenter>,
 	f: Option<Vec<Filter>>,
 }
 
-impl From<DefineAnalyzerStatement> for Analyzer {
-	fn from(az: DefineAnalyzerStatement) -> Self {
-		Self {
+impl TryFrom<DefineAnalyzerStatement> for Analyzer {
+	type Error = Error;
+
+	fn try_from(az: DefineAnalyzerStatement) -> Result<Self, Self::Error> {
+		let mut s = vec![];
+		for t in az.tokenizers.iter().flatten() {
+			if let Some(sg) = Segmenter::new(t)? {
+				s.push(sg);
+			}
+		}
+		Ok(Self {
 			t: az.tokenizers,
+			s,
 			f: Filter::from(az.filters),
-		}
+		})
 	}
 }
 
@@ -58,6 +104,47 @@ impl Analyzer {
 		Ok(res)
 	}
 
+	/// Extract the terms of a word, along with the terms of the index matching its last term.
+	/// Returns `None` if the word has no term.
+	pub(super) async fn extract_expanded_terms(
+		&self,
+		t: &Terms,
+		tx: &mut Transaction,
+		word: String,
+		expansion: &Expansion,
+	) -> Result<Option<(Vec<Option<TermId>>, Vec<TermId>)>, Error> {
+		let tokens = self.analyze(word)?;
+		let Some((last, others)) = tokens.list().split_last() else {
+			return Ok(None);
+		};
+		let mut terms = Vec::with_capacity(others.len());
+		for tk in others {
+			terms.push(t.get_term_id(tx, tokens.get_token_string(tk)?).await?);
+		}
+		let last = tokens.get_token_string(last)?;
+		let expansions = match expansion {
+			Expansion::Prefix => t.get_terms_with_prefix(tx, last, *MAX_TERM_EXPANSIONS).await?,
+			Expansion::Fuzzy(d) => t.get_fuzzy_terms(tx, last, *d, *MAX_TERM_EXPANSIONS).await?,
+		};
+		Ok(Some((terms, expansions)))
+	}
+
+	/// Extract the terms of a phrase, in order, along with their positions
+	pub(super) async fn extract_phrase(
+		&self,
+		t: &Terms,
+		tx: &mut Transaction,
+		phrase: String,
+	) -> Result<Vec<(Position, Option<TermId>)>, Error> {
+		let tokens = self.analyze(phrase)?;
+		let mut res = Vec::with_capacity(tokens.list().len());
+		for (tk, pos) in tokens.list().iter().zip(tokens.positions()) {
+			let opt_term_id = t.get_term_id(tx, tokens.get_token_string(tk)?).await?;
+			res.push((pos, opt_term_id));
+		}
+		Ok(res)
+	}
+
 	/// This method is used for indexing.
 	/// It will create new term ids for non already existing terms.
 	pub(super) async fn extract_terms_with_frequencies(
@@ -111,10 +198,10 @@ impl Analyzer {
 		// We then collect every unique terms and count the frequency and extract the offsets
 		let mut tfos: HashMap<&str, Vec<Offset>> = HashMap::new();
 		for (i, tks) in inputs.iter().enumerate() {
-			for tk in tks.list() {
+			for (tk, pos) in tks.list().iter().zip(tks.positions()) {
 				dl += 1;
 				let s = tks.get_token_string(tk)?;
-				let o = tk.new_offset(i as u32);
+				let o = tk.new_offset(i as u32, pos);
 				match tfos.entry(s) {
 					Entry::Vacant(e) => {
 						e.insert(vec![o]);
@@ -135,6 +222,39 @@ impl Analyzer {
 		Ok((dl, tfid, osid))
 	}
 
+	/// This method is used for indexing multi-field content.
+	/// It returns the frequencies of each term within each field.
+	/// The terms must have already been resolved.
+	pub(super) async fn extract_fields_frequencies(
+		&self,
+		terms: &Terms,
+		tx: &mut Transaction,
+		field_content: &Array,
+	) -> Result<Vec<(TermId, FieldsFrequencies)>, Error> {
+		let fields = field_content.len();
+		let mut ffs: HashMap<String, FieldsFrequencies> = HashMap::new();
+		for (i, v) in field_content.iter().enumerate() {
+			let mut inputs = vec![];
+			self.analyze_value(v, &mut inputs)?;
+			for tks in &inputs {
+				for tk in tks.list() {
+					let s = tks.get_token_string(tk)?;
+					let ff = ffs
+						.entry(s.to_owned())
+						.or_insert_with(|| FieldsFrequencies(vec![0; fields]));
+					ff.0[i] += 1;
+				}
+			}
+		}
+		let mut res = Vec::with_capacity(ffs.len());
+		for (t, ff) in ffs {
+			if let Some(term_id) = terms.get_term_id(tx, &t).await? {
+				res.push((term_id, ff));
+			}
+		}
+		Ok(res)
+	}
+
 	fn analyze_content(&self, field_content: &Array, tks: &mut Vec<Tokens>) -> Result<(), Error> {
 		for v in &field_content.0 {
 			self.analyze_value(v, tks)?;
@@ -160,7 +280,7 @@ impl Analyzer {
 	fn analyze(&self, input: String) -> Result<Tokens, Error> {
 		if let Some(t) = &self.t {
 			if !input.is_empty() {
-				let t = Tokenizer::tokenize(t, input);
+				let t = Tokenizer::tokenize(t, &self.s, input);
 				return Filter::apply_filters(t, &self.f);
 			}
 		}
@@ -175,7 +295,7 @@ mod tests {
 
 	pub(super) fn test_analyzer(def: &str, input: &str, expected: &[&str]) {
 		let (_, az) = analyzer(def).unwrap();
-		let a: Analyzer = az.into();
+		let a: Analyzer = az.try_into().unwrap();
 
 		let tokens = a.analyze(input.to_string()).unwrap();
 		let mut res = vec![];
@@ -0,0 +1,535 @@
+use crate::sql::language::Language;
+
+/// The built-in list of stop words of a language.
+/// The words are lowercase, so the filter is expected to follow LOWERCASE.
+pub(super) fn stopwords(l: &Language) -> &'static [&'static str] {
+	match l {
+		Language::Arabic => ARABIC,
+		Language::Danish => DANISH,
+		Language::Dutch => DUTCH,
+		Language::English => ENGLISH,
+		Language::French => FRENCH,
+		Language::German => GERMAN,
+		Language::Greek => GREEK,
+		Language::Hungarian => HUNGARIAN,
+		Language::Italian => ITALIAN,
+		Language::Norwegian => NORWEGIAN,
+		Language::Portuguese => PORTUGUESE,
+		Language::Romanian => ROMANIAN,
+		Language::Russian => RUSSIAN,
+		Language::Spanish => SPANISH,
+		Language::Swedish => SWEDISH,
+		Language::Tamil => TAMIL,
+		Language::Turkish => TURKISH,
+	}
+}
+
+const ARABIC: &[&str] = &[
+	"في",
+	"من",
+	"على",
+	"إلى",
+	"عن",
+	"مع",
+	"هذا",
+	"هذه",
+	"ذلك",
+	"تلك",
+	"الذي",
+	"التي",
+	"الذين",
+	"هو",
+	"هي",
+	"هم",
+	"هن",
+	"أنا",
+	"نحن",
+	"أنت",
+	"أنتم",
+	"كان",
+	"كانت",
+	"يكون",
+	"ليس",
+	"لم",
+	"لن",
+	"لا",
+	"ما",
+	"ماذا",
+	"متى",
+	"أين",
+	"كيف",
+	"إن",
+	"أن",
+	"قد",
+	"ثم",
+	"أو",
+	"أم",
+	"بل",
+	"لكن",
+	"كل",
+	"بعض",
+	"غير",
+	"بين",
+	"عند",
+	"حتى",
+	"إذا",
+	"كما",
+	"و",
+	"ف",
+	"ب",
+	"ل",
+];
+
+const DANISH: &[&str] = &[
+	"af", "alle", "andet", "andre", "at", "begge", "da", "de", "den", "denne", "der", "deres",
+	"det", "dette", "dig", "din", "dog", "du", "efter", "eller", "en", "end", "er", "et", "for",
+	"fra", "ham", "han", "hans", "har", "havde", "have", "hende", "hendes", "her", "hos", "hun",
+	"hvad", "hvis", "hvor", "i", "ikke", "ind", "jeg", "jer", "jo", "kunne", "man", "mange", "med",
+	"meget", "men", "mig", "min", "mine", "mit", "mod", "ned", "noget", "nogle", "nu", "når", "og",
+	"også", "om", "op", "os", "over", "på", "selv", "sig", "sin", "sine", "sit", "skal", "skulle",
+	"som", "sådan", "thi", "til", "ud", "under", "var", "vi", "vil", "ville", "vor", "være",
+	"været",
+];
+
+const DUTCH: &[&str] = &[
+	"aan", "al", "alles", "als", "altijd", "andere", "ben", "bij", "daar", "dan", "dat", "de",
+	"der", "deze", "die", "dit", "doch", "doen", "door", "dus", "een", "eens", "en", "er", "ge",
+	"geen", "geweest", "haar", "had", "heb", "hebben", "heeft", "hem", "het", "hier", "hij", "hoe",
+	"hun", "iemand", "iets", "ik", "in", "is", "ja", "je", "kan", "kon", "kunnen", "maar", "me",
+	"meer", "men", "met", "mij", "mijn", "moet", "na", "naar", "niet", "niets", "nog", "nu", "of",
+	"om", "omdat", "onder", "ons", "ook", "op", "over", "reeds", "te", "tegen", "toch", "toen",
+	"tot", "u", "uit", "uw", "van", "veel", "voor", "want", "waren", "was", "wat", "werd", "wezen",
+	"wie", "wil", "worden", "wordt", "zal", "ze", "zelf", "zich", "zij", "zijn", "zo", "zonder",
+	"zou",
+];
+
+const ENGLISH: &[&str] = &[
+	"a",
+	"about",
+	"above",
+	"after",
+	"again",
+	"against",
+	"all",
+	"am",
+	"an",
+	"and",
+	"any",
+	"are",
+	"as",
+	"at",
+	"be",
+	"because",
+	"been",
+	"before",
+	"being",
+	"below",
+	"between",
+	"both",
+	"but",
+	"by",
+	"can",
+	"did",
+	"do",
+	"does",
+	"doing",
+	"down",
+	"during",
+	"each",
+	"few",
+	"for",
+	"from",
+	"further",
+	"had",
+	"has",
+	"have",
+	"having",
+	"he",
+	"her",
+	"here",
+	"hers",
+	"herself",
+	"him",
+	"himself",
+	"his",
+	"how",
+	"i",
+	"if",
+	"in",
+	"into",
+	"is",
+	"it",
+	"its",
+	"itself",
+	"just",
+	"me",
+	"more",
+	"most",
+	"my",
+	"myself",
+	"no",
+	"nor",
+	"not",
+	"now",
+	"of",
+	"off",
+	"on",
+	"once",
+	"only",
+	"or",
+	"other",
+	"our",
+	"ours",
+	"ourselves",
+	"out",
+	"over",
+	"own",
+	"same",
+	"she",
+	"should",
+	"so",
+	"some",
+	"such",
+	"than",
+	"that",
+	"the",
+	"their",
+	"theirs",
+	"them",
+	"themselves",
+	"then",
+	"there",
+	"these",
+	"they",
+	"this",
+	"those",
+	"through",
+	"to",
+	"too",
+	"under",
+	"until",
+	"up",
+	"very",
+	"was",
+	"we",
+	"were",
+	"what",
+	"when",
+	"where",
+	"which",
+	"while",
+	"who",
+	"whom",
+	"why",
+	"will",
+	"with",
+	"you",
+	"your",
+	"yours",
+	"yourself",
+	"yourselves",
+];
+
+const FRENCH: &[&str] = &[
+	"au", "aux", "avec", "ce", "ces", "cette", "dans", "de", "des", "du", "elle", "elles", "en",
+	"est", "et", "eux", "il", "ils", "je", "la", "le", "les", "leur", "leurs", "lui", "ma", "mais",
+	"me", "même", "mes", "moi", "mon", "ne", "nos", "notre", "nous", "on", "ou", "par", "pas",
+	"pour", "qu", "que", "qui", "sa", "se", "ses", "son", "sont", "sur", "ta", "te", "tes", "toi",
+	"ton", "tu", "un", "une", "vos", "votre", "vous", "c", "d", "j", "l", "m", "n", "s", "t", "y",
+	"été", "être", "avoir", "ai", "as", "a", "avons", "avez", "ont", "était", "étaient",
+];
+
+const GERMAN: &[&str] = &[
+	"aber", "alle", "als", "also", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "bist",
+	"da", "damit", "dann", "das", "dass", "dem", "den", "denn", "der", "des", "dich", "die", "dir",
+	"doch", "dort", "du", "durch", "ein", "eine", "einem", "einen", "einer", "eines", "er", "es",
+	"euch", "euer", "für", "hat", "hatte", "hier", "ich", "ihm", "ihn", "ihr", "ihre", "im", "in",
+	"ist", "ja", "jetzt", "kann", "kein", "keine", "man", "mein", "meine", "mich", "mir", "mit",
+	"nach", "nicht", "noch", "nun", "nur", "ob", "oder", "ohne", "sehr", "sein", "seine", "sich",
+	"sie", "sind", "so", "über", "um", "und", "uns", "unser", "unter", "vom", "von", "vor", "war",
+	"waren", "was", "weil", "wenn", "wer", "wie", "wir", "wird", "wo", "zu", "zum", "zur",
+];
+
+const GREEK: &[&str] = &[
+	"ο",
+	"η",
+	"το",
+	"οι",
+	"τα",
+	"του",
+	"της",
+	"των",
+	"τον",
+	"την",
+	"και",
+	"κι",
+	"να",
+	"θα",
+	"με",
+	"σε",
+	"από",
+	"για",
+	"προς",
+	"που",
+	"πως",
+	"ως",
+	"ότι",
+	"δεν",
+	"μη",
+	"μην",
+	"αλλά",
+	"ή",
+	"ένα",
+	"μια",
+	"ένας",
+	"είναι",
+	"ήταν",
+	"αυτός",
+	"αυτή",
+	"αυτό",
+	"αυτοί",
+	"αυτά",
+	"εγώ",
+	"εσύ",
+	"εμείς",
+	"εσείς",
+	"τους",
+	"τις",
+	"στο",
+	"στη",
+	"στην",
+	"στον",
+	"στα",
+	"στους",
+	"στις",
+	"μου",
+	"σου",
+	"μας",
+	"σας",
+];
+
+const HUNGARIAN: &[&str] = &[
+	"a", "az", "egy", "és", "hogy", "nem", "is", "de", "meg", "van", "volt", "csak", "már", "még",
+	"mint", "ha", "el", "ki", "be", "fel", "le", "vagy", "pedig", "mert", "mi", "ez", "azt", "ezt",
+	"ő", "én", "te", "ti", "ők", "itt", "ott", "aki", "ami", "amely", "nagyon", "lesz", "sem",
+	"kell", "után", "között", "alatt", "felett", "nincs", "minden", "sok", "így", "úgy",
+];
+
+const ITALIAN: &[&str] = &[
+	"a", "ad", "al", "alla", "alle", "agli", "ai", "anche", "che", "chi", "ci", "come", "con",
+	"da", "dal", "dalla", "dei", "del", "della", "delle", "di", "e", "è", "ed", "gli", "ha",
+	"hanno", "i", "il", "in", "io", "la", "le", "lei", "lo", "loro", "lui", "ma", "mi", "mio",
+	"ne", "nei", "nel", "nella", "noi", "non", "o", "per", "più", "quale", "quando", "quella",
+	"quello", "questa", "questo", "se", "sei", "si", "sia", "sono", "su", "sua", "suo", "sul",
+	"sulla", "ti", "tra", "tu", "un", "una", "uno", "voi",
+];
+
+const NORWEGIAN: &[&str] = &[
+	"og", "i", "jeg", "det", "at", "en", "et", "den", "til", "er", "som", "på", "de", "med", "han",
+	"av", "ikke", "der", "så", "var", "meg", "seg", "men", "ett", "har", "om", "vi", "min", "mitt",
+	"ha", "hadde", "hun", "nå", "over", "da", "ved", "fra", "du", "ut", "sin", "dem", "oss", "opp",
+	"man", "kan", "hans", "hvor", "eller", "hva", "skal", "selv", "sjøl", "her", "alle", "vil",
+	"bli", "ble", "blitt", "kunne", "inn", "når", "være", "kom", "noen", "noe", "ville", "dere",
+	"deres", "kun", "ja", "etter", "ned", "skulle", "denne", "for", "deg", "si", "sine", "sitt",
+	"mot", "å", "meget", "hvorfor", "dette", "disse", "uten", "hvordan", "ingen", "din", "ditt",
+	"blir", "samme", "hvilken", "hvilke", "sånn", "inni", "mellom", "vår", "hver", "hvem", "vors",
+];
+
+const PORTUGUESE: &[&str] = &[
+	"a", "ao", "aos", "as", "às", "com", "como", "da", "das", "de", "dela", "dele", "do", "dos",
+	"e", "é", "ela", "elas", "ele", "eles", "em", "entre", "era", "essa", "esse", "esta", "está",
+	"este", "eu", "foi", "há", "isso", "isto", "já", "lhe", "mais", "mas", "me", "mesmo", "meu",
+	"minha", "muito", "na", "nas", "não", "nem", "no", "nos", "nós", "num", "numa", "o", "os",
+	"ou", "para", "pela", "pelo", "por", "qual", "quando", "que", "quem", "se", "sem", "ser",
+	"seu", "sua", "são", "também", "te", "tem", "um", "uma", "você",
+];
+
+const ROMANIAN: &[&str] = &[
+	"a", "acea", "aceasta", "această", "acel", "acest", "acesta", "ai", "al", "ale", "am", "ar",
+	"are", "au", "avea", "ca", "care", "ce", "cel", "cu", "că", "cum", "da", "dar", "de", "din",
+	"după", "ea", "ei", "el", "ele", "eu", "este", "fi", "fost", "iar", "il", "în", "îl", "își",
+	"la", "le", "lor", "lui", "mai", "ne", "nici", "noi", "nu", "o", "pe", "pentru", "prin", "sa",
+	"se", "si", "și", "sub", "sunt", "să", "tu", "un", "una", "unei", "unui", "voi",
+];
+
+const RUSSIAN: &[&str] = &[
+	"и",
+	"в",
+	"во",
+	"не",
+	"что",
+	"он",
+	"на",
+	"я",
+	"с",
+	"со",
+	"как",
+	"а",
+	"то",
+	"все",
+	"она",
+	"так",
+	"его",
+	"но",
+	"да",
+	"ты",
+	"к",
+	"у",
+	"же",
+	"вы",
+	"за",
+	"бы",
+	"по",
+	"только",
+	"ее",
+	"мне",
+	"было",
+	"вот",
+	"от",
+	"меня",
+	"еще",
+	"нет",
+	"о",
+	"из",
+	"ему",
+	"теперь",
+	"когда",
+	"даже",
+	"ну",
+	"ли",
+	"если",
+	"уже",
+	"или",
+	"ни",
+	"быть",
+	"был",
+	"него",
+	"до",
+	"вас",
+	"нибудь",
+	"опять",
+	"уж",
+	"вам",
+	"ведь",
+	"там",
+	"потом",
+	"себя",
+	"ничего",
+	"ей",
+	"может",
+	"они",
+	"тут",
+	"где",
+	"есть",
+	"надо",
+	"ней",
+	"для",
+	"мы",
+	"тебя",
+	"их",
+	"чем",
+	"была",
+	"сам",
+	"чтоб",
+	"без",
+	"будто",
+	"чего",
+	"раз",
+	"тоже",
+	"себе",
+	"под",
+	"будет",
+	"ж",
+	"тогда",
+	"кто",
+	"этот",
+	"того",
+	"потому",
+	"этого",
+	"какой",
+	"совсем",
+	"ним",
+	"здесь",
+	"этом",
+	"один",
+	"почти",
+	"мой",
+	"тем",
+	"чтобы",
+	"нее",
+	"при",
+];
+
+const SPANISH: &[&str] = &[
+	"a", "al", "algo", "como", "con", "contra", "cual", "cuando", "de", "del", "desde", "donde",
+	"durante", "e", "el", "él", "ella", "ellas", "ellos", "en", "entre", "era", "es", "esa", "ese",
+	"eso", "esta", "está", "este", "esto", "fue", "ha", "hay", "la", "las", "le", "les", "lo",
+	"los", "más", "me", "mi", "muy", "nada", "ni", "no", "nos", "nosotros", "o", "otra", "otro",
+	"para", "pero", "poco", "por", "porque", "que", "qué", "quien", "se", "sea", "ser", "si", "sí",
+	"sin", "sobre", "son", "su", "sus", "también", "te", "tiene", "todo", "tu", "un", "una", "uno",
+	"unos", "y", "ya", "yo",
+];
+
+const SWEDISH: &[&str] = &[
+	"och", "det", "att", "i", "en", "jag", "hon", "som", "han", "på", "den", "med", "var", "sig",
+	"för", "så", "till", "är", "men", "ett", "om", "hade", "de", "av", "icke", "mig", "du",
+	"henne", "då", "sin", "nu", "har", "inte", "hans", "honom", "skulle", "hennes", "där", "min",
+	"man", "ej", "vid", "kunde", "något", "från", "ut", "när", "efter", "upp", "vi", "dem", "vara",
+	"vad", "över", "än", "dig", "kan", "sina", "här", "ha", "mot", "alla", "under", "någon",
+	"eller", "allt", "mycket", "sedan", "ju", "denna", "själv", "detta", "åt", "utan", "varit",
+	"hur", "ingen", "mitt", "ni", "bli", "blev", "oss", "din", "dessa", "några", "deras", "blir",
+	"mina", "samma", "vilken", "er", "sådan", "vår", "blivit", "dess", "inom", "mellan", "sådant",
+	"varför", "varje", "vilka", "ditt", "vem", "vilket", "sitt", "sådana", "vart", "dina", "vars",
+	"vårt", "våra", "ert", "era", "vilkas",
+];
+
+const TAMIL: &[&str] = &[
+	"அது",
+	"இது",
+	"அந்த",
+	"இந்த",
+	"ஒரு",
+	"மற்றும்",
+	"என்று",
+	"என",
+	"மேலும்",
+	"அவர்",
+	"அவள்",
+	"அவன்",
+	"அவர்கள்",
+	"நான்",
+	"நாம்",
+	"நாங்கள்",
+	"நீ",
+	"நீங்கள்",
+	"உள்ள",
+	"உள்ளது",
+	"இல்லை",
+	"ஆனால்",
+	"அல்லது",
+	"போல",
+	"வரை",
+	"பின்",
+	"முன்",
+	"மீது",
+	"கூட",
+	"தான்",
+	"என்ன",
+	"எப்படி",
+	"ஏன்",
+	"எங்கே",
+	"எந்த",
+	"இருந்து",
+	"இருக்கும்",
+	"இருந்தது",
+	"செய்ய",
+	"பற்றி",
+];
+
+const TURKISH: &[&str] = &[
+	"acaba", "ama", "aslında", "az", "bazı", "belki", "biri", "birkaç", "birşey", "biz", "bu",
+	"çok", "çünkü", "da", "daha", "de", "defa", "diye", "eğer", "en", "gibi", "hem", "hep",
+	"hepsi", "her", "hiç", "için", "ile", "ise", "kez", "ki", "kim", "mı", "mu", "mü", "nasıl",
+	"ne", "neden", "nerde", "nerede", "nereye", "niçin", "niye", "o", "sanki", "şey", "siz", "şu",
+	"tüm", "ve", "veya", "ya", "yani",
+];
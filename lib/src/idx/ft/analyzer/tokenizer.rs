@@ -2,6 +2,8 @@ use crate::err::Error;
 use crate::idx::ft::analyzer::filter::{Filter, FilterResult, Term};
 use crate::idx::ft::offsets::{Offset, Position};
 use crate::sql::tokenizer::Tokenizer as SqlTokenizer;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub(super) struct Tokens {
 	/// The input string
@@ -65,6 +67,23 @@ impl Tokens {
 	pub(super) fn list(&self) -> &Vec<Token> {
 		&self.t
 	}
+
+	/// The position of each token. Tokens which are derived from the same
+	/// part of the input (e.g. by EDGENGRAM) share the same position.
+	pub(super) fn positions(&self) -> Vec<Position> {
+		let mut res = Vec::with_capacity(self.t.len());
+		let mut last = None;
+		let mut pos = 0;
+		for t in &self.t {
+			let start = t.start();
+			if matches!(last, Some(l) if l != start) {
+				pos += 1;
+			}
+			last = Some(start);
+			res.push(pos);
+		}
+		res
+	}
 }
 
 #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Hash)]
@@ -103,16 +122,29 @@ impl Token {
 		}
 	}
 
-	pub(super) fn new_offset(&self, i: u32) -> Offset {
+	pub(super) fn new_offset(&self, i: u32, position: Position) -> Offset {
+		match self {
+			Token::Ref {
+				chars,
+				..
+			} => Offset::new(i, chars.0, chars.1, position),
+			Token::String {
+				chars,
+				..
+			} => Offset::new(i, chars.0, chars.1, position),
+		}
+	}
+
+	fn start(&self) -> Position {
 		match self {
 			Token::Ref {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => chars.0,
 			Token::String {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => chars.0,
 		}
 	}
 
@@ -160,7 +192,7 @@ pub(super) struct Tokenizer {
 impl Tokenizer {
 	pub(in crate::idx::ft) fn new(t: &[SqlTokenizer]) -> Self {
 		Self {
-			splitters: t.iter().map(|t| t.into()).collect(),
+			splitters: t.iter().filter_map(Splitter::new).collect(),
 		}
 	}
 
@@ -178,17 +210,44 @@ impl Tokenizer {
 		res
 	}
 
-	pub(super) fn tokenize(t: &[SqlTokenizer], i: String) -> Tokens {
+	pub(super) fn tokenize(t: &[SqlTokenizer], s: &[Segmenter], i: String) -> Tokens {
+		let mut segments = vec![Segment {
+			chars: (0, i.chars().count() as Position),
+			bytes: (0, i.len() as Position),
+			split: true,
+		}];
+		for s in s {
+			segments = s.segment(&i, segments);
+		}
 		let mut w = Tokenizer::new(t);
-		let mut last_char_pos = 0;
-		let mut last_byte_pos = 0;
-		let mut current_char_pos = 0;
-		let mut current_byte_pos = 0;
+		let has_splitters = !w.splitters.is_empty();
 		let mut t = Vec::new();
-		for c in i.chars() {
+		for s in segments {
+			if s.split || has_splitters {
+				w.split(&i, &s, &mut t);
+			} else {
+				t.push(Token::Ref {
+					chars: s.chars,
+					bytes: s.bytes,
+				});
+			}
+		}
+		Tokens {
+			i,
+			t,
+		}
+	}
+
+	fn split(&mut self, i: &str, s: &Segment, t: &mut Vec<Token>) {
+		for sp in &mut self.splitters {
+			sp.state = 0;
+		}
+		let (mut last_char_pos, mut last_byte_pos) = (s.chars.0, s.bytes.0);
+		let (mut current_char_pos, mut current_byte_pos) = (s.chars.0, s.bytes.0);
+		for c in i[(s.bytes.0 as usize)..(s.bytes.1 as usize)].chars() {
 			let char_len = c.len_utf8() as Position;
 			let is_valid = Self::is_valid(c);
-			let should_split = w.should_split(c);
+			let should_split = self.should_split(c);
 			if should_split || !is_valid {
 				// The last pos may be more advanced due to the is_valid process
 				if last_char_pos < current_char_pos {
@@ -215,34 +274,162 @@ impl Tokenizer {
 				bytes: (last_byte_pos, current_byte_pos),
 			});
 		}
-		Tokens {
-			i,
-			t,
+	}
+}
+
+/// A part of the input
+struct Segment {
+	chars: (Position, Position),
+	bytes: (Position, Position),
+	/// True if the characters of the segment still have to be validated and split
+	split: bool,
+}
+
+impl Segment {
+	/// Build the segments matching the given byte ranges of the segment.
+	/// The ranges are expected to be sorted and not to overlap.
+	fn sub_segments(&self, i: &str, ranges: impl Iterator<Item = (usize, usize)>) -> Vec<Self> {
+		let mut res = vec![];
+		let (mut char_pos, mut byte_pos) = (self.chars.0, 0);
+		for (s, e) in ranges {
+			if s == e {
+				continue;
+			}
+			char_pos += i[byte_pos..s].chars().count() as Position;
+			let l = i[s..e].chars().count() as Position;
+			res.push(Self {
+				chars: (char_pos, char_pos + l),
+				bytes: (self.bytes.0 + s as Position, self.bytes.0 + e as Position),
+				split: false,
+			});
+			char_pos += l;
+			byte_pos = e;
 		}
+		res
 	}
 }
 
+/// A tokenizer which cuts the input into segments,
+/// before the characters of the segments are split.
+pub(super) enum Segmenter {
+	Words,
+	Regex(Regex),
+	Cjk,
+}
+
+impl Segmenter {
+	pub(super) fn new(t: &SqlTokenizer) -> Result<Option<Self>, Error> {
+		Ok(match t {
+			SqlTokenizer::Words => Some(Self::Words),
+			SqlTokenizer::Regex(r) => {
+				Some(Self::Regex(Regex::new(r).map_err(|e| Error::AnalyzerError(e.to_string()))?))
+			}
+			SqlTokenizer::Cjk => Some(Self::Cjk),
+			_ => None,
+		})
+	}
+
+	fn segment(&self, i: &str, segments: Vec<Segment>) -> Vec<Segment> {
+		let mut res = Vec::with_capacity(segments.len());
+		for s in segments {
+			let txt = &i[(s.bytes.0 as usize)..(s.bytes.1 as usize)];
+			match self {
+				Self::Words => {
+					res.append(&mut s.sub_segments(
+						txt,
+						txt.unicode_word_indices().map(|(p, w)| (p, p + w.len())),
+					))
+				}
+				Self::Regex(r) => res.append(
+					&mut s.sub_segments(txt, r.find_iter(txt).map(|m| (m.start(), m.end()))),
+				),
+				Self::Cjk => Self::cjk(&s, txt, &mut res),
+			}
+		}
+		res
+	}
+
+	/// Runs of CJK characters are cut into overlapping bigrams,
+	/// while the other parts of the segment are kept as they are.
+	fn cjk(s: &Segment, txt: &str, res: &mut Vec<Segment>) {
+		let chars: Vec<(usize, char)> = txt.char_indices().collect();
+		let l = chars.len();
+		let byte_pos = |p: usize| {
+			s.bytes.0
+				+ if p < l {
+					chars[p].0
+				} else {
+					txt.len()
+				} as Position
+		};
+		let sub = |start: usize, end: usize, split: bool| Segment {
+			chars: (s.chars.0 + start as Position, s.chars.0 + end as Position),
+			bytes: (byte_pos(start), byte_pos(end)),
+			split,
+		};
+		let mut start = 0;
+		while start < l {
+			let cjk = is_cjk(chars[start].1);
+			let mut end = start + 1;
+			while end < l && is_cjk(chars[end].1) == cjk {
+				end += 1;
+			}
+			if !cjk {
+				res.push(sub(start, end, s.split));
+			} else if end - start == 1 {
+				res.push(sub(start, end, false));
+			} else {
+				for p in start..(end - 1) {
+					res.push(sub(p, p + 2, false));
+				}
+			}
+			start = end;
+		}
+	}
+}
+
+/// Han ideographs, Hiragana, Katakana and Hangul
+fn is_cjk(c: char) -> bool {
+	matches!(c as u32,
+		0x1100..=0x11FF
+		| 0x3040..=0x309F
+		| 0x30A0..=0x30FF
+		| 0x3130..=0x318F
+		| 0x31F0..=0x31FF
+		| 0x3400..=0x4DBF
+		| 0x4E00..=0x9FFF
+		| 0xAC00..=0xD7AF
+		| 0xF900..=0xFAFF
+		| 0x20000..=0x2FA1F
+	)
+}
+
 struct Splitter {
 	t: SqlTokenizer,
 	state: u8,
 }
 
-impl From<&SqlTokenizer> for Splitter {
-	fn from(t: &SqlTokenizer) -> Self {
-		Self {
-			t: t.clone(),
-			state: 0,
+impl Splitter {
+	fn new(t: &SqlTokenizer) -> Option<Self> {
+		match t {
+			SqlTokenizer::Blank
+			| SqlTokenizer::Camel
+			| SqlTokenizer::Class
+			| SqlTokenizer::Punct => Some(Self {
+				t: t.clone(),
+				state: 0,
+			}),
+			_ => None,
 		}
 	}
-}
 
-impl Splitter {
 	fn should_split(&mut self, c: char) -> bool {
 		match &self.t {
 			SqlTokenizer::Blank => self.blank_state(c),
 			SqlTokenizer::Camel => self.camel_state(c),
 			SqlTokenizer::Class => self.class_state(c),
 			SqlTokenizer::Punct => self.punct_state(c),
+			_ => false,
 		}
 	}
 
@@ -368,4 +555,50 @@ static LANGUAGE: &str = "Rust";"#,
 			],
 		);
 	}
+
+	#[test]
+	fn test_tokenize_words() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS words FILTERS lowercase",
+			"The quick (“brown”) fox can’t jump 32.3 feet, right? Contact: jane.doe@example.com",
+			&[
+				"the",
+				"quick",
+				"brown",
+				"fox",
+				"can’t",
+				"jump",
+				"32.3",
+				"feet",
+				"right",
+				"contact",
+				"jane.doe",
+				"example.com",
+			],
+		);
+	}
+
+	#[test]
+	fn test_tokenize_regex() {
+		test_analyzer(
+			r"DEFINE ANALYZER test TOKENIZERS regex('[\\w.+-]+@[\\w-]+(\\.[\\w-]+)+|[A-Z]{2,}-\\d+') FILTERS lowercase",
+			"Order SKU-1234 and ABC-99 shipped, contact jane.doe@example.com or sales@shop.co.uk",
+			&["sku-1234", "abc-99", "jane.doe@example.com", "sales@shop.co.uk"],
+		);
+	}
+
+	#[test]
+	fn test_tokenize_cjk() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS cjk,blank FILTERS lowercase",
+			"東京都に住む Tokyo 한국어 日",
+			&["東京", "京都", "都に", "に住", "住む", "tokyo", "한국", "국어", "日"],
+		);
+		// Without any other tokenizer, non CJK characters are still split on invalid characters
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS cjk",
+			"北京 Beijing。",
+			&["北京", "Beijing"],
+		);
+	}
 }
@@ -0,0 +1,95 @@
+use crate::err::Error;
+use crate::idx::ft::docids::DocId;
+use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::terms::TermId;
+use crate::idx::{IndexKeyBase, SerdeState};
+use crate::kvs::Transaction;
+use serde::{Deserialize, Serialize};
+
+/// The frequency of a term in each field (column) of a document
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(super) struct FieldsFrequencies(pub(super) Vec<TermFrequency>);
+
+impl SerdeState for FieldsFrequencies {}
+
+pub(super) struct Fields {
+	index_key_base: IndexKeyBase,
+}
+
+impl Fields {
+	pub(super) fn new(index_key_base: IndexKeyBase) -> Self {
+		Self {
+			index_key_base,
+		}
+	}
+
+	pub(super) async fn set_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+		frequencies: &FieldsFrequencies,
+	) -> Result<(), Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		tx.set(key, frequencies.try_to_val()?).await?;
+		Ok(())
+	}
+
+	pub(super) async fn get_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+	) -> Result<Option<FieldsFrequencies>, Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		if let Some(val) = tx.get(key).await? {
+			Ok(Some(FieldsFrequencies::try_from_val(val)?))
+		} else {
+			Ok(None)
+		}
+	}
+
+	pub(super) async fn remove_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+	) -> Result<(), Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		tx.del(key).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::fields::{Fields, FieldsFrequencies};
+	use crate::idx::IndexKeyBase;
+	use crate::kvs::Datastore;
+
+	#[tokio::test]
+	async fn test_fields_frequencies() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let f = Fields::new(IndexKeyBase::default());
+
+		// Set the frequencies of two terms
+		f.set_frequencies(&mut tx, 1, 2, &FieldsFrequencies(vec![1, 0])).await.unwrap();
+		f.set_frequencies(&mut tx, 1, 3, &FieldsFrequencies(vec![2, 3])).await.unwrap();
+		assert_eq!(
+			f.get_frequencies(&mut tx, 1, 2).await.unwrap(),
+			Some(FieldsFrequencies(vec![1, 0]))
+		);
+		assert_eq!(
+			f.get_frequencies(&mut tx, 1, 3).await.unwrap(),
+			Some(FieldsFrequencies(vec![2, 3]))
+		);
+
+		// Remove one of them
+		f.remove_frequencies(&mut tx, 1, 2).await.unwrap();
+		assert_eq!(f.get_frequencies(&mut tx, 1, 2).await.unwrap(), None);
+		assert_eq!(
+			f.get_frequencies(&mut tx, 1, 3).await.unwrap(),
+			Some(FieldsFrequencies(vec![2, 3]))
+		);
+	}
+}
@@ -0,0 +1,112 @@
+use fst::Automaton;
+
+/// An automaton accepting the terms within a maximum Levenshtein distance of
+/// a given term. The distance is computed on characters rather than bytes.
+pub(super) struct Levenshtein {
+	chars: Vec<char>,
+	distance: u32,
+}
+
+impl Levenshtein {
+	pub(super) fn new(term: &str, distance: u32) -> Self {
+		Self {
+			chars: term.chars().collect(),
+			distance,
+		}
+	}
+}
+
+pub(super) struct LevenshteinState {
+	/// The distances between the characters read so far and each prefix of the term
+	row: Vec<u32>,
+	/// The first bytes of a UTF-8 character which is not complete yet
+	pending: Vec<u8>,
+}
+
+impl Automaton for Levenshtein {
+	type State = LevenshteinState;
+
+	fn start(&self) -> Self::State {
+		LevenshteinState {
+			row: (0..=self.chars.len() as u32).collect(),
+			pending: vec![],
+		}
+	}
+
+	fn is_match(&self, state: &Self::State) -> bool {
+		state.pending.is_empty() && state.row.last().map_or(false, |d| *d <= self.distance)
+	}
+
+	fn can_match(&self, state: &Self::State) -> bool {
+		state.row.iter().min().map_or(false, |d| *d <= self.distance)
+	}
+
+	fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+		let mut pending = state.pending.clone();
+		pending.push(byte);
+		let c = match std::str::from_utf8(&pending) {
+			Ok(s) => s.chars().next(),
+			// Wait for the next bytes of the character
+			Err(e) if e.error_len().is_none() => {
+				return LevenshteinState {
+					row: state.row.clone(),
+					pending,
+				};
+			}
+			Err(_) => None,
+		};
+		let row = match c {
+			Some(c) => {
+				let mut row = Vec::with_capacity(state.row.len());
+				row.push(state.row[0] + 1);
+				for (i, tc) in self.chars.iter().enumerate() {
+					let cost = if *tc == c {
+						0
+					} else {
+						1
+					};
+					let d = (state.row[i + 1] + 1).min(row[i] + 1).min(state.row[i] + cost);
+					row.push(d);
+				}
+				row
+			}
+			// An invalid UTF-8 sequence can't match
+			None => vec![self.distance + 1; state.row.len()],
+		};
+		LevenshteinState {
+			row,
+			pending: vec![],
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::fuzzy::Levenshtein;
+	use fst::{IntoStreamer, Set};
+
+	fn search(terms: &[&str], term: &str, distance: u32) -> Vec<String> {
+		let mut terms = terms.to_vec();
+		terms.sort();
+		let set = Set::from_iter(terms).unwrap();
+		set.search(Levenshtein::new(term, distance)).into_stream().into_strs().unwrap()
+	}
+
+	#[test]
+	fn test_levenshtein() {
+		let terms = ["car", "card", "care", "cart", "cat", "coat", "crate", "dog", "scar"];
+		assert_eq!(search(&terms, "car", 0), vec!["car"]);
+		assert_eq!(search(&terms, "car", 1), vec!["car", "card", "care", "cart", "cat", "scar"]);
+		assert_eq!(
+			search(&terms, "cart", 2),
+			vec!["car", "card", "care", "cart", "cat", "coat", "scar"]
+		);
+		assert_eq!(search(&terms, "dgo", 1), Vec::<String>::new());
+		assert_eq!(search(&terms, "dgo", 2), vec!["dog"]);
+		// The distance is computed on characters
+		let terms = ["café", "cafés", "cafe"];
+		assert_eq!(search(&terms, "cafe", 1), vec!["cafe", "café"]);
+		assert_eq!(search(&terms, "cafés", 1), vec!["café", "cafés"]);
+		assert_eq!(search(&terms, "caf", 1), vec!["cafe", "café"]);
+	}
+}
@@ -11,17 +11,34 @@ pub(super) struct Highlighter {
 	prefix: Vec<char>,
 	suffix: Vec<char>,
 	fields: Vec<(Idiom, Value)>,
+	/// The index of the first value of the highlighted field
+	base: u32,
 	offseter: Offseter,
 }
 
 impl Highlighter {
-	pub(super) fn new(prefix: Value, suffix: Value, idiom: &Idiom, doc: &Value) -> Self {
+	pub(super) fn new(
+		prefix: Value,
+		suffix: Value,
+		idiom: &Idiom,
+		cols: &[Idiom],
+		doc: &Value,
+	) -> Self {
 		let prefix = prefix.to_raw_string().chars().collect();
 		let suffix = suffix.to_raw_string().chars().collect();
 		// Extract the fields we want to highlight
 		let fields = doc.walk(idiom);
+		// The offsets are indexed across the values of every column of the index,
+		// so we skip the values of the columns preceding the highlighted one
+		let mut vals = vec![];
+		for col in cols {
+			for (_, v) in doc.walk(col) {
+				Self::extract(v, &mut vals);
+			}
+		}
 		Self {
 			fields,
+			base: vals.len() as u32,
 			prefix,
 			suffix,
 			offseter: Offseter::default(),
@@ -60,7 +77,7 @@ impl TryFrom<Highlighter> for Value {
 		}
 		let mut res = Vec::with_capacity(vals.len());
 		for (idx, val) in vals.into_iter().enumerate() {
-			if let Some(m) = hl.offseter.offsets.get(&(idx as u32)) {
+			if let Some(m) = hl.offseter.offsets.get(&(hl.base + idx as u32)) {
 				let mut v: Vec<char> = val.chars().collect();
 				let mut l = v.len();
 				let mut d = 0;
@@ -80,9 +97,18 @@ impl TryFrom<Highlighter> for Value {
 					Ok(())
 				};
 
+				// Overlapping offsets (e.g. bigrams) are highlighted as a single range
+				let mut ranges: Vec<(Position, Position)> = Vec::with_capacity(m.len());
 				for (s, e) in m {
-					append(*s, &hl.prefix)?;
-					append(*e, &hl.suffix)?;
+					match ranges.last_mut() {
+						Some((_, le)) if *s < *le => *le = (*le).max(*e),
+						_ => ranges.push((*s, *e)),
+					}
+				}
+
+				for (s, e) in ranges {
+					append(s, &hl.prefix)?;
+					append(e, &hl.suffix)?;
 				}
 
 				let s: String = v.iter().collect();
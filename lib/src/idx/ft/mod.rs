@@ -1,32 +1,39 @@
 pub(crate) mod analyzer;
 pub(crate) mod docids;
 mod doclength;
+mod fields;
+mod fuzzy;
 mod highlighter;
 mod offsets;
 mod postings;
+pub(super) mod query;
 pub(super) mod scorer;
 pub(super) mod termdocs;
 pub(crate) mod terms;
+pub(crate) mod tophits;
 
 use crate::err::Error;
 use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::docids::{DocId, DocIds};
 use crate::idx::ft::doclength::DocLengths;
+use crate::idx::ft::fields::Fields;
 use crate::idx::ft::highlighter::{Highlighter, Offseter};
 use crate::idx::ft::offsets::Offsets;
 use crate::idx::ft::postings::Postings;
+use crate::idx::ft::query::{Leaf, LeafTerms, MatchQuery};
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermDocs;
 use crate::idx::ft::terms::{TermId, Terms};
+use crate::idx::ft::tophits::TopHitsIterator;
 use crate::idx::{btree, IndexKeyBase, SerdeState};
 use crate::kvs::{Key, Transaction};
+use crate::sql::index::Weights;
 use crate::sql::scoring::Scoring;
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::{Array, Idiom, Object, Thing, Value};
 use roaring::treemap::IntoIter;
 use roaring::RoaringTreemap;
 use serde::{Deserialize, Serialize};
-use std::ops::BitAnd;
 use std::sync::Arc;
 
 pub(crate) type MatchRef = u8;
@@ -39,6 +46,7 @@ pub(crate) struct FtIndex {
 	bm25: Option<Bm25Params>,
 	order: u32,
 	highlighting: bool,
+	weights: Option<Vec<f32>>,
 }
 
 #[derive(Clone)]
@@ -90,6 +98,7 @@ impl FtIndex {
 		order: u32,
 		scoring: &Scoring,
 		hl: bool,
+		wt: Option<&Weights>,
 	) -> Result<Self, Error> {
 		let state_key: Key = index_key_base.new_bs_key();
 		let state: State = if let Some(val) = tx.get(state_key.clone()).await? {
@@ -115,7 +124,8 @@ impl FtIndex {
 			bm25,
 			order,
 			highlighting: hl,
-			analyzer: az.into(),
+			weights: wt.map(|wt| wt.0.clone()),
+			analyzer: az.try_into()?,
 		})
 	}
 
@@ -143,6 +153,10 @@ impl FtIndex {
 		Offsets::new(self.index_key_base.clone())
 	}
 
+	fn fields(&self) -> Fields {
+		Fields::new(self.index_key_base.clone())
+	}
+
 	pub(crate) async fn remove_document(
 		&mut self,
 		tx: &mut Transaction,
@@ -178,11 +192,20 @@ impl FtIndex {
 				// Remove the offsets if any
 				if self.highlighting {
 					let o = self.offsets();
-					for term_id in term_list {
+					for term_id in &term_list {
 						// TODO?: Removal can be done with a prefix on doc_id
 						o.remove_offsets(tx, doc_id, term_id).await?;
 					}
 				}
+				// Remove the fields frequencies if any (they are stored for any term of the document)
+				let f = self.fields();
+				if let Some(term_id) = term_list.min() {
+					if f.get_frequencies(tx, doc_id, term_id).await?.is_some() {
+						for term_id in term_list {
+							f.remove_frequencies(tx, doc_id, term_id).await?;
+						}
+					}
+				}
 				t.finish(tx).await?;
 				p.finish(tx).await?;
 			}
@@ -244,6 +267,7 @@ impl FtIndex {
 				old_term_ids.remove(term_id);
 			}
 			term_docs.set_doc(tx, term_id, doc_id).await?;
+			term_docs.update_max_frequency(tx, term_id, term_freq).await?;
 			terms_ids.insert(term_id);
 		}
 
@@ -270,13 +294,29 @@ impl FtIndex {
 				}
 			}
 			// In case of an update, w remove the offset for the terms that does not exist anymore
-			if let Some(old_term_ids) = old_term_ids {
+			if let Some(old_term_ids) = &old_term_ids {
 				for old_term_id in old_term_ids {
 					o.remove_offsets(tx, doc_id, old_term_id).await?;
 				}
 			}
 		}
 
+		// The frequencies per field are required to weight the fields, and to match a single field
+		if self.weights.is_some() || field_content.len() > 1 {
+			let f = self.fields();
+			// Set the frequencies of the terms within each field
+			for (tid, ff) in self.analyzer.extract_fields_frequencies(&t, tx, field_content).await?
+			{
+				f.set_frequencies(tx, doc_id, tid, &ff).await?;
+			}
+			// In case of an update, we remove the frequencies of the terms that does not exist anymore
+			if let Some(old_term_ids) = &old_term_ids {
+				for old_term_id in old_term_ids {
+					f.remove_frequencies(tx, doc_id, old_term_id).await?;
+				}
+			}
+		}
+
 		// Stores the term list for this doc_id
 		tx.set(term_ids_key, terms_ids.try_to_val()?).await?;
 
@@ -299,10 +339,37 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		query_string: String,
-	) -> Result<Vec<Option<TermId>>, Error> {
+	) -> Result<MatchQuery, Error> {
 		let t = self.terms(tx).await?;
-		let terms = self.analyzer.extract_terms(&t, tx, query_string).await?;
-		Ok(terms)
+		let expr = query::parse(&query_string);
+		let mut leaves = Vec::new();
+		if let Some(expr) = &expr {
+			expr.leaves(&mut leaves);
+		}
+		let mut terms = Vec::with_capacity(leaves.len());
+		for leaf in leaves {
+			terms.push(match leaf {
+				Leaf::Word(s) => {
+					LeafTerms::Word(self.analyzer.extract_terms(&t, tx, s.to_string()).await?)
+				}
+				Leaf::Expanded(s, e) => {
+					match self.analyzer.extract_expanded_terms(&t, tx, s.to_string(), e).await? {
+						Some((terms, expansions)) => LeafTerms::Expanded(terms, expansions),
+						None => LeafTerms::Word(vec![]),
+					}
+				}
+				Leaf::Phrase(s, slop) => LeafTerms::Phrase(
+					self.analyzer.extract_phrase(&t, tx, s.to_string()).await?,
+					*slop,
+				),
+			});
+		}
+		let query = MatchQuery::new(expr.as_ref(), terms, self.offsets());
+		// Phrases are matched using the offsets of the terms
+		if query.has_phrases() && !self.highlighting {
+			return Err(Error::PhraseWithoutHighlights);
+		}
+		Ok(query)
 	}
 
 	pub(super) async fn get_terms_docs(
@@ -327,27 +394,68 @@ impl FtIndex {
 		Ok(terms_docs)
 	}
 
-	pub(super) async fn new_hits_iterator(
+	/// Only keeps the documents containing each term within the given field (column)
+	pub(super) async fn get_field_terms_docs(
 		&self,
 		tx: &mut Transaction,
-		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
-	) -> Result<Option<HitsIterator>, Error> {
-		let mut hits: Option<RoaringTreemap> = None;
-		for opt_term_docs in terms_docs.iter() {
-			if let Some((_, term_docs)) = opt_term_docs {
-				if let Some(h) = hits {
-					hits = Some(h.bitand(term_docs));
-				} else {
-					hits = Some(term_docs.clone());
+		terms_docs: Vec<Option<(TermId, RoaringTreemap)>>,
+		col: usize,
+	) -> Result<Vec<Option<(TermId, RoaringTreemap)>>, Error> {
+		let f = self.fields();
+		let mut res = Vec::with_capacity(terms_docs.len());
+		for opt_td in terms_docs {
+			if let Some((term_id, docs)) = opt_td {
+				let mut field_docs = RoaringTreemap::new();
+				for doc_id in docs {
+					if let Some(ff) = f.get_frequencies(tx, doc_id, term_id).await? {
+						if ff.0.get(col).copied().unwrap_or(0) > 0 {
+							field_docs.insert(doc_id);
+						}
+					}
 				}
+				res.push(Some((term_id, field_docs)));
 			} else {
-				return Ok(None);
+				res.push(None);
 			}
 		}
-		if let Some(hits) = hits {
-			if !hits.is_empty() {
+		Ok(res)
+	}
+
+	pub(super) async fn new_hits_iterator(
+		&self,
+		tx: &mut Transaction,
+		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+		query: MatchQuery,
+	) -> Result<Option<HitsIterator>, Error> {
+		let hits = query.candidates(&terms_docs);
+		if !hits.is_empty() {
+			let doc_ids = self.doc_ids(tx).await?;
+			return Ok(Some(HitsIterator::new(doc_ids, hits, terms_docs, query)));
+		}
+		Ok(None)
+	}
+
+	/// Returns the hits by descending score, when the first `k` hits are expected
+	pub(super) async fn new_top_hits_iterator(
+		&self,
+		tx: &mut Transaction,
+		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+		query: MatchQuery,
+		k: usize,
+	) -> Result<Option<TopHitsIterator>, Error> {
+		let hits = query.candidates(&terms_docs);
+		if !hits.is_empty() {
+			if let Some(scorer) = self.new_scorer(tx, terms_docs.clone(), query.clone()).await? {
 				let doc_ids = self.doc_ids(tx).await?;
-				return Ok(Some(HitsIterator::new(doc_ids, hits)));
+				return Ok(Some(TopHitsIterator::new(
+					doc_ids,
+					self.term_docs(),
+					scorer,
+					hits,
+					terms_docs,
+					query,
+					k,
+				)));
 			}
 		}
 		Ok(None)
@@ -357,15 +465,18 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+		query: MatchQuery,
 	) -> Result<Option<BM25Scorer>, Error> {
 		if let Some(bm25) = &self.bm25 {
 			return Ok(Some(BM25Scorer::new(
 				self.postings(tx).await?,
 				terms_docs,
+				query,
 				self.doc_lengths(tx).await?,
 				self.state.total_docs_lengths,
 				self.state.doc_count,
 				bm25.clone(),
+				self.weights.clone().map(|w| (self.fields(), w)),
 			)));
 		}
 		Ok(None)
@@ -380,13 +491,14 @@ impl FtIndex {
 		prefix: Value,
 		suffix: Value,
 		idiom: &Idiom,
+		cols: &[Idiom],
 		doc: &Value,
 	) -> Result<Value, Error> {
 		let doc_key: Key = thg.into();
 		let doc_ids = self.doc_ids(tx).await?;
 		if let Some(doc_id) = doc_ids.get_doc_id(tx, doc_key).await? {
 			let o = self.offsets();
-			let mut hl = Highlighter::new(prefix, suffix, idiom, doc);
+			let mut hl = Highlighter::new(prefix, suffix, idiom, cols, doc);
 			for term_id in terms.iter().flatten() {
 				let o = o.get_offsets(tx, doc_id, *term_id).await?;
 				if let Some(o) = o {
@@ -434,13 +546,22 @@ impl FtIndex {
 pub(crate) struct HitsIterator {
 	doc_ids: DocIds,
 	iter: IntoIter,
+	terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+	query: MatchQuery,
 }
 
 impl HitsIterator {
-	fn new(doc_ids: DocIds, hits: RoaringTreemap) -> Self {
+	fn new(
+		doc_ids: DocIds,
+		hits: RoaringTreemap,
+		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+		query: MatchQuery,
+	) -> Self {
 		Self {
 			doc_ids,
 			iter: hits.into_iter(),
+			terms_docs,
+			query,
 		}
 	}
 
@@ -449,6 +570,10 @@ impl HitsIterator {
 		tx: &mut Transaction,
 	) -> Result<Option<(Thing, DocId)>, Error> {
 		for doc_id in self.iter.by_ref() {
+			// The candidates are checked against the phrases
+			if !self.query.is_exact() && !self.query.matches(tx, &self.terms_docs, doc_id).await? {
+				continue;
+			}
 			if let Some(doc_key) = self.doc_ids.get_doc_key(tx, doc_id).await? {
 				return Ok(Some((doc_key.into(), doc_id)));
 			}
@@ -459,6 +584,7 @@ impl HitsIterator {
 
 #[cfg(test)]
 mod tests {
+	use crate::idx::ft::docids::DocId;
 	use crate::idx::ft::scorer::{BM25Scorer, Score};
 	use crate::idx::ft::{FtIndex, HitsIterator};
 	use crate::idx::IndexKeyBase;
@@ -496,10 +622,10 @@ mod tests {
 		fti: &FtIndex,
 		qs: &str,
 	) -> (Option<HitsIterator>, BM25Scorer) {
-		let t = fti.extract_terms(tx, qs.to_string()).await.unwrap();
-		let td = Arc::new(fti.get_terms_docs(tx, &t).await.unwrap());
-		let scr = fti.new_scorer(tx, td.clone()).await.unwrap().unwrap();
-		let hits = fti.new_hits_iterator(tx, td).await.unwrap();
+		let q = fti.extract_terms(tx, qs.to_string()).await.unwrap();
+		let td = Arc::new(fti.get_terms_docs(tx, &q.terms()).await.unwrap());
+		let scr = fti.new_scorer(tx, td.clone(), q.clone()).await.unwrap().unwrap();
+		let hits = fti.new_hits_iterator(tx, td, q).await.unwrap();
 		(hits, scr)
 	}
 
@@ -524,6 +650,7 @@ mod tests {
 				default_btree_order,
 				&Scoring::bm25(),
 				false,
+				None,
 			)
 			.await
 			.unwrap();
@@ -543,6 +670,7 @@ mod tests {
 				default_btree_order,
 				&Scoring::bm25(),
 				false,
+				None,
 			)
 			.await
 			.unwrap();
@@ -560,6 +688,7 @@ mod tests {
 				default_btree_order,
 				&Scoring::bm25(),
 				false,
+				None,
 			)
 			.await
 			.unwrap();
@@ -601,6 +730,7 @@ mod tests {
 				default_btree_order,
 				&Scoring::bm25(),
 				false,
+				None,
 			)
 			.await
 			.unwrap();
@@ -631,6 +761,7 @@ mod tests {
 				default_btree_order,
 				&Scoring::bm25(),
 				false,
+				None,
 			)
 			.await
 			.unwrap();
@@ -672,6 +803,7 @@ mod tests {
 					default_btree_order,
 					&Scoring::bm25(),
 					hl,
+					None,
 				)
 				.await
 				.unwrap();
@@ -715,6 +847,7 @@ mod tests {
 					default_btree_order,
 					&Scoring::bm25(),
 					hl,
+					None,
 				)
 				.await
 				.unwrap();
@@ -781,4 +914,230 @@ mod tests {
 	async fn test_ft_index_bm_25_with_highlighting() {
 		test_ft_index_bm_25(true).await;
 	}
+
+	async fn check_phrase_hits(
+		tx: &mut Transaction,
+		fti: &FtIndex,
+		qs: &str,
+		mut e: Vec<&Thing>,
+	) -> (HashMap<Thing, DocId>, BM25Scorer) {
+		let (hits, scr) = search(tx, fti, qs).await;
+		let mut map = HashMap::new();
+		if let Some(mut hits) = hits {
+			while let Some((k, d)) = hits.next(tx).await.unwrap() {
+				map.insert(k, d);
+			}
+		}
+		let mut res: Vec<&Thing> = map.keys().collect();
+		res.sort();
+		e.sort();
+		assert_eq!(res, e, "{qs}");
+		(map, scr)
+	}
+
+	#[test(tokio::test)]
+	async fn test_ft_index_phrases() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let (_, az) = analyzer("DEFINE ANALYZER test TOKENIZERS blank;").unwrap();
+
+		let doc1: Thing = ("t", "doc1").into();
+		let doc2: Thing = ("t", "doc2").into();
+		let doc3: Thing = ("t", "doc3").into();
+
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut fti = FtIndex::new(
+			&mut tx,
+			az.clone(),
+			IndexKeyBase::default(),
+			5,
+			&Scoring::bm25(),
+			true,
+			None,
+		)
+		.await
+		.unwrap();
+		fti.index_document(
+			&mut tx,
+			&doc1,
+			&Array::from(vec!["the quick brown fox jumped over the lazy dog"]),
+		)
+		.await
+		.unwrap();
+		fti.index_document(
+			&mut tx,
+			&doc2,
+			&Array::from(vec!["the fast fox jumped over the lazy dog"]),
+		)
+		.await
+		.unwrap();
+		fti.index_document(&mut tx, &doc3, &Array::from(vec!["the dog", "lazy and quiet"]))
+			.await
+			.unwrap();
+
+		check_phrase_hits(&mut tx, &fti, r#""lazy dog""#, vec![&doc1, &doc2]).await;
+		check_phrase_hits(&mut tx, &fti, r#""quick fox""#, vec![]).await;
+		check_phrase_hits(&mut tx, &fti, r#""quick fox"~1"#, vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, r#""fox quick"~5"#, vec![]).await;
+		check_phrase_hits(&mut tx, &fti, r#""fox jumped" fast"#, vec![&doc2]).await;
+		check_phrase_hits(&mut tx, &fti, r#""the dog""#, vec![&doc3]).await;
+		// The terms of a phrase must be in the same value
+		check_phrase_hits(&mut tx, &fti, r#""dog lazy"~5"#, vec![]).await;
+		check_phrase_hits(&mut tx, &fti, r#""lazy cat""#, vec![]).await;
+
+		// A phrase occurring once scores like its terms
+		let (map, scr) =
+			check_phrase_hits(&mut tx, &fti, r#""lazy dog""#, vec![&doc1, &doc2]).await;
+		let (_, scr2) = search(&mut tx, &fti, "lazy dog").await;
+		let d = map[&doc1];
+		assert_eq!(scr.score(&mut tx, d).await.unwrap(), scr2.score(&mut tx, d).await.unwrap());
+		// A document which does not contain the phrase does not score
+		let (map, _) = check_phrase_hits(&mut tx, &fti, "dog", vec![&doc1, &doc2, &doc3]).await;
+		assert_eq!(scr.score(&mut tx, map[&doc3]).await.unwrap(), Some(0.0));
+
+		// Phrases require the offsets
+		let fti =
+			FtIndex::new(&mut tx, az, IndexKeyBase::default(), 5, &Scoring::bm25(), false, None)
+				.await
+				.unwrap();
+		assert!(fti.extract_terms(&mut tx, r#""lazy dog""#.to_string()).await.is_err());
+		tx.cancel().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_ft_index_boolean_queries() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let (_, az) = analyzer("DEFINE ANALYZER test TOKENIZERS blank;").unwrap();
+
+		let doc1: Thing = ("t", "doc1").into();
+		let doc2: Thing = ("t", "doc2").into();
+		let doc3: Thing = ("t", "doc3").into();
+
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut fti =
+			FtIndex::new(&mut tx, az, IndexKeyBase::default(), 5, &Scoring::bm25(), true, None)
+				.await
+				.unwrap();
+		fti.index_document(&mut tx, &doc1, &Array::from(vec!["new laptop with a fast cpu"]))
+			.await
+			.unwrap();
+		fti.index_document(&mut tx, &doc2, &Array::from(vec!["refurbished laptop"])).await.unwrap();
+		fti.index_document(&mut tx, &doc3, &Array::from(vec!["new tablet with a slow cpu"]))
+			.await
+			.unwrap();
+
+		check_phrase_hits(&mut tx, &fti, "laptop -refurbished", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "+laptop -refurbished", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "laptop OR tablet", vec![&doc1, &doc2, &doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "new (laptop OR tablet) -slow", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "refurbished OR new -fast", vec![&doc2, &doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "(laptop OR dummy) new", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "laptop dummy OR tablet", vec![&doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "cpu -\"fast cpu\"", vec![&doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "\"slow cpu\" OR refurbished", vec![&doc2, &doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "laptop -dummy", vec![&doc1, &doc2]).await;
+		// A query with only exclusions doesn't match anything
+		let (hits, _) = search(&mut tx, &fti, "-laptop").await;
+		assert!(hits.is_none());
+
+		// Boosts multiply the score of the terms, excluded terms don't score
+		let (map, scr) = check_phrase_hits(&mut tx, &fti, "fast", vec![&doc1]).await;
+		let (_, scr2) = search(&mut tx, &fti, "fast^2 -slow").await;
+		let d = map[&doc1];
+		let s = scr.score(&mut tx, d).await.unwrap().unwrap();
+		assert_eq!(scr2.score(&mut tx, d).await.unwrap(), Some(s * 2.0));
+		tx.cancel().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_ft_index_prefix_and_fuzzy_queries() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let (_, az) = analyzer("DEFINE ANALYZER test TOKENIZERS blank FILTERS lowercase;").unwrap();
+
+		let doc1: Thing = ("t", "doc1").into();
+		let doc2: Thing = ("t", "doc2").into();
+		let doc3: Thing = ("t", "doc3").into();
+
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut fti =
+			FtIndex::new(&mut tx, az, IndexKeyBase::default(), 5, &Scoring::bm25(), false, None)
+				.await
+				.unwrap();
+		fti.index_document(&mut tx, &doc1, &Array::from(vec!["a new laptop"])).await.unwrap();
+		fti.index_document(&mut tx, &doc2, &Array::from(vec!["two refurbished laptops"]))
+			.await
+			.unwrap();
+		fti.index_document(&mut tx, &doc3, &Array::from(vec!["a desk lamp"])).await.unwrap();
+
+		check_phrase_hits(&mut tx, &fti, "lap*", vec![&doc1, &doc2]).await;
+		check_phrase_hits(&mut tx, &fti, "LA*", vec![&doc1, &doc2, &doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "laptop*", vec![&doc1, &doc2]).await;
+		check_phrase_hits(&mut tx, &fti, "laptops*", vec![&doc2]).await;
+		check_phrase_hits(&mut tx, &fti, "lap* -refurb*", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "lamp~1", vec![&doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "laptp~", vec![&doc1]).await;
+		check_phrase_hits(&mut tx, &fti, "laptp~2", vec![&doc1, &doc2, &doc3]).await;
+		check_phrase_hits(&mut tx, &fti, "refurbsihed~2 laptop*", vec![&doc2]).await;
+		let (hits, _) = search(&mut tx, &fti, "zz*").await;
+		assert!(hits.is_none());
+		let (hits, _) = search(&mut tx, &fti, "lap* zz~1").await;
+		assert!(hits.is_none());
+		tx.cancel().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_ft_index_top_hits() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let (_, az) = analyzer("DEFINE ANALYZER test TOKENIZERS blank;").unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut fti = FtIndex::new(
+			&mut tx,
+			az.clone(),
+			IndexKeyBase::default(),
+			5,
+			&Scoring::bm25(),
+			false,
+			None,
+		)
+		.await
+		.unwrap();
+		// Documents with various term frequencies and lengths
+		for i in 0..40 {
+			let mut words = vec!["alpha"; i % 5 + 1];
+			if i % 3 == 0 {
+				words.extend(vec!["beta"; i % 4 + 1]);
+			}
+			words.extend(vec!["filler"; i % 7]);
+			let doc: Thing = ("t", format!("doc{i}").as_str()).into();
+			fti.index_document(&mut tx, &doc, &Array::from(vec![words.join(" ")])).await.unwrap();
+		}
+
+		for qs in ["alpha beta", "beta", "alpha -beta", "alpha^2 beta"] {
+			let q = fti.extract_terms(&mut tx, qs.to_string()).await.unwrap();
+			let td = Arc::new(fti.get_terms_docs(&mut tx, &q.terms()).await.unwrap());
+			// Score and sort every hit
+			let scr = fti.new_scorer(&mut tx, td.clone(), q.clone()).await.unwrap().unwrap();
+			let mut hits = fti.new_hits_iterator(&mut tx, td.clone(), q.clone()).await.unwrap();
+			let mut expected = vec![];
+			while let Some((thg, doc_id)) = hits.as_mut().unwrap().next(&mut tx).await.unwrap() {
+				let score = scr.score(&mut tx, doc_id).await.unwrap().unwrap();
+				expected.push((score, doc_id, thg));
+			}
+			expected.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+			let expected: Vec<Thing> = expected.into_iter().map(|(_, _, t)| t).collect();
+			for k in [1, 3, 10, 50] {
+				let mut top = fti
+					.new_top_hits_iterator(&mut tx, td.clone(), q.clone(), k)
+					.await
+					.unwrap()
+					.unwrap();
+				// The first hits are the best ones, and the iterator carries on with the others
+				let mut res = vec![];
+				while let Some((thg, _)) = top.next(&mut tx).await.unwrap() {
+					res.push(thg);
+				}
+				assert_eq!(res, expected, "{qs} - {k}");
+			}
+		}
+		tx.cancel().await.unwrap();
+	}
 }
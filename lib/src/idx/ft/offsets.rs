@@ -6,6 +6,10 @@ use crate::kvs::{Transaction, Val};
 
 pub(super) type Position = u32;
 
+/// The position of a term which was indexed before positions were recorded
+pub(super) const NO_POSITION: Position = Position::MAX;
+
+#[derive(Clone)]
 pub(super) struct Offsets {
 	index_key_base: IndexKeyBase,
 }
@@ -61,14 +65,17 @@ pub(super) struct Offset {
 	pub(super) index: u32,
 	pub(super) start: Position,
 	pub(super) end: Position,
+	/// The rank of the term within the value
+	pub(super) position: Position,
 }
 
 impl Offset {
-	pub(super) fn new(index: u32, start: Position, end: Position) -> Self {
+	pub(super) fn new(index: u32, start: Position, end: Position, position: Position) -> Self {
 		Self {
 			index,
 			start,
 			end,
+			position,
 		}
 	}
 }
@@ -96,6 +103,10 @@ impl TryFrom<OffsetRecords> for Val {
 			decompressed.push(o.start);
 			decompressed.push(o.end);
 		}
+		// `positions` are ascending
+		for o in &offsets.0 {
+			decompressed.push(o.position);
+		}
 		Ok(bincode::serialize(&decompressed)?)
 	}
 }
@@ -119,7 +130,13 @@ impl TryFrom<Val> for OffsetRecords {
 		for index in indexes {
 			let start = *iter.next().ok_or(Error::CorruptedIndex)?;
 			let end = *iter.next().ok_or(Error::CorruptedIndex)?;
-			res.push(Offset::new(index, start, end));
+			res.push(Offset::new(index, start, end, NO_POSITION));
+		}
+		// Offsets which were stored before positions were recorded don't have them
+		if iter.len() > 0 {
+			for o in &mut res {
+				o.position = *iter.next().ok_or(Error::CorruptedIndex)?;
+			}
 		}
 		Ok(OffsetRecords(res))
 	}
@@ -127,15 +144,31 @@ impl TryFrom<Val> for OffsetRecords {
 
 #[cfg(test)]
 mod tests {
-	use crate::idx::ft::offsets::{Offset, OffsetRecords};
+	use crate::idx::ft::offsets::{Offset, OffsetRecords, NO_POSITION};
 	use crate::kvs::Val;
 
 	#[test]
 	fn test_offset_records() {
-		let o =
-			OffsetRecords(vec![Offset::new(0, 1, 2), Offset::new(0, 11, 22), Offset::new(1, 3, 4)]);
+		let o = OffsetRecords(vec![
+			Offset::new(0, 1, 2, 0),
+			Offset::new(0, 11, 22, 3),
+			Offset::new(1, 3, 4, 0),
+		]);
 		let v: Val = o.clone().try_into().unwrap();
 		let o2 = v.try_into().unwrap();
 		assert_eq!(o, o2)
 	}
+
+	#[test]
+	fn test_offset_records_without_positions() {
+		let v: Val = bincode::serialize(&vec![2u32, 0, 0, 1, 2, 11, 22]).unwrap();
+		let o: OffsetRecords = v.try_into().unwrap();
+		assert_eq!(
+			o,
+			OffsetRecords(vec![
+				Offset::new(0, 1, 2, NO_POSITION),
+				Offset::new(0, 11, 22, NO_POSITION)
+			])
+		);
+	}
 }
@@ -0,0 +1,750 @@
+use crate::err::Error;
+use crate::idx::ft::docids::DocId;
+use crate::idx::ft::offsets::{Offset, Offsets, Position, NO_POSITION};
+use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::scorer::Score;
+use crate::idx::ft::terms::TermId;
+use crate::kvs::Transaction;
+use roaring::RoaringTreemap;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::sync::Arc;
+use std::vec::IntoIter;
+
+/// A leaf of the query string of a MATCHES expression
+#[derive(Debug, PartialEq)]
+pub(super) enum Leaf<'a> {
+	/// A word, every term of which must be present
+	Word(&'a str),
+	/// A word whose last term is expanded to the matching terms of the index
+	Expanded(&'a str, Expansion),
+	/// A quoted phrase, followed by the optional slop (`"quick fox"~3`)
+	Phrase(&'a str, u32),
+}
+
+/// The maximum edit distance of a fuzzy term
+const MAX_FUZZY_DISTANCE: u32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Expansion {
+	/// The terms starting with the word (`lap*`)
+	Prefix,
+	/// The terms within an edit distance of the word (`laptp~1`), which defaults to 1
+	Fuzzy(u32),
+}
+
+impl<'a> Leaf<'a> {
+	fn word(w: &'a str) -> Self {
+		if let Some(p) = w.strip_suffix('*').filter(|p| !p.is_empty()) {
+			return Leaf::Expanded(p, Expansion::Prefix);
+		}
+		if let Some((t, d)) = w.rsplit_once('~') {
+			if !t.is_empty() && d.chars().all(|c| c.is_ascii_digit()) {
+				let d = d.parse().unwrap_or(if d.is_empty() {
+					1
+				} else {
+					MAX_FUZZY_DISTANCE
+				});
+				return Leaf::Expanded(t, Expansion::Fuzzy(d.min(MAX_FUZZY_DISTANCE)));
+			}
+		}
+		Leaf::Word(w)
+	}
+}
+
+/// The query string of a MATCHES expression.
+///
+/// Clauses separated by blanks (or `AND`) must all match, `OR` binds less
+/// tightly than `AND`, `-clause` excludes the documents matching the clause,
+/// `+clause` is required (which is the default), clauses can be grouped with
+/// parentheses, and `clause^2` boosts the score of the terms of the clause.
+#[derive(Debug, PartialEq)]
+pub(super) enum Expr<'a> {
+	Leaf(Leaf<'a>, Score),
+	/// Every required clause must match, and none of the excluded clauses
+	And(Vec<Expr<'a>>, Vec<Expr<'a>>),
+	/// At least one of the clauses must match
+	Or(Vec<Expr<'a>>),
+}
+
+impl<'a> Expr<'a> {
+	fn boost(&mut self, boost: Score) {
+		match self {
+			Expr::Leaf(_, b) => *b *= boost,
+			Expr::And(r, e) => r.iter_mut().chain(e.iter_mut()).for_each(|c| c.boost(boost)),
+			Expr::Or(c) => c.iter_mut().for_each(|c| c.boost(boost)),
+		}
+	}
+
+	/// Collect the leaves, in order
+	pub(super) fn leaves<'b>(&'b self, res: &mut Vec<&'b Leaf<'a>>) {
+		match self {
+			Expr::Leaf(l, _) => res.push(l),
+			Expr::And(r, e) => r.iter().chain(e.iter()).for_each(|c| c.leaves(res)),
+			Expr::Or(c) => c.iter().for_each(|c| c.leaves(res)),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+	Open,
+	Close,
+	And,
+	Or,
+	Required,
+	Excluded,
+	Boost(Score),
+	Leaf(Leaf<'a>),
+}
+
+/// Split a query string into tokens. Unbalanced closing parentheses are ignored.
+fn lex(qs: &str) -> Vec<Token<'_>> {
+	let mut res = Vec::new();
+	let mut depth = 0;
+	let mut rest = qs.trim_start();
+	while let Some(c) = rest.chars().next() {
+		let tail = &rest[c.len_utf8()..];
+		rest = match c {
+			'(' => {
+				depth += 1;
+				res.push(Token::Open);
+				tail
+			}
+			')' => {
+				if depth > 0 {
+					depth -= 1;
+					res.push(Token::Close);
+				}
+				tail
+			}
+			'+' | '-' if tail.starts_with(|n: char| !n.is_whitespace()) => {
+				res.push(if c == '+' {
+					Token::Required
+				} else {
+					Token::Excluded
+				});
+				tail
+			}
+			'^' => {
+				let n = tail.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(tail.len());
+				if let Ok(boost) = tail[..n].parse() {
+					res.push(Token::Boost(boost));
+				}
+				&tail[n..]
+			}
+			'"' => {
+				// An unterminated phrase runs until the end of the query string
+				let (phrase, tail) = match tail.find('"') {
+					Some(end) => (&tail[..end], &tail[end + 1..]),
+					None => (tail, ""),
+				};
+				// Parse the optional slop
+				let (slop, tail) = match tail.strip_prefix('~') {
+					Some(tail) => {
+						let n = tail.find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len());
+						let slop = match n {
+							0 => 0,
+							n => tail[..n].parse().unwrap_or(u32::MAX),
+						};
+						(slop, &tail[n..])
+					}
+					None => (0, tail),
+				};
+				res.push(Token::Leaf(Leaf::Phrase(phrase, slop)));
+				tail
+			}
+			_ => {
+				let n = rest
+					.find(|c: char| c.is_whitespace() || "()\"^".contains(c))
+					.unwrap_or(rest.len());
+				res.push(match &rest[..n] {
+					"AND" => Token::And,
+					"OR" => Token::Or,
+					w => Token::Leaf(Leaf::word(w)),
+				});
+				&rest[n..]
+			}
+		}
+		.trim_start();
+	}
+	res
+}
+
+struct Parser<'a> {
+	tokens: Peekable<IntoIter<Token<'a>>>,
+}
+
+impl<'a> Parser<'a> {
+	fn parse_or(&mut self) -> Option<Expr<'a>> {
+		let mut clauses = Vec::new();
+		loop {
+			clauses.extend(self.parse_and());
+			if self.tokens.next_if_eq(&Token::Or).is_none() {
+				break;
+			}
+		}
+		if clauses.len() > 1 {
+			Some(Expr::Or(clauses))
+		} else {
+			clauses.pop()
+		}
+	}
+
+	fn parse_and(&mut self) -> Option<Expr<'a>> {
+		let mut required = Vec::new();
+		let mut excluded = Vec::new();
+		while let Some(t) = self.tokens.peek() {
+			match t {
+				Token::Or | Token::Close => break,
+				Token::And | Token::Boost(_) => {
+					self.tokens.next();
+				}
+				_ => match self.parse_unary() {
+					(Some(e), true) => excluded.push(e),
+					(Some(e), false) => required.push(e),
+					(None, _) => {}
+				},
+			}
+		}
+		if excluded.is_empty() && required.len() <= 1 {
+			required.pop()
+		} else {
+			Some(Expr::And(required, excluded))
+		}
+	}
+
+	/// Returns the clause, and whether it is excluded
+	fn parse_unary(&mut self) -> (Option<Expr<'a>>, bool) {
+		let mut excluded = false;
+		while let Some(t) = self.tokens.next_if(|t| matches!(t, Token::Required | Token::Excluded))
+		{
+			excluded = t == Token::Excluded;
+		}
+		let mut expr = match self.tokens.next_if(|t| matches!(t, Token::Open | Token::Leaf(_))) {
+			Some(Token::Open) => {
+				let e = self.parse_or();
+				self.tokens.next_if_eq(&Token::Close);
+				e
+			}
+			Some(Token::Leaf(l)) => Some(Expr::Leaf(l, 1.0)),
+			_ => None,
+		};
+		if let Some(Token::Boost(b)) = self.tokens.next_if(|t| matches!(t, Token::Boost(_))) {
+			if let Some(e) = &mut expr {
+				e.boost(b);
+			}
+		}
+		(expr, excluded)
+	}
+}
+
+/// Parse the query string of a MATCHES expression
+pub(super) fn parse(qs: &str) -> Option<Expr<'_>> {
+	Parser {
+		tokens: lex(qs).into_iter().peekable(),
+	}
+	.parse_or()
+}
+
+/// The terms of a leaf, as they are found in the index
+pub(super) enum LeafTerms {
+	Word(Vec<Option<TermId>>),
+	/// The terms of the word, and the expansions of its last term
+	Expanded(Vec<Option<TermId>>, Vec<TermId>),
+	Phrase(Vec<(Position, Option<TermId>)>, u32),
+}
+
+/// The terms of a phrase, along with their position relative to the first term
+#[derive(Debug)]
+struct Phrase {
+	terms: Vec<(Position, TermId)>,
+	slop: u32,
+}
+
+impl Phrase {
+	fn new(mut terms: Vec<(Position, TermId)>, slop: u32) -> Self {
+		if let Some(first) = terms.first().map(|(p, _)| *p) {
+			terms.iter_mut().for_each(|(p, _)| *p -= first);
+		}
+		Self {
+			terms,
+			slop,
+		}
+	}
+
+	/// Count the occurrences of the phrase, given the offsets of each of its terms.
+	/// The terms must appear in the order of the phrase, within the same value,
+	/// with at most `slop` additional positions between the first and the last term.
+	fn frequency(&self, offsets: &[&Vec<Offset>]) -> TermFrequency {
+		let mut count = 0;
+		for first in offsets[0].iter().filter(|o| o.position != NO_POSITION) {
+			let mut last = first.position;
+			let mut found = true;
+			for (w, next_offsets) in self.terms.windows(2).zip(offsets.iter().skip(1)) {
+				let gap = w[1].0 - w[0].0;
+				// Take the nearest following occurrence of the next term
+				let next = next_offsets
+					.iter()
+					.filter(|o| o.index == first.index && o.position != NO_POSITION)
+					.filter(|o| o.position >= last + gap)
+					.map(|o| o.position)
+					.min();
+				match next {
+					Some(p) => last = p,
+					None => {
+						found = false;
+						break;
+					}
+				}
+			}
+			let span = last - first.position;
+			if found && span - self.terms[self.terms.len() - 1].0 <= self.slop {
+				count += 1;
+			}
+		}
+		count
+	}
+}
+
+/// A term of the query
+pub(super) struct Slot {
+	pub(super) term: Option<TermId>,
+	/// The boost of the term, or `None` if the term is only excluded
+	pub(super) boost: Option<Score>,
+	/// Whether the term only appears within phrases
+	pub(super) phrase_only: bool,
+}
+
+/// The query expression, resolved against the terms of the index
+#[derive(Debug)]
+enum Node {
+	/// The index of a slot
+	Term(usize),
+	/// The index of a phrase, along with the slots of its terms
+	Phrase(usize, Vec<usize>),
+	And(Vec<Node>, Vec<Node>),
+	Or(Vec<Node>),
+}
+
+type TermsDocs = [Option<(TermId, RoaringTreemap)>];
+
+impl Node {
+	/// Whether the documents returned by `candidates` all match
+	fn is_exact(&self) -> bool {
+		match self {
+			Node::Term(_) => true,
+			Node::Phrase(..) => false,
+			Node::And(r, e) => r.iter().chain(e.iter()).all(|n| n.is_exact()),
+			Node::Or(c) => c.iter().all(|n| n.is_exact()),
+		}
+	}
+
+	fn docs(td: &TermsDocs, slot: usize) -> Option<&RoaringTreemap> {
+		td[slot].as_ref().map(|(_, docs)| docs)
+	}
+
+	fn intersection(mut it: impl Iterator<Item = RoaringTreemap>) -> RoaringTreemap {
+		let mut res = it.next().unwrap_or_default();
+		for docs in it {
+			res &= docs;
+		}
+		res
+	}
+
+	/// The documents which may match: phrases are only checked against their terms
+	fn candidates(&self, td: &TermsDocs) -> RoaringTreemap {
+		match self {
+			Node::Term(s) => Self::docs(td, *s).cloned().unwrap_or_default(),
+			Node::Phrase(_, slots) => Self::intersection(
+				slots.iter().map(|s| Self::docs(td, *s).cloned().unwrap_or_default()),
+			),
+			Node::And(r, e) => {
+				let mut res = Self::intersection(r.iter().map(|n| n.candidates(td)));
+				// Only the exact candidates can be excluded
+				for n in e.iter().filter(|n| n.is_exact()) {
+					res -= n.candidates(td);
+				}
+				res
+			}
+			Node::Or(c) => {
+				let mut res = RoaringTreemap::new();
+				for n in c {
+					res |= n.candidates(td);
+				}
+				res
+			}
+		}
+	}
+
+	fn contains(&self, td: &TermsDocs, freqs: &[TermFrequency], doc_id: DocId) -> bool {
+		match self {
+			Node::Term(s) => Self::docs(td, *s).map_or(false, |d| d.contains(doc_id)),
+			Node::Phrase(p, _) => freqs[*p] > 0,
+			Node::And(r, e) => {
+				!r.is_empty()
+					&& r.iter().all(|n| n.contains(td, freqs, doc_id))
+					&& !e.iter().any(|n| n.contains(td, freqs, doc_id))
+			}
+			Node::Or(c) => c.iter().any(|n| n.contains(td, freqs, doc_id)),
+		}
+	}
+}
+
+#[derive(Default)]
+struct Builder {
+	slots: Vec<Slot>,
+	phrases: Vec<Phrase>,
+}
+
+impl Builder {
+	fn slot(&mut self, term: Option<TermId>, boost: Score, excluded: bool, phrase: bool) -> usize {
+		let i = match self.slots.iter().position(|s| s.term == term) {
+			Some(i) => i,
+			None => {
+				self.slots.push(Slot {
+					term,
+					boost: None,
+					phrase_only: true,
+				});
+				self.slots.len() - 1
+			}
+		};
+		if !excluded {
+			let s = &mut self.slots[i];
+			s.boost = Some(s.boost.map_or(boost, |b| b.max(boost)));
+			s.phrase_only &= phrase;
+		}
+		i
+	}
+
+	fn nodes(
+		&mut self,
+		exprs: &[Expr],
+		leaves: &mut IntoIter<LeafTerms>,
+		excluded: bool,
+	) -> Vec<Node> {
+		let mut res = Vec::with_capacity(exprs.len());
+		for e in exprs {
+			res.extend(self.build(e, leaves, excluded));
+		}
+		res
+	}
+
+	fn and(mut nodes: Vec<Node>) -> Option<Node> {
+		if nodes.len() > 1 {
+			Some(Node::And(nodes, vec![]))
+		} else {
+			nodes.pop()
+		}
+	}
+
+	fn build(
+		&mut self,
+		expr: &Expr,
+		leaves: &mut IntoIter<LeafTerms>,
+		excluded: bool,
+	) -> Option<Node> {
+		match expr {
+			Expr::Leaf(_, boost) => match leaves.next()? {
+				LeafTerms::Word(terms) => {
+					let nodes = terms
+						.into_iter()
+						.map(|t| Node::Term(self.slot(t, *boost, excluded, false)))
+						.collect();
+					Self::and(nodes)
+				}
+				LeafTerms::Expanded(terms, expansions) => {
+					let mut nodes: Vec<Node> = terms
+						.into_iter()
+						.map(|t| Node::Term(self.slot(t, *boost, excluded, false)))
+						.collect();
+					let mut expansions: Vec<Node> = expansions
+						.into_iter()
+						.map(|t| Node::Term(self.slot(Some(t), *boost, excluded, false)))
+						.collect();
+					nodes.push(match expansions.len() {
+						// No term of the index matches
+						0 => Node::Term(self.slot(None, *boost, excluded, false)),
+						1 => expansions.remove(0),
+						_ => Node::Or(expansions),
+					});
+					Self::and(nodes)
+				}
+				LeafTerms::Phrase(terms, slop) => {
+					let slots: Vec<usize> =
+						terms.iter().map(|(_, t)| self.slot(*t, *boost, excluded, true)).collect();
+					if slots.is_empty() {
+						return None;
+					}
+					let terms: Option<Vec<(Position, TermId)>> =
+						terms.into_iter().map(|(p, t)| t.map(|t| (p, t))).collect();
+					match terms {
+						Some(terms) => {
+							self.phrases.push(Phrase::new(terms, slop));
+							Some(Node::Phrase(self.phrases.len() - 1, slots))
+						}
+						// A phrase with a term which is not indexed can't match
+						None => {
+							Some(Node::And(slots.into_iter().map(Node::Term).collect(), vec![]))
+						}
+					}
+				}
+			},
+			Expr::And(r, e) => {
+				let r = self.nodes(r, leaves, excluded);
+				let e = self.nodes(e, leaves, true);
+				if r.is_empty() && e.is_empty() {
+					None
+				} else {
+					Some(Node::And(r, e))
+				}
+			}
+			Expr::Or(c) => {
+				let mut c = self.nodes(c, leaves, excluded);
+				if c.len() > 1 {
+					Some(Node::Or(c))
+				} else {
+					c.pop()
+				}
+			}
+		}
+	}
+}
+
+/// The query string of a MATCHES expression, resolved against the terms of a full-text index
+#[derive(Clone)]
+pub(crate) struct MatchQuery(Arc<Inner>);
+
+struct Inner {
+	root: Option<Node>,
+	slots: Vec<Slot>,
+	phrases: Vec<Phrase>,
+	offsets: Offsets,
+}
+
+impl MatchQuery {
+	/// Build the query, given the terms of each of the leaves of the expression
+	pub(super) fn new(expr: Option<&Expr>, leaves: Vec<LeafTerms>, offsets: Offsets) -> Self {
+		let mut b = Builder::default();
+		let root = expr.and_then(|e| b.build(e, &mut leaves.into_iter(), false));
+		Self(Arc::new(Inner {
+			root,
+			slots: b.slots,
+			phrases: b.phrases,
+			offsets,
+		}))
+	}
+
+	pub(super) fn has_phrases(&self) -> bool {
+		!self.0.phrases.is_empty()
+	}
+
+	pub(super) fn slots(&self) -> &[Slot] {
+		&self.0.slots
+	}
+
+	/// Every term of the query, in the order of the slots
+	pub(in crate::idx) fn terms(&self) -> Vec<Option<TermId>> {
+		self.0.slots.iter().map(|s| s.term).collect()
+	}
+
+	/// The terms of the query which are not excluded
+	pub(in crate::idx) fn matched_terms(&self) -> Vec<Option<TermId>> {
+		self.0.slots.iter().filter(|s| s.boost.is_some()).map(|s| s.term).collect()
+	}
+
+	/// Whether the documents returned by `candidates` don't need to be checked with `matches`
+	pub(super) fn is_exact(&self) -> bool {
+		self.0.root.as_ref().map_or(true, |n| n.is_exact())
+	}
+
+	/// The documents which may match the query
+	pub(super) fn candidates(&self, td: &TermsDocs) -> RoaringTreemap {
+		self.0.root.as_ref().map(|n| n.candidates(td)).unwrap_or_default()
+	}
+
+	pub(in crate::idx) async fn matches(
+		&self,
+		tx: &mut Transaction,
+		td: &TermsDocs,
+		doc_id: DocId,
+	) -> Result<bool, Error> {
+		if let Some(root) = &self.0.root {
+			let freqs = self.frequencies(tx, doc_id).await?;
+			return Ok(root.contains(td, &freqs, doc_id));
+		}
+		Ok(false)
+	}
+
+	/// Returns the number of occurrences of each phrase in a document
+	async fn frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+	) -> Result<Vec<TermFrequency>, Error> {
+		let mut offsets: HashMap<TermId, Vec<Offset>> = HashMap::new();
+		let mut res = Vec::with_capacity(self.0.phrases.len());
+		for phrase in &self.0.phrases {
+			// Collect the offsets of the terms
+			for (_, term_id) in &phrase.terms {
+				if let Entry::Vacant(e) = offsets.entry(*term_id) {
+					let o = self.0.offsets.get_offsets(tx, doc_id, *term_id).await?;
+					e.insert(o.map(|o| o.0).unwrap_or_default());
+				}
+			}
+			let terms_offsets: Vec<&Vec<Offset>> =
+				phrase.terms.iter().filter_map(|(_, t)| offsets.get(t)).collect();
+			res.push(phrase.frequency(&terms_offsets));
+		}
+		Ok(res)
+	}
+
+	/// Returns, for each term belonging to a phrase found in the document,
+	/// the highest number of occurrences of these phrases.
+	pub(super) async fn phrases_terms_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+	) -> Result<HashMap<TermId, TermFrequency>, Error> {
+		let mut res = HashMap::new();
+		if self.has_phrases() {
+			let freqs = self.frequencies(tx, doc_id).await?;
+			for (phrase, freq) in self.0.phrases.iter().zip(freqs) {
+				if freq > 0 {
+					for (_, term_id) in &phrase.terms {
+						let f = res.entry(*term_id).or_insert(0);
+						*f = freq.max(*f);
+					}
+				}
+			}
+		}
+		Ok(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::offsets::Offset;
+	use crate::idx::ft::query::{parse, Expansion, Expr, Leaf, Phrase};
+
+	fn word(w: &str) -> Expr<'_> {
+		Expr::Leaf(Leaf::Word(w), 1.0)
+	}
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(parse(""), None);
+		assert_eq!(parse("hello"), Some(word("hello")));
+		assert_eq!(
+			parse("hello world"),
+			Some(Expr::And(vec![word("hello"), word("world")], vec![]))
+		);
+		assert_eq!(
+			parse(r#"city "new york" state"#),
+			Some(Expr::And(
+				vec![word("city"), Expr::Leaf(Leaf::Phrase("new york", 0), 1.0), word("state")],
+				vec![]
+			))
+		);
+		assert_eq!(
+			parse(r#""quick fox"~3 dog"#),
+			Some(Expr::And(
+				vec![Expr::Leaf(Leaf::Phrase("quick fox", 3), 1.0), word("dog")],
+				vec![]
+			))
+		);
+		assert_eq!(parse(r#""quick fox"~"#), Some(Expr::Leaf(Leaf::Phrase("quick fox", 0), 1.0)));
+		assert_eq!(
+			parse(r#"a "quick fox"#),
+			Some(Expr::And(vec![word("a"), Expr::Leaf(Leaf::Phrase("quick fox", 0), 1.0)], vec![]))
+		);
+	}
+
+	#[test]
+	fn test_parse_boolean() {
+		assert_eq!(
+			parse("laptop -refurbished"),
+			Some(Expr::And(vec![word("laptop")], vec![word("refurbished")]))
+		);
+		assert_eq!(parse("+laptop"), Some(word("laptop")));
+		assert_eq!(parse("-laptop"), Some(Expr::And(vec![], vec![word("laptop")])));
+		assert_eq!(parse("a OR b"), Some(Expr::Or(vec![word("a"), word("b")])));
+		assert_eq!(
+			parse("a AND b OR c"),
+			Some(Expr::Or(vec![Expr::And(vec![word("a"), word("b")], vec![]), word("c")]))
+		);
+		assert_eq!(
+			parse("a (b OR c) -(d e)"),
+			Some(Expr::And(
+				vec![word("a"), Expr::Or(vec![word("b"), word("c")])],
+				vec![Expr::And(vec![word("d"), word("e")], vec![])]
+			))
+		);
+		assert_eq!(
+			parse(r#"a^2 "b c"^0.5 (d e^2)^3"#),
+			Some(Expr::And(
+				vec![
+					Expr::Leaf(Leaf::Word("a"), 2.0),
+					Expr::Leaf(Leaf::Phrase("b c", 0), 0.5),
+					Expr::And(
+						vec![Expr::Leaf(Leaf::Word("d"), 3.0), Expr::Leaf(Leaf::Word("e"), 6.0)],
+						vec![]
+					)
+				],
+				vec![]
+			))
+		);
+		// Unbalanced parentheses and dangling operators are ignored
+		assert_eq!(parse("a) (b"), Some(Expr::And(vec![word("a"), word("b")], vec![])));
+		assert_eq!(parse("OR a -"), Some(Expr::And(vec![word("a"), word("-")], vec![])));
+		assert_eq!(parse("a - b"), Some(Expr::And(vec![word("a"), word("-"), word("b")], vec![])));
+	}
+
+	#[test]
+	fn test_parse_expansions() {
+		let expanded = |w, e| Expr::Leaf(Leaf::Expanded(w, e), 1.0);
+		assert_eq!(parse("lap*"), Some(expanded("lap", Expansion::Prefix)));
+		assert_eq!(parse("laptp~"), Some(expanded("laptp", Expansion::Fuzzy(1))));
+		assert_eq!(parse("laptp~2"), Some(expanded("laptp", Expansion::Fuzzy(2))));
+		// The edit distance is capped
+		assert_eq!(parse("laptp~9"), Some(expanded("laptp", Expansion::Fuzzy(2))));
+		assert_eq!(
+			parse("-lap*^2 tab~1"),
+			Some(Expr::And(
+				vec![expanded("tab", Expansion::Fuzzy(1))],
+				vec![Expr::Leaf(Leaf::Expanded("lap", Expansion::Prefix), 2.0)]
+			))
+		);
+		// Otherwise these are plain words
+		assert_eq!(parse("*"), Some(word("*")));
+		assert_eq!(parse("~1"), Some(word("~1")));
+		assert_eq!(parse("a~b"), Some(word("a~b")));
+	}
+
+	fn offsets(positions: &[u32]) -> Vec<Offset> {
+		positions.iter().map(|p| Offset::new(0, 0, 0, *p)).collect()
+	}
+
+	#[test]
+	fn test_frequency() {
+		// the quick brown fox jumped over the quick fox
+		let quick = offsets(&[1, 7]);
+		let fox = offsets(&[3, 8]);
+		let brown = offsets(&[2]);
+		let p = Phrase::new(vec![(4, 1), (5, 2)], 0);
+		assert_eq!(p.frequency(&[&quick, &fox]), 1);
+		let p = Phrase::new(vec![(4, 1), (5, 2)], 1);
+		assert_eq!(p.frequency(&[&quick, &fox]), 2);
+		// The terms must appear in order
+		let p = Phrase::new(vec![(0, 2), (1, 1)], 2);
+		assert_eq!(p.frequency(&[&fox, &quick]), 0);
+		let p = Phrase::new(vec![(0, 1), (1, 3), (2, 2)], 0);
+		assert_eq!(p.frequency(&[&quick, &brown, &fox]), 1);
+		// The terms must be in the same value
+		let fox = vec![Offset::new(1, 0, 0, 2)];
+		let p = Phrase::new(vec![(0, 1), (1, 2)], 0);
+		assert_eq!(p.frequency(&[&offsets(&[1]), &fox]), 0);
+	}
+}
@@ -1,7 +1,9 @@
 use crate::err::Error;
 use crate::idx::ft::docids::DocId;
 use crate::idx::ft::doclength::{DocLength, DocLengths};
+use crate::idx::ft::fields::Fields;
 use crate::idx::ft::postings::{Postings, TermFrequency};
+use crate::idx::ft::query::MatchQuery;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::Bm25Params;
 use crate::kvs::Transaction;
@@ -13,28 +15,36 @@ pub(super) type Score = f32;
 pub(crate) struct BM25Scorer {
 	postings: Postings,
 	terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+	query: MatchQuery,
 	doc_lengths: DocLengths,
 	average_doc_length: f32,
 	doc_count: f32,
 	bm25: Bm25Params,
+	/// The per-field term frequencies, and the weight of each field
+	fields: Option<(Fields, Vec<f32>)>,
 }
 
 impl BM25Scorer {
+	#[allow(clippy::too_many_arguments)]
 	pub(super) fn new(
 		postings: Postings,
 		terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+		query: MatchQuery,
 		doc_lengths: DocLengths,
 		total_docs_length: u128,
 		doc_count: u64,
 		bm25: Bm25Params,
+		fields: Option<(Fields, Vec<f32>)>,
 	) -> Self {
 		Self {
 			postings,
 			terms_docs,
+			query,
 			doc_lengths,
 			average_doc_length: (total_docs_length as f32) / (doc_count as f32),
 			doc_count: doc_count as f32,
 			bm25,
+			fields,
 		}
 	}
 
@@ -49,24 +59,83 @@ impl BM25Scorer {
 		Ok(self.compute_bm25_score(term_frequency as f32, term_doc_count as f32, doc_length as f32))
 	}
 
+	/// The weighted sum of the scores of a term within each field.
+	/// Returns None if the frequencies per field are not available.
+	async fn fields_score(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+		term_doc_count: DocLength,
+	) -> Result<Option<Score>, Error> {
+		if let Some((fields, weights)) = &self.fields {
+			if let Some(ff) = fields.get_frequencies(tx, doc_id, term_id).await? {
+				let doc_length = self.doc_lengths.get_doc_length(tx, doc_id).await?.unwrap_or(0);
+				let mut sc = 0.0;
+				for (term_freq, weight) in ff.0.into_iter().zip(weights) {
+					if term_freq > 0 {
+						sc += weight
+							* self.compute_bm25_score(
+								term_freq as f32,
+								term_doc_count as f32,
+								doc_length as f32,
+							);
+					}
+				}
+				return Ok(Some(sc));
+			}
+		}
+		Ok(None)
+	}
+
 	pub(crate) async fn score(
 		&self,
 		tx: &mut Transaction,
 		doc_id: DocId,
 	) -> Result<Option<Score>, Error> {
+		// The terms which only belong to phrases are only counted where the phrases occur
+		let phrases_freqs = self.query.phrases_terms_frequencies(tx, doc_id).await?;
 		let mut sc = 0.0;
-		for (term_id, docs) in self.terms_docs.iter().flatten() {
-			if docs.contains(doc_id) {
-				if let Some(term_freq) =
-					self.postings.get_term_frequency(tx, *term_id, doc_id).await?
-				{
-					sc += self.term_score(tx, doc_id, docs.len(), term_freq).await?;
+		for (slot, opt_td) in self.query.slots().iter().zip(self.terms_docs.iter()) {
+			// Excluded terms don't score
+			if let (Some(boost), Some((term_id, docs))) = (slot.boost, opt_td) {
+				if docs.contains(doc_id) {
+					if !slot.phrase_only {
+						if let Some(s) = self.fields_score(tx, doc_id, *term_id, docs.len()).await?
+						{
+							sc += boost * s;
+							continue;
+						}
+					}
+					let term_freq = if slot.phrase_only {
+						phrases_freqs.get(term_id).copied()
+					} else {
+						self.postings.get_term_frequency(tx, *term_id, doc_id).await?
+					};
+					if let Some(term_freq) = term_freq {
+						sc += boost * self.term_score(tx, doc_id, docs.len(), term_freq).await?;
+					}
 				}
 			}
 		}
 		Ok(Some(sc))
 	}
 
+	/// An upper bound of the score of a term in any document, given its highest frequency.
+	/// The score increases with the term frequency and decreases with the document length.
+	pub(super) fn max_term_score(
+		&self,
+		term_doc_count: DocLength,
+		max_term_freq: TermFrequency,
+	) -> Score {
+		let sc = self.compute_bm25_score(max_term_freq as f32, term_doc_count as f32, 0.0);
+		match &self.fields {
+			// The frequency of a term in a field is not higher than in the whole document
+			Some((_, weights)) => sc * weights.iter().sum::<f32>().max(1.0),
+			None => sc,
+		}
+	}
+
 	// https://en.wikipedia.org/wiki/Okapi_BM25
 	// Including the lower-bounding term frequency normalization (2011 CIKM)
 	fn compute_bm25_score(&self, term_freq: f32, term_doc_count: f32, doc_length: f32) -> f32 {
@@ -1,6 +1,7 @@
 use crate::err::Error;
 use crate::idx::ft::docids::DocId;
 use crate::idx::ft::doclength::DocLength;
+use crate::idx::ft::postings::TermFrequency;
 use crate::idx::ft::terms::TermId;
 use crate::idx::{IndexKeyBase, SerdeState};
 use crate::kvs::Transaction;
@@ -60,6 +61,7 @@ impl TermDocs {
 				let key = self.index_key_base.new_bc_key(term_id);
 				if docs.is_empty() {
 					tx.del(key).await?;
+					tx.del(self.index_key_base.new_bm_key(term_id)).await?;
 				} else {
 					tx.set(key, docs.try_to_val()?).await?;
 				}
@@ -69,4 +71,35 @@ impl TermDocs {
 			Ok(0)
 		}
 	}
+
+	/// Keep track of the highest frequency of a term within a document.
+	/// The value is an upper bound: it is not lowered when documents are removed.
+	pub(super) async fn update_max_frequency(
+		&self,
+		tx: &mut Transaction,
+		term_id: TermId,
+		term_freq: TermFrequency,
+	) -> Result<(), Error> {
+		if let Some(max) = self.get_max_frequency(tx, term_id).await? {
+			if max >= term_freq {
+				return Ok(());
+			}
+		}
+		let key = self.index_key_base.new_bm_key(term_id);
+		tx.set(key, bincode::serialize(&term_freq)?).await?;
+		Ok(())
+	}
+
+	pub(super) async fn get_max_frequency(
+		&self,
+		tx: &mut Transaction,
+		term_id: TermId,
+	) -> Result<Option<TermFrequency>, Error> {
+		let key = self.index_key_base.new_bm_key(term_id);
+		if let Some(val) = tx.get(key).await? {
+			Ok(Some(bincode::deserialize(&val)?))
+		} else {
+			Ok(None)
+		}
+	}
 }
@@ -1,8 +1,11 @@
 use crate::err::Error;
 use crate::idx::bkeys::FstKeys;
 use crate::idx::btree::{BTree, KeyProvider, NodeId, Statistics};
+use crate::idx::ft::fuzzy::Levenshtein;
 use crate::idx::{btree, IndexKeyBase, SerdeState};
 use crate::kvs::{Key, Transaction};
+use fst::automaton::Str;
+use fst::Automaton;
 use roaring::RoaringTreemap;
 use serde::{Deserialize, Serialize};
 
@@ -84,6 +87,31 @@ impl Terms {
 		self.btree.search::<FstKeys>(tx, &term.into()).await
 	}
 
+	/// Returns, in term order, at most `limit` terms starting with the given prefix
+	pub(super) async fn get_terms_with_prefix(
+		&self,
+		tx: &mut Transaction,
+		prefix: &str,
+		limit: usize,
+	) -> Result<Vec<TermId>, Error> {
+		let aut = Str::new(prefix).starts_with();
+		let res = self.btree.search_by_automaton::<FstKeys, _>(tx, &aut, limit).await?;
+		Ok(res.into_iter().map(|(_, term_id)| term_id).collect())
+	}
+
+	/// Returns, in term order, at most `limit` terms within the given edit distance of a term
+	pub(super) async fn get_fuzzy_terms(
+		&self,
+		tx: &mut Transaction,
+		term: &str,
+		distance: u32,
+		limit: usize,
+	) -> Result<Vec<TermId>, Error> {
+		let aut = Levenshtein::new(term, distance);
+		let res = self.btree.search_by_automaton::<FstKeys, _>(tx, &aut, limit).await?;
+		Ok(res.into_iter().map(|(_, term_id)| term_id).collect())
+	}
+
 	pub(super) async fn remove_term_id(
 		&mut self,
 		tx: &mut Transaction,
@@ -160,11 +188,11 @@ impl KeyProvider for TermsKeyProvider {
 #[cfg(test)]
 mod tests {
 	use crate::idx::ft::postings::TermFrequency;
-	use crate::idx::ft::terms::Terms;
+	use crate::idx::ft::terms::{TermId, Terms};
 	use crate::idx::IndexKeyBase;
 	use crate::kvs::Datastore;
 	use rand::{thread_rng, Rng};
-	use std::collections::HashSet;
+	use std::collections::{HashMap, HashSet};
 
 	fn random_term(key_length: usize) -> String {
 		thread_rng()
@@ -271,6 +299,34 @@ mod tests {
 		tx.commit().await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_prefix_and_fuzzy_terms() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut t = Terms::new(&mut tx, IndexKeyBase::default(), 2).await.unwrap();
+		let mut ids = HashMap::new();
+		for term in ["laptop", "lap", "lamp", "laptops", "label", "tablet", "table"] {
+			ids.insert(term, t.resolve_term_id(&mut tx, term).await.unwrap());
+		}
+		let ids = |terms: &[&str]| -> Vec<TermId> { terms.iter().map(|t| ids[t]).collect() };
+
+		let res = t.get_terms_with_prefix(&mut tx, "lap", 10).await.unwrap();
+		assert_eq!(res, ids(&["lap", "laptop", "laptops"]));
+		let res = t.get_terms_with_prefix(&mut tx, "la", 2).await.unwrap();
+		assert_eq!(res, ids(&["label", "lamp"]));
+		let res = t.get_terms_with_prefix(&mut tx, "z", 10).await.unwrap();
+		assert!(res.is_empty());
+
+		let res = t.get_fuzzy_terms(&mut tx, "lamtop", 1, 10).await.unwrap();
+		assert_eq!(res, ids(&["laptop"]));
+		let res = t.get_fuzzy_terms(&mut tx, "tabel", 2, 10).await.unwrap();
+		assert_eq!(res, ids(&["label", "table", "tablet"]));
+		let res = t.get_fuzzy_terms(&mut tx, "tabel", 2, 1).await.unwrap();
+		assert_eq!(res, ids(&["label"]));
+		t.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+
 	fn random_term_freq_vec(term_count: usize) -> Vec<(String, TermFrequency)> {
 		let mut i = 1;
 		let mut vec = Vec::with_capacity(term_count);
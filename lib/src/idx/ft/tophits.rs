@@ -0,0 +1,203 @@
+use crate::err::Error;
+use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::ft::query::MatchQuery;
+use crate::idx::ft::scorer::{BM25Scorer, Score};
+use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
+use crate::kvs::Transaction;
+use crate::sql::Thing;
+use roaring::RoaringTreemap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Returns the hits of a query by descending score.
+///
+/// The first `k` hits are collected using a max-score traversal: the terms are
+/// sorted by the upper bound of their score, and once `k` hits have been found,
+/// the documents which only contain terms whose combined bound is lower than the
+/// k-th best score are not scored at all. The remaining hits are only scored and
+/// sorted if more than `k` hits are requested (e.g. if some records are filtered
+/// out by another condition).
+pub(crate) struct TopHitsIterator {
+	doc_ids: DocIds,
+	term_docs: TermDocs,
+	scorer: BM25Scorer,
+	hits: RoaringTreemap,
+	terms_docs: TermsDocs,
+	query: MatchQuery,
+	k: usize,
+	/// The hits which are not returned yet, the best score first
+	sorted: Option<VecDeque<DocId>>,
+	/// The hits which have been sorted
+	done: RoaringTreemap,
+	/// True once every hit has been sorted
+	complete: bool,
+}
+
+impl TopHitsIterator {
+	pub(super) fn new(
+		doc_ids: DocIds,
+		term_docs: TermDocs,
+		scorer: BM25Scorer,
+		hits: RoaringTreemap,
+		terms_docs: TermsDocs,
+		query: MatchQuery,
+		k: usize,
+	) -> Self {
+		Self {
+			doc_ids,
+			term_docs,
+			scorer,
+			hits,
+			terms_docs,
+			query,
+			k,
+			sorted: None,
+			done: RoaringTreemap::new(),
+			complete: false,
+		}
+	}
+
+	pub(crate) async fn next(
+		&mut self,
+		tx: &mut Transaction,
+	) -> Result<Option<(Thing, DocId)>, Error> {
+		loop {
+			if self.sorted.is_none() {
+				let top = self.top_k(tx).await?;
+				self.done.extend(top.iter().copied());
+				self.sorted = Some(top);
+			}
+			if let Some(doc_id) = self.sorted.as_mut().and_then(|s| s.pop_front()) {
+				if let Some(doc_key) = self.doc_ids.get_doc_key(tx, doc_id).await? {
+					return Ok(Some((doc_key.into(), doc_id)));
+				}
+				continue;
+			}
+			if self.complete {
+				return Ok(None);
+			}
+			// More hits than expected are needed
+			let remaining = &self.hits - &self.done;
+			self.sorted = Some(self.sort(tx, remaining).await?);
+			self.complete = true;
+		}
+	}
+
+	/// Check if a candidate actually matches the query
+	async fn matches(&self, tx: &mut Transaction, doc_id: DocId) -> Result<bool, Error> {
+		Ok(self.query.is_exact() || self.query.matches(tx, &self.terms_docs, doc_id).await?)
+	}
+
+	/// Collect the `k` hits with the best scores
+	async fn top_k(&self, tx: &mut Transaction) -> Result<VecDeque<DocId>, Error> {
+		if self.k == 0 {
+			return Ok(VecDeque::new());
+		}
+		// The upper bound of the score of each term, by ascending order
+		let mut bounds = Vec::with_capacity(self.terms_docs.len());
+		for (slot, opt_td) in self.query.slots().iter().zip(self.terms_docs.iter()) {
+			if let (Some(boost), Some((term_id, docs))) = (slot.boost, opt_td) {
+				let bound = match self.term_docs.get_max_frequency(tx, *term_id).await? {
+					Some(tf) => boost.max(0.0) * self.scorer.max_term_score(docs.len(), tf),
+					// The frequencies of terms indexed by a previous version are unknown
+					None => Score::INFINITY,
+				};
+				bounds.push((bound, docs));
+			}
+		}
+		bounds.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+		let mut heap = BinaryHeap::with_capacity(self.k + 1);
+		// The number of non-essential terms, and the sum of their bounds
+		let mut non_essential = 0;
+		let mut non_essential_bound = 0.0;
+		for doc_id in &self.hits {
+			if heap.len() == self.k {
+				if let Some(Hit {
+					score: threshold,
+					..
+				}) = heap.peek()
+				{
+					while let Some((bound, _)) = bounds.get(non_essential) {
+						if non_essential_bound + bound >= *threshold {
+							break;
+						}
+						non_essential_bound += bound;
+						non_essential += 1;
+					}
+				}
+				// No document can beat the current top anymore
+				if non_essential == bounds.len() {
+					break;
+				}
+				// A document without any essential term can't beat the current top
+				if non_essential > 0
+					&& !bounds[non_essential..].iter().any(|(_, docs)| docs.contains(doc_id))
+				{
+					continue;
+				}
+			}
+			if !self.matches(tx, doc_id).await? {
+				continue;
+			}
+			if let Some(score) = self.scorer.score(tx, doc_id).await? {
+				heap.push(Hit {
+					score,
+					doc_id,
+				});
+				if heap.len() > self.k {
+					heap.pop();
+				}
+			}
+		}
+		Ok(heap.into_sorted_vec().into_iter().map(|h| h.doc_id).collect())
+	}
+
+	/// Score and sort every given hit
+	async fn sort(
+		&self,
+		tx: &mut Transaction,
+		hits: RoaringTreemap,
+	) -> Result<VecDeque<DocId>, Error> {
+		let mut res = Vec::with_capacity(hits.len() as usize);
+		for doc_id in hits {
+			if !self.matches(tx, doc_id).await? {
+				continue;
+			}
+			if let Some(score) = self.scorer.score(tx, doc_id).await? {
+				res.push(Hit {
+					score,
+					doc_id,
+				});
+			}
+		}
+		res.sort();
+		Ok(res.into_iter().map(|h| h.doc_id).collect())
+	}
+}
+
+/// A scored hit. The hits are ordered by descending score, and the hits
+/// sharing the same score keep the ascending order of their doc ids.
+struct Hit {
+	score: Score,
+	doc_id: DocId,
+}
+
+impl PartialEq for Hit {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Hit {}
+
+impl PartialOrd for Hit {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Hit {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.score.total_cmp(&self.score).then(self.doc_id.cmp(&other.doc_id))
+	}
+}
@@ -1,7 +1,10 @@
 mod bkeys;
 pub(crate) mod btree;
+pub(crate) mod build;
 pub(crate) mod ft;
+pub(crate) mod mtree;
 pub(crate) mod planner;
+pub(crate) mod stats;
 
 use crate::dbs::Options;
 use crate::err::Error;
@@ -14,11 +17,14 @@ use crate::key::bf::Bf;
 use crate::key::bi::Bi;
 use crate::key::bk::Bk;
 use crate::key::bl::Bl;
+use crate::key::bm::Bm;
 use crate::key::bo::Bo;
 use crate::key::bp::Bp;
 use crate::key::bs::Bs;
 use crate::key::bt::Bt;
 use crate::key::bu::Bu;
+use crate::key::bw::Bw;
+use crate::key::vm::Vm;
 use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
 use roaring::RoaringTreemap;
@@ -106,6 +112,17 @@ impl IndexKeyBase {
 		.into()
 	}
 
+	fn new_bm_key(&self, term_id: TermId) -> Key {
+		Bm::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			term_id,
+		)
+		.into()
+	}
+
 	fn new_bo_key(&self, doc_id: DocId, term_id: TermId) -> Key {
 		Bo::new(
 			self.inner.ns.as_str(),
@@ -118,6 +135,18 @@ impl IndexKeyBase {
 		.into()
 	}
 
+	fn new_bw_key(&self, doc_id: DocId, term_id: TermId) -> Key {
+		Bw::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+			term_id,
+		)
+		.into()
+	}
+
 	fn new_bp_key(&self, node_id: Option<NodeId>) -> Key {
 		Bp::new(
 			self.inner.ns.as_str(),
@@ -172,6 +201,17 @@ impl IndexKeyBase {
 		)
 		.into()
 	}
+
+	fn new_vm_key(&self, node_id: Option<NodeId>) -> Key {
+		Vm::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			node_id,
+		)
+		.into()
+	}
 }
 
 /// This trait provides `bincode` based default implementations for serialization/deserialization
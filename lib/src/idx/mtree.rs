@@ -0,0 +1,693 @@
+use crate::err::Error;
+use crate::idx::btree::NodeId;
+use crate::idx::{IndexKeyBase, SerdeState};
+use crate::kvs::{Key, Transaction};
+use crate::sql::index::{Distance, MTreeParams};
+use crate::sql::{Object, Thing, Value};
+use async_recursion::async_recursion;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+pub(crate) type Vector = Vec<f64>;
+
+/// An M-tree, which indexes vectors by their distance to each other.
+///
+/// Every internal node holds routing entries, made of a center vector and
+/// a radius covering every vector of the child node. Every leaf node holds
+/// the indexed vectors, along with the records having this vector. The
+/// radiuses allow the nearest neighbours of a vector to be found without
+/// visiting the nodes which are too far away to contain any of them.
+///
+/// Cosine distances do not satisfy the triangle inequality, so for this
+/// distance the vectors are normalised, and indexed by the euclidean
+/// distance between them, which is ordered like the cosine distance.
+pub(crate) struct MTree {
+	state_key: Key,
+	index_key_base: IndexKeyBase,
+	dimension: usize,
+	distance: Distance,
+	capacity: usize,
+	state: State,
+	updated: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Statistics {
+	vectors_count: u64,
+	records_count: u64,
+	max_depth: u32,
+	nodes_count: u32,
+	total_size: u64,
+}
+
+impl From<Statistics> for Value {
+	fn from(stats: Statistics) -> Self {
+		let mut res = Object::default();
+		res.insert("vectors_count".to_owned(), Value::from(stats.vectors_count));
+		res.insert("records_count".to_owned(), Value::from(stats.records_count));
+		res.insert("max_depth".to_owned(), Value::from(stats.max_depth));
+		res.insert("nodes_count".to_owned(), Value::from(stats.nodes_count));
+		res.insert("total_size".to_owned(), Value::from(stats.total_size));
+		Value::from(res)
+	}
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+	root: Option<NodeId>,
+	next_node_id: NodeId,
+}
+
+impl SerdeState for State {}
+
+#[derive(Serialize, Deserialize)]
+enum Node {
+	Internal(Vec<Routing>),
+	Leaf(Vec<Entry>),
+}
+
+impl SerdeState for Node {}
+
+/// A child node, and the sphere containing every vector of the child node
+#[derive(Clone, Serialize, Deserialize)]
+struct Routing {
+	center: Vector,
+	radius: f64,
+	node: NodeId,
+}
+
+/// An indexed vector, and the records having this vector
+#[derive(Serialize, Deserialize)]
+struct Entry {
+	vector: Vector,
+	things: Vec<Thing>,
+}
+
+/// A distance which can be ordered
+#[derive(Clone, Copy, PartialEq)]
+struct Dist(f64);
+
+impl Eq for Dist {}
+
+impl PartialOrd for Dist {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Dist {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0)
+	}
+}
+
+impl MTree {
+	/// The smallest number of entries which can be split in two nodes
+	const MIN_CAPACITY: usize = 2;
+
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		index_key_base: IndexKeyBase,
+		p: &MTreeParams,
+	) -> Result<Self, Error> {
+		let state_key = index_key_base.new_vm_key(None);
+		let state = match tx.get(state_key.clone()).await? {
+			Some(val) => State::try_from_val(val)?,
+			None => State::default(),
+		};
+		Ok(Self {
+			state_key,
+			index_key_base,
+			dimension: p.dimension as usize,
+			distance: p.distance.clone(),
+			capacity: (p.capacity as usize).max(Self::MIN_CAPACITY),
+			state,
+			updated: false,
+		})
+	}
+
+	/// Convert a value to the vector which is indexed. Records without a
+	/// value for the indexed field are not indexed.
+	pub(crate) fn vector(&self, v: &Value) -> Result<Option<Vector>, Error> {
+		let a = match v {
+			Value::None | Value::Null => return Ok(None),
+			Value::Array(a) => a,
+			v => {
+				return Err(Error::InvalidVectorValue {
+					current: v.to_string(),
+				})
+			}
+		};
+		if a.len() != self.dimension {
+			return Err(Error::InvalidVectorDimension {
+				current: a.len(),
+				expected: self.dimension,
+			});
+		}
+		let mut vector = Vec::with_capacity(a.len());
+		for v in a.iter() {
+			match v {
+				Value::Number(n) => vector.push(n.to_float()),
+				v => {
+					return Err(Error::InvalidVectorValue {
+						current: v.to_string(),
+					})
+				}
+			}
+		}
+		if self.distance == Distance::Cosine {
+			let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+			if norm > 0.0 {
+				vector.iter_mut().for_each(|v| *v /= norm);
+			}
+		}
+		Ok(Some(vector))
+	}
+
+	/// The distance between two indexed vectors
+	fn dist(&self, a: &[f64], b: &[f64]) -> f64 {
+		let it = a.iter().zip(b.iter());
+		match self.distance {
+			Distance::Euclidean | Distance::Cosine => {
+				it.map(|(a, b)| (a - b) * (a - b)).sum::<f64>().sqrt()
+			}
+			Distance::Manhattan => it.map(|(a, b)| (a - b).abs()).sum::<f64>(),
+		}
+	}
+
+	/// The distance between two indexed vectors, as defined by the index
+	fn output(&self, d: f64) -> f64 {
+		match self.distance {
+			// The euclidean distance between two unit vectors is sqrt(2 - 2cos)
+			Distance::Cosine => d * d / 2.0,
+			_ => d,
+		}
+	}
+
+	/// Check if a vector is within the sphere of a routing entry, allowing
+	/// for the rounding errors of the radiuses computed from other radiuses
+	fn covers(r: &Routing, d: f64) -> bool {
+		d <= r.radius + (1.0 + r.radius) * 1e-9
+	}
+
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		v: &Value,
+	) -> Result<(), Error> {
+		let vector = match self.vector(v)? {
+			Some(v) => v,
+			None => return Ok(()),
+		};
+		match self.state.root {
+			None => {
+				let id = self.new_node_id();
+				let node = Node::Leaf(vec![Entry {
+					vector,
+					things: vec![rid.clone()],
+				}]);
+				self.write(tx, id, node).await?;
+				self.state.root = Some(id);
+			}
+			Some(root) => {
+				// Grow a new root when the root is split
+				if let Some((a, b)) = self.insert(tx, root, &vector, rid).await? {
+					let id = self.new_node_id();
+					self.write(tx, id, Node::Internal(vec![a, b])).await?;
+					self.state.root = Some(id);
+				}
+			}
+		}
+		self.updated = true;
+		Ok(())
+	}
+
+	/// Insert the vector in the subtree of the node. If the node
+	/// overflows, it is split, and the two new routing entries are returned.
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		id: NodeId,
+		vector: &Vector,
+		rid: &Thing,
+	) -> Result<Option<(Routing, Routing)>, Error> {
+		match self.read(tx, id).await?.0 {
+			Node::Internal(mut rs) => {
+				// Choose the child which requires the least enlargement of its radius
+				let (i, d) = rs
+					.iter()
+					.map(|r| self.dist(&r.center, vector))
+					.enumerate()
+					.min_by(|(i, a), (j, b)| {
+						let ea = Dist((a - rs[*i].radius).max(0.0));
+						let eb = Dist((b - rs[*j].radius).max(0.0));
+						ea.cmp(&eb).then(Dist(*a).cmp(&Dist(*b)))
+					})
+					.ok_or(Error::CorruptedIndex)?;
+				if d > rs[i].radius {
+					rs[i].radius = d;
+				}
+				if let Some((a, b)) = self.insert(tx, rs[i].node, vector, rid).await? {
+					rs[i] = a;
+					rs.push(b);
+				}
+				if rs.len() > self.capacity {
+					return Ok(Some(self.split_internal(tx, id, rs).await?));
+				}
+				self.write(tx, id, Node::Internal(rs)).await?;
+			}
+			Node::Leaf(mut es) => {
+				match es.iter_mut().find(|e| &e.vector == vector) {
+					Some(e) => {
+						if !e.things.contains(rid) {
+							e.things.push(rid.clone());
+						}
+					}
+					None => es.push(Entry {
+						vector: vector.clone(),
+						things: vec![rid.clone()],
+					}),
+				}
+				if es.len() > self.capacity {
+					return Ok(Some(self.split_leaf(tx, id, es).await?));
+				}
+				self.write(tx, id, Node::Leaf(es)).await?;
+			}
+		}
+		Ok(None)
+	}
+
+	/// Choose the two vectors which are the furthest apart as the new centers,
+	/// and assign every other vector to the nearest of these centers.
+	fn partition(&self, vectors: &[&Vector]) -> (usize, usize, Vec<bool>) {
+		let (mut a, mut b, mut max) = (0, 1, f64::MIN);
+		for i in 0..vectors.len() {
+			for j in i + 1..vectors.len() {
+				let d = self.dist(vectors[i], vectors[j]);
+				if d > max {
+					(a, b, max) = (i, j, d);
+				}
+			}
+		}
+		let mut left = 0;
+		let sides = (0..vectors.len())
+			.map(|i| {
+				let side = match i {
+					i if i == a => true,
+					i if i == b => false,
+					i => {
+						let da = self.dist(vectors[a], vectors[i]);
+						let db = self.dist(vectors[b], vectors[i]);
+						// Balance the vectors which are as near to both centers
+						da < db || (da == db && left * 2 < vectors.len())
+					}
+				};
+				if side {
+					left += 1;
+				}
+				side
+			})
+			.collect();
+		(a, b, sides)
+	}
+
+	async fn split_leaf(
+		&mut self,
+		tx: &mut Transaction,
+		id: NodeId,
+		es: Vec<Entry>,
+	) -> Result<(Routing, Routing), Error> {
+		let vectors: Vec<_> = es.iter().map(|e| &e.vector).collect();
+		let (a, b, sides) = self.partition(&vectors);
+		let (ca, cb) = (es[a].vector.clone(), es[b].vector.clone());
+		let (mut la, mut lb) = (vec![], vec![]);
+		for (e, side) in es.into_iter().zip(sides) {
+			match side {
+				true => la.push(e),
+				false => lb.push(e),
+			}
+		}
+		let ra = la.iter().map(|e| self.dist(&ca, &e.vector)).fold(0.0, f64::max);
+		let rb = lb.iter().map(|e| self.dist(&cb, &e.vector)).fold(0.0, f64::max);
+		let new_id = self.new_node_id();
+		self.write(tx, id, Node::Leaf(la)).await?;
+		self.write(tx, new_id, Node::Leaf(lb)).await?;
+		Ok((Routing::new(ca, ra, id), Routing::new(cb, rb, new_id)))
+	}
+
+	async fn split_internal(
+		&mut self,
+		tx: &mut Transaction,
+		id: NodeId,
+		rs: Vec<Routing>,
+	) -> Result<(Routing, Routing), Error> {
+		let vectors: Vec<_> = rs.iter().map(|r| &r.center).collect();
+		let (a, b, sides) = self.partition(&vectors);
+		let (ca, cb) = (rs[a].center.clone(), rs[b].center.clone());
+		let (mut la, mut lb) = (vec![], vec![]);
+		for (r, side) in rs.into_iter().zip(sides) {
+			match side {
+				true => la.push(r),
+				false => lb.push(r),
+			}
+		}
+		let ra = la.iter().map(|r| self.dist(&ca, &r.center) + r.radius).fold(0.0, f64::max);
+		let rb = lb.iter().map(|r| self.dist(&cb, &r.center) + r.radius).fold(0.0, f64::max);
+		let new_id = self.new_node_id();
+		self.write(tx, id, Node::Internal(la)).await?;
+		self.write(tx, new_id, Node::Internal(lb)).await?;
+		Ok((Routing::new(ca, ra, id), Routing::new(cb, rb, new_id)))
+	}
+
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		v: &Value,
+	) -> Result<(), Error> {
+		// Values which are not vectors were never indexed
+		let vector = match self.vector(v) {
+			Ok(Some(v)) => v,
+			_ => return Ok(()),
+		};
+		if let Some(root) = self.state.root {
+			if let (true, true) = self.remove(tx, root, &vector, rid).await? {
+				self.state.root = None;
+			}
+			self.updated = true;
+		}
+		Ok(())
+	}
+
+	/// Remove the record from the subtree of the node. Returns whether the
+	/// record was found, and whether the node is now empty and was deleted.
+	/// The radiuses of the routing entries are not shrunk, as they still
+	/// cover every vector of their child nodes.
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	async fn remove(
+		&mut self,
+		tx: &mut Transaction,
+		id: NodeId,
+		vector: &Vector,
+		rid: &Thing,
+	) -> Result<(bool, bool), Error> {
+		match self.read(tx, id).await?.0 {
+			Node::Internal(mut rs) => {
+				for i in 0..rs.len() {
+					if !Self::covers(&rs[i], self.dist(&rs[i].center, vector)) {
+						continue;
+					}
+					match self.remove(tx, rs[i].node, vector, rid).await? {
+						(false, _) => continue,
+						(true, false) => return Ok((true, false)),
+						(true, true) => {
+							rs.remove(i);
+							if rs.is_empty() {
+								tx.del(self.index_key_base.new_vm_key(Some(id))).await?;
+								return Ok((true, true));
+							}
+							self.write(tx, id, Node::Internal(rs)).await?;
+							return Ok((true, false));
+						}
+					}
+				}
+				Ok((false, false))
+			}
+			Node::Leaf(mut es) => {
+				let i = match es.iter().position(|e| &e.vector == vector && e.things.contains(rid))
+				{
+					Some(i) => i,
+					None => return Ok((false, false)),
+				};
+				es[i].things.retain(|t| t != rid);
+				if es[i].things.is_empty() {
+					es.remove(i);
+				}
+				if es.is_empty() {
+					tx.del(self.index_key_base.new_vm_key(Some(id))).await?;
+					return Ok((true, true));
+				}
+				self.write(tx, id, Node::Leaf(es)).await?;
+				Ok((true, false))
+			}
+		}
+	}
+
+	/// Find the `k` records nearest to the vector, ordered by their distance.
+	/// The nodes are visited in the order of the smallest distance that any
+	/// of their vectors may have, until no node can contain a nearer vector.
+	pub(crate) async fn knn(
+		&self,
+		tx: &mut Transaction,
+		v: &Value,
+		k: usize,
+	) -> Result<Vec<(Thing, f64)>, Error> {
+		let vector = match self.vector(v)? {
+			Some(v) => v,
+			None => {
+				return Err(Error::InvalidVectorValue {
+					current: v.to_string(),
+				})
+			}
+		};
+		let mut res: BinaryHeap<(Dist, Thing)> = BinaryHeap::new();
+		let mut queue: BinaryHeap<Reverse<(Dist, NodeId)>> = BinaryHeap::new();
+		if let (Some(root), true) = (self.state.root, k > 0) {
+			queue.push(Reverse((Dist(0.0), root)));
+		}
+		while let Some(Reverse((Dist(min), id))) = queue.pop() {
+			// No other node can contain a nearer vector
+			if res.len() >= k && res.peek().map_or(false, |(d, _)| min > d.0) {
+				break;
+			}
+			match self.read(tx, id).await?.0 {
+				Node::Internal(rs) => {
+					for r in rs {
+						let min = (self.dist(&r.center, &vector) - r.radius).max(0.0);
+						if res.len() < k || res.peek().map_or(true, |(d, _)| min <= d.0) {
+							queue.push(Reverse((Dist(min), r.node)));
+						}
+					}
+				}
+				Node::Leaf(es) => {
+					for e in es {
+						let d = Dist(self.dist(&e.vector, &vector));
+						for t in e.things {
+							if res.len() < k {
+								res.push((d, t));
+							} else if res.peek().map_or(false, |(max, _)| d < *max) {
+								res.pop();
+								res.push((d, t));
+							}
+						}
+					}
+				}
+			}
+		}
+		Ok(res.into_sorted_vec().into_iter().map(|(d, t)| (t, self.output(d.0))).collect())
+	}
+
+	pub(crate) async fn statistics(&self, tx: &mut Transaction) -> Result<Statistics, Error> {
+		let mut stats = Statistics::default();
+		let mut queue = VecDeque::new();
+		if let Some(root) = self.state.root {
+			queue.push_back((root, 1));
+		}
+		while let Some((id, depth)) = queue.pop_front() {
+			let (node, size) = self.read(tx, id).await?;
+			stats.nodes_count += 1;
+			stats.total_size += size as u64;
+			stats.max_depth = stats.max_depth.max(depth);
+			match node {
+				Node::Internal(rs) => {
+					queue.extend(rs.into_iter().map(|r| (r.node, depth + 1)));
+				}
+				Node::Leaf(es) => {
+					stats.vectors_count += es.len() as u64;
+					stats.records_count += es.iter().map(|e| e.things.len() as u64).sum::<u64>();
+				}
+			}
+		}
+		Ok(stats)
+	}
+
+	pub(crate) async fn finish(self, tx: &mut Transaction) -> Result<(), Error> {
+		if self.updated {
+			tx.set(self.state_key, self.state.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+
+	fn new_node_id(&mut self) -> NodeId {
+		let id = self.state.next_node_id;
+		self.state.next_node_id += 1;
+		id
+	}
+
+	async fn read(&self, tx: &mut Transaction, id: NodeId) -> Result<(Node, u32), Error> {
+		match tx.get(self.index_key_base.new_vm_key(Some(id))).await? {
+			Some(val) => {
+				let size = val.len() as u32;
+				Ok((Node::try_from_val(val)?, size))
+			}
+			None => Err(Error::CorruptedIndex),
+		}
+	}
+
+	async fn write(&self, tx: &mut Transaction, id: NodeId, node: Node) -> Result<(), Error> {
+		tx.set(self.index_key_base.new_vm_key(Some(id)), node.try_to_val()?).await
+	}
+}
+
+impl Routing {
+	fn new(center: Vector, radius: f64, node: NodeId) -> Self {
+		Self {
+			center,
+			radius,
+			node,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::mtree::MTree;
+	use crate::idx::IndexKeyBase;
+	use crate::kvs::Datastore;
+	use crate::sql::index::{Distance, MTreeParams};
+	use crate::sql::{Thing, Value};
+	use rand::{thread_rng, Rng};
+
+	fn params(distance: Distance) -> MTreeParams {
+		MTreeParams {
+			dimension: 3,
+			distance,
+			capacity: 4,
+		}
+	}
+
+	fn vector(v: Vec<i64>) -> Value {
+		Value::from(v.into_iter().map(Value::from).collect::<Vec<_>>())
+	}
+
+	fn thing(i: usize) -> Thing {
+		Thing::from(("t".to_owned(), i.to_string()))
+	}
+
+	/// The nearest records found by comparing the vector with every record
+	fn brute_force(t: &MTree, vs: &[(Thing, Value)], q: &Value, k: usize) -> Vec<f64> {
+		let q = t.vector(q).unwrap().unwrap();
+		let mut res: Vec<f64> =
+			vs.iter().map(|(_, v)| t.output(t.dist(&t.vector(v).unwrap().unwrap(), &q))).collect();
+		res.sort_by(f64::total_cmp);
+		res.truncate(k);
+		res
+	}
+
+	async fn check_knn(distance: Distance) {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut rng = thread_rng();
+		let mut vs: Vec<(Thing, Value)> = (0..200)
+			.map(|i| {
+				let v: Vec<i64> = (0..3).map(|_| rng.gen_range(-20..20)).collect();
+				(thing(i), vector(v))
+			})
+			.collect();
+		// Index every record
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut t =
+			MTree::new(&mut tx, IndexKeyBase::default(), &params(distance.clone())).await.unwrap();
+		for (rid, v) in &vs {
+			t.index_document(&mut tx, rid, v).await.unwrap();
+		}
+		t.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+		// Remove half of the records
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut t =
+			MTree::new(&mut tx, IndexKeyBase::default(), &params(distance.clone())).await.unwrap();
+		for (rid, v) in vs.drain(..100) {
+			t.remove_document(&mut tx, &rid, &v).await.unwrap();
+		}
+		t.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+		// The nearest records are the same as found by brute force
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let t = MTree::new(&mut tx, IndexKeyBase::default(), &params(distance)).await.unwrap();
+		for _ in 0..20 {
+			let q: Vec<i64> = (0..3).map(|_| rng.gen_range(-25..25)).collect();
+			let q = vector(q);
+			let res = t.knn(&mut tx, &q, 10).await.unwrap();
+			let exp = brute_force(&t, &vs, &q, 10);
+			let res: Vec<f64> = res.into_iter().map(|(_, d)| d).collect();
+			assert_eq!(res.len(), exp.len());
+			for (r, e) in res.iter().zip(exp.iter()) {
+				assert!((r - e).abs() < 1e-9, "{res:?} != {exp:?}");
+			}
+		}
+		let stats = t.statistics(&mut tx).await.unwrap();
+		assert_eq!(stats.records_count, 100);
+		assert!(stats.max_depth > 1);
+	}
+
+	#[tokio::test]
+	async fn test_knn_euclidean() {
+		check_knn(Distance::Euclidean).await;
+	}
+
+	#[tokio::test]
+	async fn test_knn_cosine() {
+		check_knn(Distance::Cosine).await;
+	}
+
+	#[tokio::test]
+	async fn test_knn_manhattan() {
+		check_knn(Distance::Manhattan).await;
+	}
+
+	#[tokio::test]
+	async fn test_remove_every_record() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let p = params(Distance::Euclidean);
+		let mut t = MTree::new(&mut tx, IndexKeyBase::default(), &p).await.unwrap();
+		let vs: Vec<_> = (0..50).map(|i| (thing(i), vector(vec![i as i64, 0, 0]))).collect();
+		for (rid, v) in &vs {
+			t.index_document(&mut tx, rid, v).await.unwrap();
+		}
+		// Records sharing a vector are both returned
+		t.index_document(&mut tx, &thing(100), &vector(vec![3, 0, 0])).await.unwrap();
+		let res = t.knn(&mut tx, &vector(vec![3, 0, 0]), 2).await.unwrap();
+		assert_eq!(res, vec![(thing(100), 0.0), (thing(3), 0.0)]);
+		t.remove_document(&mut tx, &thing(100), &vector(vec![3, 0, 0])).await.unwrap();
+		for (rid, v) in &vs {
+			t.remove_document(&mut tx, rid, v).await.unwrap();
+		}
+		assert!(t.knn(&mut tx, &vector(vec![3, 0, 0]), 2).await.unwrap().is_empty());
+		assert_eq!(t.statistics(&mut tx).await.unwrap().nodes_count, 0);
+		// Every node was deleted
+		t.finish(&mut tx).await.unwrap();
+		let res = tx.scan(vec![0x00]..vec![0xff], 100).await.unwrap();
+		assert_eq!(res.len(), 1);
+		tx.cancel().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_invalid_vectors() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let p = params(Distance::Euclidean);
+		let mut t = MTree::new(&mut tx, IndexKeyBase::default(), &p).await.unwrap();
+		let res = t.index_document(&mut tx, &thing(1), &vector(vec![1, 2])).await;
+		assert!(res.is_err());
+		let res = t.index_document(&mut tx, &thing(1), &Value::from("test")).await;
+		assert!(res.is_err());
+		t.index_document(&mut tx, &thing(1), &Value::None).await.unwrap();
+		tx.cancel().await.unwrap();
+	}
+}
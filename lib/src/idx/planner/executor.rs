@@ -1,28 +1,30 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
-use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::ft::docids::{DocId, DocIds, NO_DOC_ID};
+use crate::idx::ft::query::MatchQuery;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::{FtIndex, MatchRef};
+use crate::idx::mtree::MTree;
 use crate::idx::planner::plan::IndexOption;
 use crate::idx::planner::tree::IndexMap;
 use crate::idx::IndexKeyBase;
 use crate::kvs;
 use crate::kvs::Key;
 use crate::sql::index::Index;
-use crate::sql::{Expression, Table, Thing, Value};
+use crate::sql::{Expression, Operator, Table, Thing, Value};
 use roaring::RoaringTreemap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub(crate) struct QueryExecutor {
 	table: String,
 	pre_match_expression: Option<Expression>,
-	pre_match_entry: Option<FtEntry>,
 	ft_map: HashMap<String, FtIndex>,
 	mr_entries: HashMap<MatchRef, FtEntry>,
 	exp_entries: HashMap<Expression, FtEntry>,
+	knn_entries: HashMap<Expression, KnnEntry>,
 }
 
 impl QueryExecutor {
@@ -38,66 +40,88 @@ impl QueryExecutor {
 		let mut mr_entries = HashMap::default();
 		let mut exp_entries = HashMap::default();
 		let mut ft_map = HashMap::default();
+		let mut knn_entries = HashMap::default();
 
 		// Create all the instances of FtIndex
 		// Build the FtEntries and map them to Expressions and MatchRef
-		for (exp, io) in index_map.consume() {
-			let mut entry = None;
-			if let Index::Search {
-				az,
-				order,
-				sc,
-				hl,
-			} = &io.ix().index
-			{
-				let ixn = &io.ix().name.0;
-				if let Some(ft) = ft_map.get(ixn) {
-					if entry.is_none() {
-						entry = FtEntry::new(&mut run, ft, io).await?;
-					}
-				} else {
+		for (exp, ios) in index_map.consume() {
+			for io in ios {
+				let mut entry = None;
+				// Find the nearest neighbours of KNN expressions up front
+				if let (Index::MTree(p), Operator::Knn(k)) = (&io.ix().index, io.op()) {
 					let ikb = IndexKeyBase::new(opt, io.ix());
-					let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
-					let ft = FtIndex::new(&mut run, az, ikb, *order, sc, *hl).await?;
-					let ixn = ixn.to_owned();
-					if entry.is_none() {
-						entry = FtEntry::new(&mut run, &ft, io).await?;
+					let mt = MTree::new(&mut run, ikb, p).await?;
+					let res = mt.knn(&mut run, io.value(), *k as usize).await?;
+					let things: Vec<Thing> = res.into_iter().map(|(t, _)| t).collect();
+					knn_entries.insert(
+						exp.clone(),
+						KnnEntry {
+							set: things.iter().cloned().collect(),
+							things: Arc::new(things),
+						},
+					);
+					continue;
+				}
+				if let Index::Search {
+					az,
+					order,
+					sc,
+					hl,
+					wt,
+				} = &io.ix().index
+				{
+					let ixn = &io.ix().name.0;
+					if let Some(ft) = ft_map.get(ixn) {
+						if entry.is_none() {
+							entry = FtEntry::new(&mut run, ft, io).await?;
+						}
+					} else {
+						let ikb = IndexKeyBase::new(opt, io.ix());
+						let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
+						let ft =
+							FtIndex::new(&mut run, az, ikb, *order, sc, *hl, wt.as_ref()).await?;
+						let ixn = ixn.to_owned();
+						if entry.is_none() {
+							entry = FtEntry::new(&mut run, &ft, io).await?;
+						}
+						ft_map.insert(ixn, ft);
 					}
-					ft_map.insert(ixn, ft);
 				}
-			}
 
-			if let Some(e) = entry {
-				if let Some(mr) = e.0.index_option.match_ref() {
-					if mr_entries.insert(*mr, e.clone()).is_some() {
-						return Err(Error::DuplicatedMatchRef {
-							mr: *mr,
-						});
+				if let Some(e) = entry {
+					if let Some(mr) = e.0.index_option.match_ref() {
+						if mr_entries.insert(*mr, e.clone()).is_some() {
+							return Err(Error::DuplicatedMatchRef {
+								mr: *mr,
+							});
+						}
 					}
+					exp_entries.insert(exp.clone(), e);
 				}
-				exp_entries.insert(exp, e);
 			}
 		}
 
-		let mut pre_match_entry = None;
-		if let Some(exp) = &pre_match_expression {
-			pre_match_entry = exp_entries.get(exp).cloned();
-		}
 		Ok(Self {
 			table: table.0.clone(),
 			pre_match_expression,
-			pre_match_entry,
 			ft_map,
 			mr_entries,
 			exp_entries,
+			knn_entries,
 		})
 	}
 
-	pub(super) fn pre_match_terms_docs(&self) -> Option<TermsDocs> {
-		if let Some(entry) = &self.pre_match_entry {
-			return Some(entry.0.terms_docs.clone());
-		}
-		None
+	pub(super) fn terms_docs(&self, exp: &Expression) -> Option<TermsDocs> {
+		self.exp_entries.get(exp).map(|e| e.0.terms_docs.clone())
+	}
+
+	pub(super) fn query(&self, exp: &Expression) -> Option<MatchQuery> {
+		self.exp_entries.get(exp).map(|e| e.0.query.clone())
+	}
+
+	/// The records nearest to the vector of a KNN expression, ordered by distance
+	pub(super) fn knn_things(&self, exp: &Expression) -> Option<Arc<Vec<Thing>>> {
+		self.knn_entries.get(exp).map(|e| e.things.clone())
 	}
 
 	fn get_match_ref(match_ref: &Value) -> Option<MatchRef> {
@@ -131,22 +155,8 @@ impl QueryExecutor {
 				let mut run = txn.lock().await;
 				let doc_key: Key = thg.into();
 				if let Some(doc_id) = ft.0.doc_ids.get_doc_id(&mut run, doc_key).await? {
-					let term_goals = ft.0.terms_docs.len();
-					// If there is no terms, it can't be a match
-					if term_goals == 0 {
-						return Ok(Value::Bool(false));
-					}
-					for opt_td in ft.0.terms_docs.iter() {
-						if let Some((_, docs)) = opt_td {
-							if !docs.contains(doc_id) {
-								return Ok(Value::Bool(false));
-							}
-						} else {
-							// If one of the term is missing, it can't be a match
-							return Ok(Value::Bool(false));
-						}
-					}
-					return Ok(Value::Bool(true));
+					let res = ft.0.query.matches(&mut run, &ft.0.terms_docs, doc_id).await?;
+					return Ok(Value::Bool(res));
 				}
 				return Ok(Value::Bool(false));
 			}
@@ -158,6 +168,25 @@ impl QueryExecutor {
 		})
 	}
 
+	pub(crate) fn knn(&self, thg: &Thing, exp: &Expression) -> Result<Value, Error> {
+		// If we find the expression in `pre_match_expression`,
+		// the records are returned by the vector index itself.
+		if let Some(pme) = &self.pre_match_expression {
+			if pme.eq(exp) {
+				return Ok(Value::Bool(true));
+			}
+		}
+		// Otherwise, check if the record is one of the nearest neighbours
+		if thg.tb.eq(&self.table) {
+			if let Some(e) = self.knn_entries.get(exp) {
+				return Ok(Value::Bool(e.set.contains(thg)));
+			}
+		}
+		Err(Error::NoIndexFoundForKnn {
+			value: exp.to_string(),
+		})
+	}
+
 	fn get_ft_entry(&self, match_ref: &Value) -> Option<&FtEntry> {
 		if let Some(mr) = Self::get_match_ref(match_ref) {
 			self.mr_entries.get(&mr)
@@ -186,8 +215,11 @@ impl QueryExecutor {
 	) -> Result<Value, Error> {
 		if let Some((e, ft)) = self.get_ft_entry_and_index(match_ref) {
 			let mut run = txn.lock().await;
+			let io = &e.0.index_option;
+			// The columns of the index which precede the highlighted one
+			let cols = &io.ix().cols[..io.col()];
 			return ft
-				.highlight(&mut run, thg, &e.0.terms, prefix, suffix, e.0.index_option.id(), doc)
+				.highlight(&mut run, thg, &e.0.terms, prefix, suffix, io.id(), cols, doc)
 				.await;
 		}
 		Ok(Value::None)
@@ -216,7 +248,8 @@ impl QueryExecutor {
 		if let Some(e) = self.get_ft_entry(match_ref) {
 			if let Some(scorer) = &e.0.scorer {
 				let mut run = txn.lock().await;
-				if doc_id.is_none() {
+				// Records returned by another index don't have a doc id
+				if matches!(doc_id, None | Some(NO_DOC_ID)) {
 					let key: Key = rid.into();
 					doc_id = e.0.doc_ids.get_doc_id(&mut run, key).await?;
 				};
@@ -240,6 +273,7 @@ struct Inner {
 	doc_ids: DocIds,
 	terms: Vec<Option<TermId>>,
 	terms_docs: Arc<Vec<Option<(TermId, RoaringTreemap)>>>,
+	query: MatchQuery,
 	scorer: Option<BM25Scorer>,
 }
 
@@ -250,17 +284,29 @@ impl FtEntry {
 		io: IndexOption,
 	) -> Result<Option<Self>, Error> {
 		if let Some(qs) = io.qs() {
-			let terms = ft.extract_terms(tx, qs.to_owned()).await?;
-			let terms_docs = Arc::new(ft.get_terms_docs(tx, &terms).await?);
+			let query = ft.extract_terms(tx, qs.to_owned()).await?;
+			let mut terms_docs = ft.get_terms_docs(tx, &query.terms()).await?;
+			// A multi-field index only matches the documents within the queried field
+			if io.ix().cols.len() > 1 {
+				terms_docs = ft.get_field_terms_docs(tx, terms_docs, io.col()).await?;
+			}
+			let terms_docs = Arc::new(terms_docs);
 			Ok(Some(Self(Arc::new(Inner {
 				index_option: io,
 				doc_ids: ft.doc_ids(tx).await?,
-				scorer: ft.new_scorer(tx, terms_docs.clone()).await?,
-				terms,
+				scorer: ft.new_scorer(tx, terms_docs.clone(), query.clone()).await?,
+				terms: query.matched_terms(),
 				terms_docs,
+				query,
 			}))))
 		} else {
 			Ok(None)
 		}
 	}
 }
+
+/// The records nearest to the vector of a KNN expression
+struct KnnEntry {
+	things: Arc<Vec<Thing>>,
+	set: HashSet<Thing>,
+}
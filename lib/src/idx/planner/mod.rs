@@ -3,26 +3,45 @@ pub(crate) mod plan;
 mod tree;
 
 use crate::ctx::Context;
-use crate::dbs::{Iterable, Options};
+use crate::dbs::{Iterable, Options, Transaction};
 use crate::err::Error;
+use crate::idx::build::IndexBuild;
+use crate::idx::ft::MatchRef;
 use crate::idx::planner::executor::QueryExecutor;
-use crate::idx::planner::plan::{Plan, PlanBuilder};
-use crate::idx::planner::tree::{Node, Tree};
-use crate::sql::{Cond, Operator, Table};
+use crate::idx::planner::plan::{IndexPlan, Plan, PlanBuilder};
+use crate::idx::planner::tree::{IndexMap, Node, Tree};
+use crate::sql::index::Index;
+use crate::sql::scoring::Scoring;
+use crate::sql::{Cond, Field, Fields, Function, Idiom, Kind, Operator, Orders, Table, Value};
 use std::collections::HashMap;
 
 pub(crate) struct QueryPlanner<'a> {
 	opt: &'a Options,
 	cond: &'a Option<Cond>,
+	/// The order in which the records are output, if it can be satisfied by an index
+	order: Option<&'a Orders>,
+	/// The fields of the SELECT statement
+	fields: &'a Fields,
+	/// The number of first records needed, when there is an order
+	limit: Option<usize>,
 	/// There is one executor per table
 	executors: HashMap<String, QueryExecutor>,
 }
 
 impl<'a> QueryPlanner<'a> {
-	pub(crate) fn new(opt: &'a Options, cond: &'a Option<Cond>) -> Self {
+	pub(crate) fn new(
+		opt: &'a Options,
+		cond: &'a Option<Cond>,
+		order: Option<&'a Orders>,
+		fields: &'a Fields,
+		limit: Option<usize>,
+	) -> Self {
 		Self {
 			opt,
 			cond,
+			order,
+			fields,
+			limit,
 			executors: HashMap::default(),
 		}
 	}
@@ -35,17 +54,139 @@ impl<'a> QueryPlanner<'a> {
 		let txn = ctx.try_clone_transaction()?;
 		let res = Tree::build(ctx, self.opt, &txn, &t, self.cond).await?;
 		if let Some((node, im)) = res {
-			if let Some(plan) = AllAndStrategy::build(&node)? {
-				let e = QueryExecutor::new(self.opt, &txn, &t, im, Some(plan.e.clone())).await?;
+			let plan = match AllAndStrategy::build(self.opt, &txn, &node).await? {
+				Some(p) => Some(self.score_plan(p)),
+				None => AnyOrStrategy::build(self.opt, &txn, &node).await?,
+			};
+			if let Some(plan) = plan {
+				let e = QueryExecutor::new(self.opt, &txn, &t, im, plan.expression()).await?;
 				self.executors.insert(t.0.clone(), e);
 				return Ok(Iterable::Index(t, plan));
 			}
 			let e = QueryExecutor::new(self.opt, &txn, &t, im, None).await?;
 			self.executors.insert(t.0.clone(), e);
 		}
+		if let Some(plan) = self.order_plan(&txn, &t).await? {
+			if !self.executors.contains_key(&t.0) {
+				let e = QueryExecutor::new(self.opt, &txn, &t, IndexMap::default(), None).await?;
+				self.executors.insert(t.0.clone(), e);
+			}
+			return Ok(Iterable::Index(t, plan));
+		}
 		Ok(Iterable::Table(t))
 	}
 
+	/// Find an index which returns the records in the order of the ORDER BY clause.
+	/// The keys of an index are only ordered like the values for some kinds of
+	/// values, so the field needs to be defined with one of these types. The
+	/// index is read backwards for a descending order, when the storage engine
+	/// supports it.
+	async fn order_plan(&self, txn: &Transaction, t: &Table) -> Result<Option<Plan>, Error> {
+		let order = match self.order.map(|o| o.as_slice()) {
+			Some([o]) if !o.random && !o.collate && !o.numeric => o,
+			_ => return Ok(None),
+		};
+		// The records are ordered by the output value, which must be the stored field
+		if !self.is_stored_field(&order.order) {
+			return Ok(None);
+		}
+		let mut run = txn.lock().await;
+		// The index can only be read backwards by some storage engines
+		if !order.direction && !run.can_scan_rev() {
+			return Ok(None);
+		}
+		let fds = run.all_fd(self.opt.ns(), self.opt.db(), &t.0).await?;
+		let ordered = fds.iter().any(|fd| {
+			fd.name == order.order && fd.kind.as_ref().map_or(false, Self::is_ordered_kind)
+		});
+		if !ordered {
+			return Ok(None);
+		}
+		let ixs = IndexBuild::ready(&mut run, self.opt.ns(), self.opt.db(), &t.0).await?;
+		let ix = ixs.iter().find(|ix| {
+			matches!(ix.index, Index::Idx | Index::Uniq) && ix.cols.first() == Some(&order.order)
+		});
+		Ok(ix.map(|ix| Plan::Order(ix.clone(), order.direction)))
+	}
+
+	/// Return the records by descending score when the first records are ordered
+	/// by the score of the full-text query resolved by the index.
+	fn score_plan(&self, p: IndexPlan) -> Plan {
+		if let (Some([o]), Some(k)) = (self.order.map(|o| o.as_slice()), self.limit) {
+			let desc = !o.random && !o.collate && !o.numeric && !o.direction;
+			if desc && p.p.is_empty() && p.and.is_empty() {
+				if let (
+					Operator::Matches(_),
+					Some(mr),
+					Index::Search {
+						sc: Scoring::Bm {
+							..
+						},
+						..
+					},
+				) = (p.i.op(), p.i.match_ref(), &p.i.ix().index)
+				{
+					if self.is_score(&o.order, *mr) {
+						return Plan::Score(p, k);
+					}
+				}
+			}
+		}
+		Plan::Index(p)
+	}
+
+	/// Check if the field is the score of the given match reference
+	fn is_score(&self, id: &Idiom, mr: MatchRef) -> bool {
+		self.fields.iter().any(|f| match f {
+			Field::Single {
+				expr: Value::Function(f),
+				alias: Some(a),
+			} if a == id => match f.as_ref() {
+				Function::Normal(name, args) if name == "search::score" => {
+					matches!(args.as_slice(), [Value::Number(n)] if n.to_int() == mr as i64)
+				}
+				_ => false,
+			},
+			_ => false,
+		})
+	}
+
+	/// Check if the field is output with its stored value, and is not replaced
+	/// by a projection which is aliased to it, or which computes a value for it
+	fn is_stored_field(&self, id: &Idiom) -> bool {
+		// The records are not output as objects with a VALUE clause
+		if self.fields.1 {
+			return false;
+		}
+		self.fields.iter().all(|f| match f {
+			Field::All => true,
+			Field::Single {
+				expr,
+				alias,
+			} => {
+				let name = match (alias, expr) {
+					(Some(a), _) => a,
+					(None, Value::Idiom(i)) => i,
+					_ => return true,
+				};
+				// A projection of a part of the field, or of an object containing it
+				if !name.starts_with(id) && !id.starts_with(name) {
+					return true;
+				}
+				name == id && matches!(expr, Value::Idiom(i) if i == id)
+			}
+		})
+	}
+
+	/// Check if the keys of the values of this kind are ordered like the values
+	fn is_ordered_kind(kind: &Kind) -> bool {
+		match kind {
+			Kind::Bool | Kind::Datetime | Kind::Float | Kind::Int | Kind::String => true,
+			Kind::Option(k) => Self::is_ordered_kind(k),
+			_ => false,
+		}
+	}
+
 	pub(crate) fn finish(self) -> Option<HashMap<String, QueryExecutor>> {
 		if self.executors.is_empty() {
 			None
@@ -62,12 +203,16 @@ struct AllAndStrategy {
 /// Successful if every boolean operators are AND
 /// and there is at least one condition covered by an index
 impl AllAndStrategy {
-	fn build(node: &Node) -> Result<Option<Plan>, Error> {
+	async fn build(
+		opt: &Options,
+		txn: &Transaction,
+		node: &Node,
+	) -> Result<Option<IndexPlan>, Error> {
 		let mut s = AllAndStrategy {
 			b: PlanBuilder::default(),
 		};
 		match s.eval_node(node) {
-			Ok(_) => match s.b.build() {
+			Ok(_) => match s.b.build(opt, txn).await {
 				Ok(p) => Ok(Some(p)),
 				Err(Error::BypassQueryPlanner) => Ok(None),
 				Err(e) => Err(e),
@@ -80,12 +225,12 @@ impl AllAndStrategy {
 	fn eval_node(&mut self, node: &Node) -> Result<(), Error> {
 		match node {
 			Node::Expression {
-				io: index_option,
+				ios: index_options,
 				left,
 				right,
 				exp: expression,
 			} => {
-				if let Some(io) = index_option {
+				for io in index_options {
 					self.b.add_index_option(expression.clone(), io.clone());
 				}
 				self.eval_expression(left, right, expression.operator())
@@ -104,3 +249,41 @@ impl AllAndStrategy {
 		Ok(())
 	}
 }
+
+/// Successful if the conditions are OR-ed branches,
+/// and every branch is resolved by the AllAndStrategy
+struct AnyOrStrategy;
+
+impl AnyOrStrategy {
+	async fn build(opt: &Options, txn: &Transaction, node: &Node) -> Result<Option<Plan>, Error> {
+		let mut branches = vec![];
+		Self::eval_node(node, &mut branches);
+		if branches.len() < 2 {
+			return Ok(None);
+		}
+		let mut plans = Vec::with_capacity(branches.len());
+		for b in branches {
+			match AllAndStrategy::build(opt, txn, b).await? {
+				Some(p) => plans.push(p),
+				// A branch without an index requires a table scan
+				None => return Ok(None),
+			}
+		}
+		Ok(Some(Plan::Union(plans)))
+	}
+
+	fn eval_node<'a>(node: &'a Node, branches: &mut Vec<&'a Node>) {
+		match node {
+			Node::Expression {
+				left,
+				right,
+				exp,
+				..
+			} if exp.operator().eq(&Operator::Or) => {
+				Self::eval_node(left, branches);
+				Self::eval_node(right, branches);
+			}
+			_ => branches.push(node),
+		}
+	}
+}
@@ -1,19 +1,23 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::docids::{DocId, NO_DOC_ID};
+use crate::idx::ft::query::MatchQuery;
 use crate::idx::ft::termdocs::TermsDocs;
+use crate::idx::ft::tophits::TopHitsIterator;
 use crate::idx::ft::{FtIndex, HitsIterator, MatchRef};
 use crate::idx::planner::executor::QueryExecutor;
+use crate::idx::stats::IndexStatistics;
 use crate::idx::IndexKeyBase;
 use crate::key;
-use crate::kvs::Key;
-use crate::sql::index::Index;
+use crate::kvs::{Key, Val};
+use crate::sql::index::{Index, Weights};
 use crate::sql::scoring::Scoring;
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Expression, Ident, Idiom, Object, Operator, Thing, Value};
+use crate::sql::{Array, Expression, Ident, Idiom, Number, Object, Operator, Thing, Value};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
+use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Default)]
@@ -21,49 +25,327 @@ pub(super) struct PlanBuilder {
 	indexes: Vec<(Expression, IndexOption)>,
 }
 
+/// A candidate plan, with the estimated number of records it returns
+struct Candidate<'a> {
+	/// The conditions covering the leftmost prefix of the index columns
+	p: Vec<&'a (Expression, IndexOption)>,
+	/// The estimated number of records
+	rows: f64,
+	/// The statistics of the index, if it was analyzed
+	stats: Option<IndexStatistics>,
+}
+
 impl PlanBuilder {
+	/// The estimated number of records matching a full-text condition
+	const SEARCH_ROWS: f64 = 100.0;
+	/// The cost of fetching a record, relative to reading an index entry
+	const FETCH_COST: f64 = 4.0;
+
 	pub(super) fn add_index_option(&mut self, e: Expression, i: IndexOption) {
 		self.indexes.push((e, i));
 	}
 
-	pub(super) fn build(mut self) -> Result<Plan, Error> {
-		// TODO select the best option if there are several (cost based)
-		if let Some((e, i)) = self.indexes.pop() {
-			Ok(Plan::new(e, i))
-		} else {
-			Err(Error::BypassQueryPlanner)
+	/// Select the candidate with the lowest estimated number of records, and
+	/// intersect it with other indexes when this is estimated to be cheaper
+	/// than fetching and filtering the records.
+	pub(super) async fn build(self, opt: &Options, txn: &Transaction) -> Result<IndexPlan, Error> {
+		let mut candidates = self.candidates(opt, txn).await?;
+		// On equal estimates, prefer the last condition
+		let best = candidates
+			.iter()
+			.enumerate()
+			.min_by(|(i, a), (j, b)| a.rows.total_cmp(&b.rows).then(j.cmp(i)))
+			.map(|(i, _)| i)
+			.ok_or(Error::BypassQueryPlanner)?;
+		let best = candidates.remove(best);
+		let mut plan = Self::plan(&best);
+		// Intersections are only considered with analyzed indexes
+		if best.stats.is_some() {
+			let mut rows = best.rows;
+			candidates.sort_by(|a, b| a.rows.total_cmp(&b.rows));
+			for c in candidates {
+				let ix = c.p[0].1.ix();
+				let stats = match &c.stats {
+					Some(stats) => stats,
+					None => continue,
+				};
+				if rows <= 1.0 || ix.name == plan.i.ix().name {
+					continue;
+				}
+				if plan.and.iter().any(|p| p.i.ix().name == ix.name) {
+					continue;
+				}
+				// The fraction of the records returned by the candidate
+				let sel = c.rows / (stats.entries().max(1) as f64);
+				if c.rows < Self::FETCH_COST * rows * (1.0 - sel) {
+					rows *= sel;
+					plan.and.push(Self::plan(&c));
+				}
+			}
 		}
+		Ok(plan)
+	}
+
+	fn plan(c: &Candidate) -> IndexPlan {
+		let ((e, i), p) = c.p.split_last().unwrap();
+		let p = p.iter().map(|(_, io)| io.clone()).collect();
+		IndexPlan::new(e.clone(), i.clone(), p)
+	}
+
+	/// Collect the candidate plans, and estimate the number of records they return.
+	/// A composite index is used with the longest leftmost prefix of its columns
+	/// which is covered by the conditions.
+	async fn candidates(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Vec<Candidate<'_>>, Error> {
+		let mut run = txn.lock().await;
+		// Load the statistics of the plain and unique indexes
+		let mut stats: HashMap<&Ident, Option<IndexStatistics>> = HashMap::new();
+		for (_, io) in &self.indexes {
+			let ix = io.ix();
+			if matches!(ix.index, Index::Idx | Index::Uniq) && !stats.contains_key(&ix.name) {
+				stats.insert(&ix.name, IndexStatistics::get(&mut run, opt, ix).await?);
+			}
+		}
+		// The indexes which were not analyzed are estimated from their definition,
+		// with the number of entries of the analyzed indexes of the table
+		let entries = stats.values().flatten().map(IndexStatistics::entries).max();
+		let mut res: Vec<Candidate> = vec![];
+		for o in &self.indexes {
+			let ix = o.1.ix();
+			// A full-text index can be searched through any of its columns
+			let search = matches!(ix.index, Index::Search { .. });
+			// The trailing columns of a composite index can't be used on their own
+			if o.1.col() != 0 && !search {
+				continue;
+			}
+			let p = match ix.cols.len() > 1 && !search {
+				true => self.composite_prefix(ix),
+				false => vec![o],
+			};
+			if res.iter().any(|c| c.p == p) {
+				continue;
+			}
+			let rows = match ix.index {
+				Index::Search {
+					..
+				} => Self::SEARCH_ROWS,
+				Index::MTree(_) => match o.1.op() {
+					Operator::Knn(k) => *k as f64,
+					_ => Self::SEARCH_ROWS,
+				},
+				_ => match &stats[&ix.name] {
+					Some(s) => Self::estimate(ix, s, &p),
+					None => Self::estimate(ix, &IndexStatistics::estimate(ix, entries), &p),
+				},
+			};
+			res.push(Candidate {
+				p,
+				rows,
+				stats: stats.get(&ix.name).cloned().flatten(),
+			});
+		}
+		Ok(res)
+	}
+
+	/// Estimate the number of records returned by a prefix of the index columns
+	fn estimate(
+		ix: &DefineIndexStatement,
+		stats: &IndexStatistics,
+		p: &[&(Expression, IndexOption)],
+	) -> f64 {
+		let (_, io) = p[p.len() - 1];
+		let m = p.len();
+		// A unique index contains one entry per value
+		let per_value = match ix.index == Index::Uniq && m == ix.cols.len() {
+			true => stats.rows_per_value(m).min(1.0),
+			false => stats.rows_per_value(m),
+		};
+		match (io.op(), io.value()) {
+			(Operator::Equal, _) => per_value,
+			(Operator::Inside, Value::Array(a)) => per_value * a.len() as f64,
+			// Assume a range matches a third of the entries
+			_ => stats.rows_per_value(m - 1) / 3.0,
+		}
+	}
+
+	/// Find the leftmost prefix of a composite index covered by the conditions.
+	/// The prefix is made of equality conditions, and may end with one range
+	/// or INSIDE condition.
+	fn composite_prefix(&self, ix: &DefineIndexStatement) -> Vec<&(Expression, IndexOption)> {
+		let find = |col: usize, eq: bool| {
+			self.indexes.iter().find(|(_, io)| {
+				io.ix().name == ix.name && io.col() == col && (io.op() == &Operator::Equal) == eq
+			})
+		};
+		let mut p = vec![];
+		for col in 0..ix.cols.len() {
+			if let Some(o) = find(col, true) {
+				p.push(o);
+			} else {
+				if let Some(o) = find(col, false) {
+					p.push(o);
+				}
+				break;
+			}
+		}
+		p
 	}
 }
 
-pub(crate) struct Plan {
+/// The plan of the records returned by the index
+pub(crate) enum Plan {
+	/// The records returned by one index
+	Index(IndexPlan),
+	/// The deduplicated union of the records returned by every OR-ed condition
+	Union(Vec<IndexPlan>),
+	/// Every record of the index, in ascending (true) or descending
+	/// (false) order of the values of its first column
+	Order(DefineIndexStatement, bool),
+	/// The records matching a full-text query, in descending order of their
+	/// score, when (at least) the given number of first records are needed
+	Score(IndexPlan, usize),
+}
+
+impl Plan {
+	/// The expression which every record returned by the plan is known to match
+	pub(super) fn expression(&self) -> Option<Expression> {
+		match self {
+			Plan::Index(p) | Plan::Score(p, _) => Some(p.e.clone()),
+			Plan::Union(_) | Plan::Order(..) => None,
+		}
+	}
+
+	/// Check if the records are returned in the order of the ORDER BY clause
+	pub(crate) fn is_ordered(&self) -> bool {
+		matches!(self, Plan::Order(..) | Plan::Score(..))
+	}
+
+	pub(crate) async fn new_iterator(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		exe: &QueryExecutor,
+	) -> Result<Box<dyn ThingIterator>, Error> {
+		match self {
+			Plan::Index(p) => p.new_iterator(opt, txn, exe).await,
+			Plan::Union(ps) => {
+				let mut its = Vec::with_capacity(ps.len());
+				for p in ps {
+					its.push(p.new_iterator(opt, txn, exe).await?);
+				}
+				Ok(Box::new(UnionThingIterator::new(its)))
+			}
+			Plan::Order(ix, asc) => Ok(Box::new(IndexOrderThingIterator::new(opt, ix, *asc))),
+			Plan::Score(p, k) => {
+				let e = &p.e;
+				Ok(Box::new(
+					MatchesTopThingIterator::new(
+						opt,
+						txn,
+						p.i.ix(),
+						exe.terms_docs(e),
+						exe.query(e),
+						*k,
+					)
+					.await?,
+				))
+			}
+		}
+	}
+
+	pub(crate) fn explain(&self) -> Value {
+		match self {
+			Plan::Index(p) => p.explain(),
+			Plan::Union(ps) => Value::Object(Object::from(HashMap::from([(
+				"union",
+				Value::from(ps.iter().map(IndexPlan::explain).collect::<Vec<_>>()),
+			)]))),
+			Plan::Order(ix, asc) => Value::Object(Object::from(HashMap::from([
+				("index", Value::from(ix.name.0.to_owned())),
+				(
+					"order",
+					Value::from(if *asc {
+						"ASC"
+					} else {
+						"DESC"
+					}),
+				),
+			]))),
+			Plan::Score(p, k) => {
+				let mut e = p.explain();
+				if let Value::Object(o) = &mut e {
+					o.insert("top".to_owned(), Value::from(*k));
+				}
+				e
+			}
+		}
+	}
+}
+
+pub(crate) struct IndexPlan {
 	pub(super) e: Expression,
 	pub(super) i: IndexOption,
+	/// The equality conditions on the leading columns of a composite index
+	pub(super) p: Vec<IndexOption>,
+	/// The plans of other indexes whose records are intersected
+	pub(super) and: Vec<IndexPlan>,
 }
 
-impl Plan {
-	pub(super) fn new(e: Expression, i: IndexOption) -> Self {
+impl IndexPlan {
+	pub(super) fn new(e: Expression, i: IndexOption, p: Vec<IndexOption>) -> Self {
 		Self {
 			e,
 			i,
+			p,
+			and: vec![],
 		}
 	}
 
-	pub(crate) async fn new_iterator(
+	async fn new_iterator(
 		&self,
 		opt: &Options,
 		txn: &Transaction,
 		exe: &QueryExecutor,
 	) -> Result<Box<dyn ThingIterator>, Error> {
-		self.i.new_iterator(opt, txn, exe).await
+		let it = self.i.new_iterator(opt, txn, exe, &self.e, &self.p).await?;
+		if self.and.is_empty() {
+			return Ok(it);
+		}
+		let mut others = Vec::with_capacity(self.and.len());
+		for p in &self.and {
+			others.push(p.i.new_iterator(opt, txn, exe, &p.e, &p.p).await?);
+		}
+		Ok(Box::new(IntersectThingIterator::new(it, others)))
 	}
 
-	pub(crate) fn explain(&self) -> Value {
-		Value::Object(Object::from(HashMap::from([
-			("index", Value::from(self.i.ix().name.0.to_owned())),
+	fn explain(&self) -> Value {
+		let ix = self.i.ix();
+		let mut e = HashMap::from([
+			("index", Value::from(ix.name.0.to_owned())),
 			("operator", Value::from(self.i.op().to_string())),
 			("value", self.i.value().clone()),
-		])))
+		]);
+		// Show which columns of a composite index are used
+		if ix.cols.len() > 1 && !matches!(ix.index, Index::Search { .. }) {
+			let cols = ix.cols.iter().take(self.p.len() + 1);
+			let vals = self.p.iter().chain([&self.i]);
+			e.insert(
+				"prefix",
+				Value::from(cols.map(|c| Value::from(c.to_string())).collect::<Vec<_>>()),
+			);
+			e.insert("value", Value::from(vals.map(|io| io.value().clone()).collect::<Vec<_>>()));
+		}
+		// Show the indexes whose records are intersected
+		if !self.and.is_empty() {
+			e.insert(
+				"intersect",
+				Value::from(self.and.iter().map(IndexPlan::explain).collect::<Vec<_>>()),
+			);
+		}
+		Value::Object(Object::from(e))
 	}
 }
 
@@ -73,6 +355,7 @@ pub(super) struct IndexOption(Arc<Inner>);
 #[derive(Debug, Eq, PartialEq, Hash)]
 pub(super) struct Inner {
 	ix: DefineIndexStatement,
+	col: usize,
 	id: Idiom,
 	v: Value,
 	qs: Option<String>,
@@ -83,6 +366,7 @@ pub(super) struct Inner {
 impl IndexOption {
 	pub(super) fn new(
 		ix: DefineIndexStatement,
+		col: usize,
 		id: Idiom,
 		op: Operator,
 		v: Value,
@@ -91,6 +375,7 @@ impl IndexOption {
 	) -> Self {
 		Self(Arc::new(Inner {
 			ix,
+			col,
 			id,
 			op,
 			v,
@@ -103,6 +388,11 @@ impl IndexOption {
 		&self.0.ix
 	}
 
+	/// The position of the field in the columns of the index
+	pub(super) fn col(&self) -> usize {
+		self.0.col
+	}
+
 	pub(super) fn op(&self) -> &Operator {
 		&self.0.op
 	}
@@ -128,40 +418,71 @@ impl IndexOption {
 		opt: &Options,
 		txn: &Transaction,
 		exe: &QueryExecutor,
+		e: &Expression,
+		prefix: &[IndexOption],
 	) -> Result<Box<dyn ThingIterator>, Error> {
+		let single = self.ix().cols.len() == 1;
 		match &self.ix().index {
 			Index::Idx => {
-				if self.op() == &Operator::Equal {
+				if single && self.op() == &Operator::Equal {
 					return Ok(Box::new(NonUniqueEqualThingIterator::new(
 						opt,
 						self.ix(),
 						self.value(),
 					)?));
 				}
+				if let Some(r) =
+					IndexRangeThingIterator::new(opt, self.ix(), prefix, self.op(), self.value())
+				{
+					return Ok(Box::new(r));
+				}
 			}
 			Index::Uniq => {
-				if self.op() == &Operator::Equal {
+				if single && self.op() == &Operator::Equal {
 					return Ok(Box::new(UniqueEqualThingIterator::new(
 						opt,
 						self.ix(),
 						self.value(),
 					)?));
 				}
+				if let Some(r) =
+					IndexRangeThingIterator::new(opt, self.ix(), prefix, self.op(), self.value())
+				{
+					return Ok(Box::new(r));
+				}
 			}
 			Index::Search {
 				az,
 				hl,
 				sc,
 				order,
+				wt,
 			} => {
 				if let Operator::Matches(_) = self.op() {
-					let td = exe.pre_match_terms_docs();
+					let td = exe.terms_docs(e);
+					let q = exe.query(e);
 					return Ok(Box::new(
-						MatchesThingIterator::new(opt, txn, self.ix(), az, *hl, sc, *order, td)
-							.await?,
+						MatchesThingIterator::new(
+							opt,
+							txn,
+							self.ix(),
+							az,
+							*hl,
+							sc,
+							*order,
+							wt.as_ref(),
+							td,
+							q,
+						)
+						.await?,
 					));
 				}
 			}
+			Index::MTree(_) => {
+				if let Operator::Knn(_) = self.op() {
+					return Ok(Box::new(KnnThingIterator::new(exe.knn_things(e))));
+				}
+			}
 		}
 		Err(Error::BypassQueryPlanner)
 	}
@@ -245,6 +566,475 @@ impl ThingIterator for UniqueEqualThingIterator {
 	}
 }
 
+/// The key ranges of a plain or unique index, which hold the entries
+/// satisfying a range (`<`, `<=`, `>`, `>=`) or an `INSIDE` condition.
+///
+/// The ranges may contain more entries than strictly necessary. The
+/// documents are always checked against the condition afterwards, so
+/// the ranges only need to be sure to contain every matching entry.
+struct IndexRanges<'a> {
+	opt: &'a Options,
+	ix: &'a DefineIndexStatement,
+	/// The values of the leading columns of a composite index
+	prefix: Array,
+}
+
+impl<'a> IndexRanges<'a> {
+	/// The keys of a number are ordered by the kind of number (integer,
+	/// float or decimal) before they are ordered by value. These NaN
+	/// floats respectively have the lowest and highest key encodings.
+	const FLOAT_MIN: u64 = u64::MAX;
+	const FLOAT_MAX: u64 = i64::MAX as u64;
+
+	fn new(opt: &'a Options, ix: &'a DefineIndexStatement, prefix: Array) -> Self {
+		Self {
+			opt,
+			ix,
+			prefix,
+		}
+	}
+
+	/// The start of the index, or of the entries matching the prefix
+	fn all_beg(&self) -> Key {
+		let (ns, db, tb, ix) = (self.opt.ns(), self.opt.db(), &self.ix.what, &self.ix.name);
+		match self.prefix.is_empty() {
+			true => key::index::prefix(ns, db, tb, ix),
+			false => key::index::prefix_ids_composite_beg(ns, db, tb, ix, &self.prefix),
+		}
+	}
+
+	/// The end of the index, or of the entries matching the prefix
+	fn all_end(&self) -> Key {
+		let (ns, db, tb, ix) = (self.opt.ns(), self.opt.db(), &self.ix.what, &self.ix.name);
+		match self.prefix.is_empty() {
+			true => key::index::suffix(ns, db, tb, ix),
+			false => key::index::prefix_ids_composite_end(ns, db, tb, ix, &self.prefix),
+		}
+	}
+
+	/// The key before every entry equal to the value
+	fn beg(&self, v: Value) -> Key {
+		let mut fd = self.prefix.clone();
+		fd.push(v);
+		key::index::prefix_ids_composite_beg(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			&fd,
+		)
+	}
+
+	/// The key after every entry equal to the value
+	fn end(&self, v: Value) -> Key {
+		let mut fd = self.prefix.clone();
+		fd.push(v);
+		key::index::prefix_ids_composite_end(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			&fd,
+		)
+	}
+
+	fn float(v: u64) -> Value {
+		Value::from(f64::from_bits(v))
+	}
+
+	/// Compute the ranges for the given operator and value
+	fn ranges(&self, op: &Operator, v: &Value) -> Option<Vec<(Key, Key)>> {
+		let r = match (op, v) {
+			(Operator::Equal, v) => vec![(self.beg(v.clone()), self.end(v.clone()))],
+			(Operator::Inside, Value::Array(a)) => {
+				let mut r = vec![];
+				for v in a.iter() {
+					match v {
+						Value::Number(n) => r.append(&mut self.number_ranges(Some(n), Some(n))),
+						v => r.push((self.beg(v.clone()), self.end(v.clone()))),
+					}
+				}
+				r
+			}
+			(Operator::MoreThan | Operator::MoreThanOrEqual, Value::Number(n)) => {
+				self.number_ranges(Some(n), None)
+			}
+			(Operator::LessThan | Operator::LessThanOrEqual, Value::Number(n)) => {
+				self.number_ranges(None, Some(n))
+			}
+			(Operator::MoreThan, Value::Strand(_)) => vec![(self.end(v.clone()), self.all_end())],
+			(Operator::MoreThanOrEqual, Value::Strand(_)) => {
+				vec![(self.beg(v.clone()), self.all_end())]
+			}
+			(Operator::LessThan, Value::Strand(_)) => vec![(self.all_beg(), self.beg(v.clone()))],
+			(Operator::LessThanOrEqual, Value::Strand(_)) => {
+				vec![(self.all_beg(), self.end(v.clone()))]
+			}
+			_ => return None,
+		};
+		Some(r)
+	}
+
+	/// Compute the ranges containing the numbers between the bounds. A missing
+	/// lower bound includes any value ordered before a number, and a missing
+	/// upper bound includes any value ordered after a number.
+	fn number_ranges(&self, lower: Option<&Number>, upper: Option<&Number>) -> Vec<(Key, Key)> {
+		// The integers
+		let int_beg = match lower {
+			Some(n) => self.beg(Value::from(Self::int_bound(n, true))),
+			None => self.all_beg(),
+		};
+		let int_end = match upper {
+			Some(n) => self.end(Value::from(Self::int_bound(n, false))),
+			None => self.end(Value::from(i64::MAX)),
+		};
+		// The floats
+		let flt_beg = match lower {
+			Some(n) => self.beg(Value::from(Self::float_bound(n, true))),
+			None => self.beg(Self::float(Self::FLOAT_MIN)),
+		};
+		let flt_end = match upper {
+			Some(n) => self.end(Value::from(Self::float_bound(n, false))),
+			None => self.end(Self::float(Self::FLOAT_MAX)),
+		};
+		// The decimals are not ordered by value
+		let dec_beg = self.end(Self::float(Self::FLOAT_MAX));
+		let dec_end = match upper {
+			Some(_) => self.beg(Value::from("")),
+			None => self.all_end(),
+		};
+		vec![(int_beg, int_end), (flt_beg, flt_end), (dec_beg, dec_end)]
+	}
+
+	/// The integer bound containing every integer satisfying the number bound
+	fn int_bound(n: &Number, lower: bool) -> i64 {
+		match n {
+			Number::Int(v) => *v,
+			n => {
+				let f = n.to_float();
+				match lower {
+					true => (f.floor() as i64).saturating_sub(1),
+					false => (f.ceil() as i64).saturating_add(1),
+				}
+			}
+		}
+	}
+
+	/// The float bound containing every float satisfying the number bound
+	fn float_bound(n: &Number, lower: bool) -> f64 {
+		match n {
+			Number::Float(v) => *v,
+			n => {
+				// Allow for any loss of precision in the conversion
+				let f = n.to_float();
+				let d = f.abs() * f64::EPSILON * 2.0;
+				match lower {
+					true => f - d,
+					false => f + d,
+				}
+			}
+		}
+	}
+}
+
+struct IndexRangeThingIterator {
+	ranges: VecDeque<(Key, Key)>,
+}
+
+impl IndexRangeThingIterator {
+	fn new(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		prefix: &[IndexOption],
+		op: &Operator,
+		v: &Value,
+	) -> Option<Self> {
+		let prefix = prefix.iter().map(|io| io.value().clone()).collect::<Vec<_>>().into();
+		let ranges = IndexRanges::new(opt, ix, prefix).ranges(op, v)?;
+		Some(Self {
+			ranges: Self::merge(ranges),
+		})
+	}
+
+	/// Removes the empty ranges, and merges the overlapping ranges, so
+	/// that each index entry is only scanned once. The ranges of mixed
+	/// integers and floats, such as `INSIDE [1, 1.5]`, can overlap.
+	fn merge(mut ranges: Vec<(Key, Key)>) -> VecDeque<(Key, Key)> {
+		ranges.retain(|(beg, end)| beg < end);
+		ranges.sort();
+		let mut res: VecDeque<(Key, Key)> = VecDeque::with_capacity(ranges.len());
+		for (beg, end) in ranges {
+			match res.back_mut() {
+				Some((_, last)) if beg <= *last => {
+					if end > *last {
+						*last = end;
+					}
+				}
+				_ => res.push_back((beg, end)),
+			}
+		}
+		res
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for IndexRangeThingIterator {
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		while let Some((beg, end)) = self.ranges.front_mut() {
+			let min = beg.clone();
+			let max = end.clone();
+			let res = txn.lock().await.scan(min..max, limit).await?;
+			match res.last() {
+				// The range may contain more entries
+				Some((key, _)) if res.len() as u32 >= limit => {
+					*beg = key.clone();
+					beg.push(0x00);
+				}
+				// The range is exhausted
+				_ => {
+					self.ranges.pop_front();
+				}
+			}
+			if !res.is_empty() {
+				let res = res.iter().map(|(_, val)| (val.into(), NO_DOC_ID)).collect();
+				return Ok(res);
+			}
+		}
+		Ok(vec![])
+	}
+}
+
+/// Returns the records of the first iterator which are
+/// also returned by every other iterator.
+struct IntersectThingIterator {
+	first: Box<dyn ThingIterator>,
+	others: Vec<Box<dyn ThingIterator>>,
+	/// The records returned by every other iterator
+	set: Option<HashSet<Thing>>,
+}
+
+impl IntersectThingIterator {
+	fn new(first: Box<dyn ThingIterator>, others: Vec<Box<dyn ThingIterator>>) -> Self {
+		Self {
+			first,
+			others,
+			set: None,
+		}
+	}
+
+	async fn collect(
+		it: &mut Box<dyn ThingIterator>,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<HashSet<Thing>, Error> {
+		let mut set = HashSet::new();
+		loop {
+			let res = it.next_batch(txn, limit).await?;
+			if res.is_empty() {
+				return Ok(set);
+			}
+			set.extend(res.into_iter().map(|(t, _)| t));
+		}
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for IntersectThingIterator {
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		if self.set.is_none() {
+			let mut set: Option<HashSet<Thing>> = None;
+			for it in self.others.iter_mut() {
+				let res = Self::collect(it, txn, limit).await?;
+				set = Some(match set {
+					Some(set) => set.into_iter().filter(|t| res.contains(t)).collect(),
+					None => res,
+				});
+			}
+			self.set = Some(set.unwrap_or_default());
+		}
+		let set = self.set.as_ref().unwrap();
+		loop {
+			let res = self.first.next_batch(txn, limit).await?;
+			if res.is_empty() {
+				return Ok(res);
+			}
+			let res: Vec<_> = res.into_iter().filter(|(t, _)| set.contains(t)).collect();
+			if !res.is_empty() {
+				return Ok(res);
+			}
+		}
+	}
+}
+
+/// Returns every record of an index, ordered by the values of its first column,
+/// reading the index entries in batches. In descending order, each batch is read
+/// backwards, and the entries of each value are returned in ascending order, like
+/// they would be after a stable sort. The entries of the lowest value of a batch
+/// are read again with the next batch, as more of them may precede the batch,
+/// unless they fill the whole batch, in which case they are read forwards.
+struct IndexOrderThingIterator {
+	beg: Key,
+	end: Key,
+	asc: bool,
+	/// The remaining entries of a value which did not fit in one batch
+	group: Option<Range<Key>>,
+}
+
+impl IndexOrderThingIterator {
+	fn new(opt: &Options, ix: &DefineIndexStatement, asc: bool) -> Self {
+		Self {
+			beg: key::index::prefix(opt.ns(), opt.db(), &ix.what, &ix.name),
+			end: key::index::suffix(opt.ns(), opt.db(), &ix.what, &ix.name),
+			asc,
+			group: None,
+		}
+	}
+
+	async fn next_batch_desc(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut run = txn.lock().await;
+		loop {
+			// Read the entries of a large value forwards
+			if let Some(rng) = &mut self.group {
+				let res = run.scan(rng.clone(), limit).await?;
+				if let Some((key, _)) = res.last() {
+					rng.start = key.clone();
+					rng.start.push(0x00);
+					return Ok(res
+						.into_iter()
+						.map(|(_, v)| (Thing::from(&v), NO_DOC_ID))
+						.collect());
+				}
+				self.group = None;
+			}
+			let min = self.beg.clone();
+			let max = self.end.clone();
+			let ent = run.scan_rev(min..max, limit).await?;
+			let full = ent.len() >= limit as usize;
+			// Split the entries by value, in descending order
+			let mut groups: Vec<(Value, Vec<(Key, Val)>)> = vec![];
+			for (k, v) in ent {
+				let fd = key::index::Index::decode(&k)?.fd.0.into_iter().next().unwrap_or_default();
+				match groups.last_mut() {
+					Some((g, es)) if *g == fd => es.push((k, v)),
+					_ => groups.push((fd, vec![(k, v)])),
+				}
+			}
+			if full {
+				if let Some((fd, es)) = groups.pop() {
+					match groups.last() {
+						// Read the lowest value again with the next batch
+						Some((_, prev)) => {
+							if let Some((key, _)) = prev.last() {
+								self.end = key.clone();
+							}
+						}
+						// The value fills the batch, so read its entries forwards
+						None => {
+							let i = key::index::Index::decode(&es[0].0)?;
+							let fd = Array::from(vec![fd]);
+							let beg =
+								key::index::prefix_ids_composite_beg(i.ns, i.db, i.tb, i.ix, &fd);
+							let mut end = es[0].0.clone();
+							end.push(0x00);
+							self.end = beg.clone();
+							self.group = Some(beg..end);
+							continue;
+						}
+					}
+				}
+			} else {
+				// Every remaining entry was read
+				self.end = self.beg.clone();
+			}
+			return Ok(groups
+				.into_iter()
+				.flat_map(|(_, es)| es.into_iter().rev())
+				.map(|(_, v)| (Thing::from(&v), NO_DOC_ID))
+				.collect());
+		}
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for IndexOrderThingIterator {
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		if !self.asc {
+			return self.next_batch_desc(txn, limit).await;
+		}
+		let min = self.beg.clone();
+		let max = self.end.clone();
+		let res = txn.lock().await.scan(min..max, limit).await?;
+		if let Some((key, _)) = res.last() {
+			self.beg = key.clone();
+			self.beg.push(0x00);
+		}
+		Ok(res.into_iter().map(|(_, v)| (Thing::from(&v), NO_DOC_ID)).collect())
+	}
+}
+
+/// Returns the records of every iterator, each record only once.
+struct UnionThingIterator {
+	its: VecDeque<Box<dyn ThingIterator>>,
+	/// The records which have already been returned
+	seen: HashSet<Thing>,
+}
+
+impl UnionThingIterator {
+	fn new(its: Vec<Box<dyn ThingIterator>>) -> Self {
+		Self {
+			its: its.into(),
+			seen: HashSet::new(),
+		}
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for UnionThingIterator {
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		while let Some(it) = self.its.front_mut() {
+			let res = it.next_batch(txn, limit).await?;
+			if res.is_empty() {
+				self.its.pop_front();
+				continue;
+			}
+			// The doc ids of different full-text indexes can't be told apart
+			let res: Vec<_> = res
+				.into_iter()
+				.filter(|(t, _)| self.seen.insert(t.clone()))
+				.map(|(t, _)| (t, NO_DOC_ID))
+				.collect();
+			if !res.is_empty() {
+				return Ok(res);
+			}
+		}
+		Ok(vec![])
+	}
+}
+
 struct MatchesThingIterator {
 	hits: Option<HitsIterator>,
 }
@@ -259,7 +1049,9 @@ impl MatchesThingIterator {
 		hl: bool,
 		sc: &Scoring,
 		order: u32,
+		wt: Option<&Weights>,
 		terms_docs: Option<TermsDocs>,
+		query: Option<MatchQuery>,
 	) -> Result<Self, Error> {
 		let ikb = IndexKeyBase::new(opt, ix);
 		if let Scoring::Bm {
@@ -268,9 +1060,9 @@ impl MatchesThingIterator {
 		{
 			let mut run = txn.lock().await;
 			let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
-			let fti = FtIndex::new(&mut run, az, ikb, order, sc, hl).await?;
-			if let Some(terms_docs) = terms_docs {
-				let hits = fti.new_hits_iterator(&mut run, terms_docs).await?;
+			let fti = FtIndex::new(&mut run, az, ikb, order, sc, hl, wt).await?;
+			if let (Some(terms_docs), Some(query)) = (terms_docs, query) {
+				let hits = fti.new_hits_iterator(&mut run, terms_docs, query).await?;
 				Ok(Self {
 					hits,
 				})
@@ -311,6 +1103,96 @@ impl ThingIterator for MatchesThingIterator {
 	}
 }
 
+/// Returns the records matching a full-text query by descending score
+struct MatchesTopThingIterator {
+	hits: Option<TopHitsIterator>,
+}
+
+impl MatchesTopThingIterator {
+	async fn new(
+		opt: &Options,
+		txn: &Transaction,
+		ix: &DefineIndexStatement,
+		terms_docs: Option<TermsDocs>,
+		query: Option<MatchQuery>,
+		k: usize,
+	) -> Result<Self, Error> {
+		let mut hits = None;
+		if let (
+			Index::Search {
+				az,
+				hl,
+				sc,
+				order,
+				wt,
+			},
+			Some(terms_docs),
+			Some(query),
+		) = (&ix.index, terms_docs, query)
+		{
+			let ikb = IndexKeyBase::new(opt, ix);
+			let mut run = txn.lock().await;
+			let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
+			let fti = FtIndex::new(&mut run, az, ikb, *order, sc, *hl, wt.as_ref()).await?;
+			hits = fti.new_top_hits_iterator(&mut run, terms_docs, query, k).await?;
+		}
+		Ok(Self {
+			hits,
+		})
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for MatchesTopThingIterator {
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		mut limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut res = vec![];
+		if let Some(hits) = &mut self.hits {
+			let mut run = txn.lock().await;
+			while limit > 0 {
+				if let Some(hit) = hits.next(&mut run).await? {
+					res.push(hit);
+				} else {
+					break;
+				}
+				limit -= 1;
+			}
+		}
+		Ok(res)
+	}
+}
+
+/// Returns the records nearest to the vector of a KNN expression, by
+/// ascending distance. They are found when the query executor is created.
+struct KnnThingIterator {
+	things: VecDeque<Thing>,
+}
+
+impl KnnThingIterator {
+	fn new(things: Option<Arc<Vec<Thing>>>) -> Self {
+		Self {
+			things: things.map(|t| t.iter().cloned().collect()).unwrap_or_default(),
+		}
+	}
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ThingIterator for KnnThingIterator {
+	async fn next_batch(
+		&mut self,
+		_txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let n = self.things.len().min(limit as usize);
+		Ok(self.things.drain(..n).map(|t| (t, NO_DOC_ID)).collect())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::idx::planner::plan::IndexOption;
@@ -323,6 +1205,7 @@ mod tests {
 		let mut set = HashSet::new();
 		let io1 = IndexOption::new(
 			DefineIndexStatement::default(),
+			0,
 			Idiom::from("a.b".to_string()),
 			Operator::Equal,
 			Value::from("test"),
@@ -332,6 +1215,7 @@ mod tests {
 
 		let io2 = IndexOption::new(
 			DefineIndexStatement::default(),
+			0,
 			Idiom::from("a.b".to_string()),
 			Operator::Equal,
 			Value::from("test"),
@@ -345,4 +1229,109 @@ mod tests {
 
 		assert_eq!(set.len(), 1);
 	}
+
+	#[cfg(feature = "kv-rocksdb")]
+	#[tokio::test]
+	async fn test_index_order_desc_reads_limit_entries() {
+		use crate::dbs::{Options, Session};
+		use crate::idx::planner::plan::{IndexOrderThingIterator, ThingIterator};
+		use crate::key;
+		use crate::kvs::Datastore;
+		use crate::sql::{Id, Thing};
+		use futures::lock::Mutex;
+		use std::sync::Arc;
+		use temp_dir::TempDir;
+		// The memory storage engine can't scan backwards
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		let ds = Datastore::new(format!("rocksdb:{path}").as_str()).await.unwrap();
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		let mut sql =
+			"DEFINE FIELD ts ON event TYPE int; DEFINE INDEX event_ts ON event FIELDS ts;"
+				.to_string();
+		for i in 0..1000 {
+			sql.push_str(&format!("CREATE event:{i} SET ts = {};", i % 500));
+		}
+		ds.execute(&sql, &ses, None).await.unwrap();
+		let opt = Options::new().with_ns(Some("test".into())).with_db(Some("test".into()));
+		let ix = DefineIndexStatement {
+			name: "event_ts".into(),
+			what: "event".into(),
+			..Default::default()
+		};
+		let txn = Arc::new(Mutex::new(ds.transaction(false, false).await.unwrap()));
+		let mut it = IndexOrderThingIterator::new(&opt, &ix, false);
+		// The records of the greatest value are returned in ascending order
+		let res = it.next_batch(&txn, 3).await.unwrap();
+		let res: Vec<Thing> = res.into_iter().map(|(t, _)| t).collect();
+		assert_eq!(
+			res,
+			vec![
+				Thing::from(("event".to_owned(), Id::from(499))),
+				Thing::from(("event".to_owned(), Id::from(999)))
+			]
+		);
+		// The entries of the lowest value of the batch are read again
+		let end = key::index::Index::decode(&it.end).unwrap();
+		assert_eq!(end.fd, vec![Value::from(499)].into());
+		assert_eq!(end.id, Some(Id::from(499)));
+		//
+		let res = it.next_batch(&txn, 3).await.unwrap();
+		let res: Vec<Thing> = res.into_iter().map(|(t, _)| t).collect();
+		assert_eq!(
+			res,
+			vec![
+				Thing::from(("event".to_owned(), Id::from(498))),
+				Thing::from(("event".to_owned(), Id::from(998)))
+			]
+		);
+		txn.lock().await.cancel().await.unwrap();
+	}
+
+	#[cfg(feature = "kv-rocksdb")]
+	#[tokio::test]
+	async fn test_index_order_desc_reads_large_values_in_batches() {
+		use crate::dbs::{Options, Session};
+		use crate::idx::planner::plan::{IndexOrderThingIterator, ThingIterator};
+		use crate::kvs::Datastore;
+		use crate::sql::{Id, Thing};
+		use futures::lock::Mutex;
+		use std::sync::Arc;
+		use temp_dir::TempDir;
+		// The memory storage engine can't scan backwards
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		let ds = Datastore::new(format!("rocksdb:{path}").as_str()).await.unwrap();
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		let mut sql =
+			"DEFINE FIELD ts ON event TYPE int; DEFINE INDEX event_ts ON event FIELDS ts;"
+				.to_string();
+		for i in 0..10 {
+			sql.push_str(&format!("CREATE event:{i} SET ts = 1;"));
+		}
+		sql.push_str("CREATE event:10 SET ts = 0;");
+		ds.execute(&sql, &ses, None).await.unwrap();
+		let opt = Options::new().with_ns(Some("test".into())).with_db(Some("test".into()));
+		let ix = DefineIndexStatement {
+			name: "event_ts".into(),
+			what: "event".into(),
+			..Default::default()
+		};
+		let txn = Arc::new(Mutex::new(ds.transaction(false, false).await.unwrap()));
+		let mut it = IndexOrderThingIterator::new(&opt, &ix, false);
+		// The records of a value are returned in ascending order, one batch at a time
+		let mut res = vec![];
+		loop {
+			let batch = it.next_batch(&txn, 3).await.unwrap();
+			if batch.is_empty() {
+				break;
+			}
+			assert!(batch.len() <= 3);
+			res.extend(batch.into_iter().map(|(t, _)| t));
+		}
+		let exp: Vec<Thing> = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+			.into_iter()
+			.map(|i| Thing::from(("event".to_owned(), Id::from(i))))
+			.collect();
+		assert_eq!(res, exp);
+		txn.lock().await.cancel().await.unwrap();
+	}
 }
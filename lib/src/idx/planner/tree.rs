@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
+use crate::idx::build::IndexBuild;
 use crate::idx::planner::plan::IndexOption;
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
@@ -47,25 +48,24 @@ struct TreeBuilder<'a> {
 }
 
 impl<'a> TreeBuilder<'a> {
-	async fn find_index(&mut self, i: &Idiom) -> Result<Option<DefineIndexStatement>, Error> {
+	/// Find every index having the idiom as one of its columns,
+	/// along with the position of the idiom in the index columns.
+	async fn find_indexes(&mut self, i: &Idiom) -> Result<Vec<IndexRef>, Error> {
 		if self.indexes.is_none() {
-			let indexes = self
-				.txn
-				.clone()
-				.lock()
-				.await
-				.all_ix(self.opt.ns(), self.opt.db(), &self.table.0)
-				.await?;
+			let mut run = self.txn.lock().await;
+			let indexes =
+				IndexBuild::ready(&mut run, self.opt.ns(), self.opt.db(), &self.table.0).await?;
 			self.indexes = Some(indexes);
 		}
+		let mut irs = vec![];
 		if let Some(indexes) = &self.indexes {
 			for ix in indexes.as_ref() {
-				if ix.cols.len() == 1 && ix.cols[0].eq(i) {
-					return Ok(Some(ix.clone()));
+				if let Some(col) = ix.cols.iter().position(|c| c.eq(i)) {
+					irs.push((ix.clone(), col));
 				}
 			}
 		}
-		Ok(None)
+		Ok(irs)
 	}
 
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
@@ -77,6 +77,7 @@ impl<'a> TreeBuilder<'a> {
 			Value::Strand(_) => Node::Scalar(v.to_owned()),
 			Value::Number(_) => Node::Scalar(v.to_owned()),
 			Value::Bool(_) => Node::Scalar(v.to_owned()),
+			Value::Array(a) if v.is_static() => Node::Scalar(Value::Array(a.to_owned())),
 			Value::Subquery(s) => self.eval_subquery(s).await?,
 			Value::Param(p) => {
 				let v = p.compute(self.ctx, self.opt).await?;
@@ -87,10 +88,11 @@ impl<'a> TreeBuilder<'a> {
 	}
 
 	async fn eval_idiom(&mut self, i: &Idiom) -> Result<Node, Error> {
-		Ok(if let Some(ix) = self.find_index(i).await? {
-			Node::IndexedField(i.to_owned(), ix)
-		} else {
+		let irs = self.find_indexes(i).await?;
+		Ok(if irs.is_empty() {
 			Node::NonIndexedField
+		} else {
+			Node::IndexedField(i.to_owned(), irs)
 		})
 	}
 
@@ -108,22 +110,22 @@ impl<'a> TreeBuilder<'a> {
 			} => {
 				let left = self.eval_value(l).await?;
 				let right = self.eval_value(r).await?;
-				if let Some(io) = self.index_map.0.get(e) {
+				if let Some(ios) = self.index_map.0.get(e) {
 					return Ok(Node::Expression {
-						io: Some(io.clone()),
+						ios: ios.clone(),
 						left: Box::new(left),
 						right: Box::new(right),
 						exp: e.clone(),
 					});
 				}
-				let mut io = None;
-				if let Some((id, ix)) = left.is_indexed_field() {
-					io = self.lookup_index_option(ix, o, id, &right, e);
-				} else if let Some((id, ix)) = right.is_indexed_field() {
-					io = self.lookup_index_option(ix, o, id, &left, e);
+				let mut ios = vec![];
+				if let Some((id, irs)) = left.is_indexed_field() {
+					ios = self.lookup_index_options(irs, o, id, &right, e);
+				} else if let Some((id, irs)) = right.is_indexed_field() {
+					ios = self.lookup_index_options(irs, &Self::reverse_operator(o), id, &left, e);
 				};
 				Ok(Node::Expression {
-					io,
+					ios,
 					left: Box::new(left),
 					right: Box::new(right),
 					exp: e.clone(),
@@ -132,35 +134,85 @@ impl<'a> TreeBuilder<'a> {
 		}
 	}
 
-	fn lookup_index_option(
+	fn lookup_index_options(
 		&mut self,
-		ix: &DefineIndexStatement,
+		irs: &[IndexRef],
 		op: &Operator,
 		id: &Idiom,
 		v: &Node,
 		e: &Expression,
-	) -> Option<IndexOption> {
+	) -> Vec<IndexOption> {
+		let mut ios = vec![];
 		if let Some(v) = v.is_scalar() {
-			let (found, mr, qs) = match &ix.index {
-				Index::Idx => (Operator::Equal.eq(op), None, None),
-				Index::Uniq => (Operator::Equal.eq(op), None, None),
-				Index::Search {
-					..
-				} => {
-					if let Operator::Matches(mr) = op {
-						(true, *mr, Some(v.clone().to_raw_string()))
-					} else {
-						(false, None, None)
+			for (ix, col) in irs {
+				let (found, mr, qs) = match &ix.index {
+					Index::Idx => (Self::is_index_operator(op, v), None, None),
+					Index::Uniq => (Self::is_index_operator(op, v), None, None),
+					Index::Search {
+						..
+					} => {
+						if let Operator::Matches(mr) = op {
+							// Only one full-text index can resolve a MATCHES expression
+							if ios.iter().any(|io: &IndexOption| io.qs().is_some()) {
+								continue;
+							}
+							(true, *mr, Some(v.clone().to_raw_string()))
+						} else {
+							(false, None, None)
+						}
+					}
+					Index::MTree(_) => {
+						if ix.cols.len() != 1 {
+							continue;
+						}
+						(matches!(op, Operator::Knn(_)), None, None)
 					}
+				};
+				if found {
+					ios.push(IndexOption::new(
+						ix.clone(),
+						*col,
+						id.clone(),
+						op.to_owned(),
+						v.clone(),
+						qs,
+						mr,
+					));
 				}
-			};
-			if found {
-				let io = IndexOption::new(ix.clone(), id.clone(), op.to_owned(), v.clone(), qs, mr);
-				self.index_map.0.insert(e.clone(), io.clone());
-				return Some(io);
 			}
 		}
-		None
+		if !ios.is_empty() {
+			self.index_map.0.insert(e.clone(), ios.clone());
+		}
+		ios
+	}
+
+	/// Check if a plain or unique index can resolve the operator for this value.
+	/// Range conditions are only resolved for numbers and strings, as the keys
+	/// of other types of values are not necessarily ordered like the values.
+	fn is_index_operator(op: &Operator, v: &Value) -> bool {
+		match op {
+			Operator::Equal => true,
+			Operator::LessThan
+			| Operator::LessThanOrEqual
+			| Operator::MoreThan
+			| Operator::MoreThanOrEqual => matches!(v, Value::Number(_) | Value::Strand(_)),
+			Operator::Inside => matches!(v, Value::Array(_)),
+			_ => false,
+		}
+	}
+
+	/// Returns the operator to use when the indexed field is on the right hand side
+	fn reverse_operator(op: &Operator) -> Operator {
+		match op {
+			Operator::LessThan => Operator::MoreThan,
+			Operator::LessThanOrEqual => Operator::MoreThanOrEqual,
+			Operator::MoreThan => Operator::LessThan,
+			Operator::MoreThanOrEqual => Operator::LessThanOrEqual,
+			Operator::Contain => Operator::Inside,
+			Operator::Inside => Operator::Contain,
+			op => op.to_owned(),
+		}
 	}
 
 	async fn eval_subquery(&mut self, s: &Subquery) -> Result<Node, Error> {
@@ -171,12 +223,15 @@ impl<'a> TreeBuilder<'a> {
 	}
 }
 
-/// For each expression the a possible index option
+/// An index, and the position of a field in the columns of the index
+pub(super) type IndexRef = (DefineIndexStatement, usize);
+
+/// For each expression the possible index options
 #[derive(Default)]
-pub(super) struct IndexMap(HashMap<Expression, IndexOption>);
+pub(super) struct IndexMap(HashMap<Expression, Vec<IndexOption>>);
 
 impl IndexMap {
-	pub(super) fn consume(self) -> HashMap<Expression, IndexOption> {
+	pub(super) fn consume(self) -> HashMap<Expression, Vec<IndexOption>> {
 		self.0
 	}
 }
@@ -184,12 +239,12 @@ impl IndexMap {
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(super) enum Node {
 	Expression {
-		io: Option<IndexOption>,
+		ios: Vec<IndexOption>,
 		left: Box<Node>,
 		right: Box<Node>,
 		exp: Expression,
 	},
-	IndexedField(Idiom, DefineIndexStatement),
+	IndexedField(Idiom, Vec<IndexRef>),
 	NonIndexedField,
 	Scalar(Value),
 	Unsupported,
@@ -204,9 +259,9 @@ impl Node {
 		}
 	}
 
-	pub(super) fn is_indexed_field(&self) -> Option<(&Idiom, &DefineIndexStatement)> {
-		if let Node::IndexedField(id, ix) = self {
-			Some((id, ix))
+	pub(super) fn is_indexed_field(&self) -> Option<(&Idiom, &[IndexRef])> {
+		if let Node::IndexedField(id, irs) = self {
+			Some((id, irs))
 		} else {
 			None
 		}
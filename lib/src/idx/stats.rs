@@ -0,0 +1,193 @@
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::idx::SerdeState;
+use crate::key;
+use crate::kvs::{Key, Transaction};
+use crate::sql::index::Index;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Array, Object, Value};
+use serde::{Deserialize, Serialize};
+
+/// The number of entries assumed for an index which has not been analyzed
+const DEFAULT_ENTRIES: u64 = 10_000;
+/// The number of entries sharing a value assumed for a non-unique index
+const DEFAULT_DUPLICATES: u64 = 10;
+
+/// The cardinality statistics of a plain or unique index. These are only
+/// computed and saved by the ANALYZE statement, and are not updated when
+/// records are written, so they are approximate and should be refreshed by
+/// running ANALYZE again after significant changes to the table. The query
+/// planner falls back on the estimates of the index definitions of the
+/// indexes which were not analyzed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IndexStatistics {
+	/// The number of entries in the index
+	entries: u64,
+	/// The number of distinct values of each leftmost prefix of the columns
+	distinct: Vec<u64>,
+}
+
+impl SerdeState for IndexStatistics {}
+
+impl From<IndexStatistics> for Value {
+	fn from(stats: IndexStatistics) -> Self {
+		let mut res = Object::default();
+		res.insert("entries".to_owned(), Value::from(stats.entries));
+		res.insert(
+			"distinct".to_owned(),
+			Value::from(stats.distinct.into_iter().map(Value::from).collect::<Vec<_>>()),
+		);
+		Value::from(res)
+	}
+}
+
+impl IndexStatistics {
+	/// Compute the statistics by scanning every entry of the index
+	pub(crate) async fn compute(
+		tx: &mut Transaction,
+		opt: &Options,
+		ix: &DefineIndexStatement,
+	) -> Result<Self, Error> {
+		let (ns, db, tb, ix) = (opt.ns(), opt.db(), ix.what.as_str(), ix.name.as_str());
+		let beg = key::index::prefix(ns, db, tb, ix);
+		let end = key::index::suffix(ns, db, tb, ix);
+		let mut stats = Self::default();
+		let mut prev: Option<Array> = None;
+		let mut nxt: Option<Key> = None;
+		loop {
+			// Get records batch
+			let min = match nxt.take() {
+				None => beg.clone(),
+				Some(mut k) => {
+					k.push(0x00);
+					k
+				}
+			};
+			let res = tx.scan(min..end.clone(), 1000).await?;
+			// Exit when settled
+			if res.is_empty() {
+				break;
+			}
+			// Count the entries and the distinct prefixes
+			for (k, _) in res.iter() {
+				let fd = key::index::Index::decode(k)?.fd;
+				if stats.distinct.len() < fd.len() {
+					stats.distinct.resize(fd.len(), 0);
+				}
+				// The first column which differs from the previous entry
+				let col = match &prev {
+					Some(p) => fd.iter().zip(p.iter()).take_while(|(a, b)| a == b).count(),
+					None => 0,
+				};
+				for d in stats.distinct.iter_mut().take(fd.len()).skip(col) {
+					*d += 1;
+				}
+				stats.entries += 1;
+				prev = Some(fd);
+			}
+			// Ready the next
+			nxt = res.into_iter().last().map(|(k, _)| k);
+		}
+		Ok(stats)
+	}
+
+	/// Load the saved statistics, if the index was analyzed
+	pub(crate) async fn get(
+		tx: &mut Transaction,
+		opt: &Options,
+		ix: &DefineIndexStatement,
+	) -> Result<Option<Self>, Error> {
+		let key = key::is::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+		match tx.get(key).await? {
+			Some(val) => Ok(Some(Self::try_from_val(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Save the statistics of the index
+	pub(crate) async fn set(
+		&self,
+		tx: &mut Transaction,
+		opt: &Options,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = key::is::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+		tx.set(key, self.try_to_val()?).await
+	}
+
+	/// Estimate the statistics of an index which was never analyzed. The
+	/// number of entries is taken from the analyzed indexes of the same
+	/// table when there are any, as every record has an entry in each index.
+	pub(crate) fn estimate(ix: &DefineIndexStatement, entries: Option<u64>) -> Self {
+		let n = ix.cols.len().max(1);
+		let entries = entries.unwrap_or(DEFAULT_ENTRIES);
+		let full = match ix.index {
+			Index::Uniq => entries,
+			_ => entries / DEFAULT_DUPLICATES,
+		};
+		Self {
+			entries,
+			distinct: (1..=n).map(|m| Self::interpolate(full, m, n)).collect(),
+		}
+	}
+
+	/// Interpolate the number of distinct values of a prefix of `m` out of `n`
+	/// columns, assuming every column contributes equally
+	fn interpolate(full: u64, m: usize, n: usize) -> u64 {
+		((full as f64).powf(m as f64 / n as f64).round() as u64).max(1)
+	}
+
+	/// Estimate the number of entries matching one value of the first `m` columns
+	pub(crate) fn rows_per_value(&self, m: usize) -> f64 {
+		let entries = self.entries as f64;
+		if m == 0 {
+			return entries;
+		}
+		let distinct = match self.distinct.get(m - 1) {
+			Some(d) => *d,
+			// Columns which were never populated
+			None => match self.distinct.last() {
+				Some(d) => *d,
+				None => return 0.0,
+			},
+		};
+		match distinct {
+			0 => 0.0,
+			d => entries / d as f64,
+		}
+	}
+
+	/// The number of entries in the index
+	pub(crate) fn entries(&self) -> u64 {
+		self.entries
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::stats::IndexStatistics;
+	use crate::sql::index::Index;
+	use crate::sql::statements::DefineIndexStatement;
+	use crate::sql::{Idiom, Idioms};
+
+	#[test]
+	fn estimate() {
+		let mut ix = DefineIndexStatement {
+			cols: Idioms(vec![Idiom::from("a".to_string()), Idiom::from("b".to_string())]),
+			index: Index::Idx,
+			..Default::default()
+		};
+		let s = IndexStatistics::estimate(&ix, None);
+		assert_eq!(s.entries, 10_000);
+		assert_eq!(s.distinct, vec![32, 1000]);
+		assert_eq!(s.rows_per_value(2), 10.0);
+		ix.index = Index::Uniq;
+		let s = IndexStatistics::estimate(&ix, None);
+		assert_eq!(s.distinct, vec![100, 10000]);
+		assert_eq!(s.rows_per_value(2), 1.0);
+		// The number of entries known from another index of the table
+		let s = IndexStatistics::estimate(&ix, Some(400));
+		assert_eq!(s.entries, 400);
+		assert_eq!(s.distinct, vec![20, 400]);
+	}
+}
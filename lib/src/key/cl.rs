@@ -14,6 +14,22 @@ pub struct Cl {
 	pub nd: Uuid,
 }
 
+pub fn new(nd: Uuid) -> Cl {
+	Cl::new(nd)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'c', b'l', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = Cl::new(Uuid::from_bytes([0xff; 16])).encode().unwrap();
+	k.push(0x00);
+	k
+}
+
 impl Cl {
 	pub fn new(nd: Uuid) -> Self {
 		Self {
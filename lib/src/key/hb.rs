@@ -15,6 +15,22 @@ pub struct Hb {
 	pub nd: Uuid,
 }
 
+pub fn new(hb: Timestamp, nd: Uuid) -> Hb {
+	Hb::new(hb, nd)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'h', b'b', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'h', b'b', 0xff]);
+	k
+}
+
 impl Hb {
 	pub fn new(hb: Timestamp, nd: Uuid) -> Self {
 		Self {
@@ -0,0 +1,133 @@
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Hs stands for the History of a record, with one entry for each
+// versionstamp at which the record was changed in a change feed.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Hs<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub vs: [u8; 10],
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+impl<'a> Prefix<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'h',
+			_f: b's',
+			id: id.to_owned(),
+		}
+	}
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, vs: [u8; 10]) -> Hs<'a> {
+	Hs::new(ns, db, tb, id.to_owned(), vs)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'h', b's', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'h', b's', 0xff]);
+	k
+}
+
+pub fn id_prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn id_suffix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl<'a> Hs<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, id: Id, vs: [u8; 10]) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'h',
+			_f: b's',
+			id,
+			vs,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Hs::new(
+			"test",
+			"test",
+			"test",
+			"test".into(),
+			[0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+		);
+		let enc = Hs::encode(&val).unwrap();
+		assert_eq!(enc, b"/*test\0*test\0*test\0!hs\0\0\0\x01test\0\0\0\0\0\0\0\0\x01\0\0");
+		let dec = Hs::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let id: Id = "test".into();
+		let val =
+			new("test", "test", "test", &id, [0, 0, 0, 0, 0, 0, 0, 2, 0, 0]).encode().unwrap();
+		let beg = id_prefix("test", "test", "test", &id);
+		let end = id_suffix("test", "test", "test", &id);
+		assert!(beg < val && val < end);
+		assert!(prefix("test", "test", "test") < beg && end < suffix("test", "test", "test"));
+	}
+}
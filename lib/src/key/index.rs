@@ -114,6 +114,23 @@ pub fn suffix_all_ids(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec
 	k
 }
 
+/// Returns the prefix of the keys of every array starting with the given values
+pub fn prefix_ids_composite_beg(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+	let mut k = PrefixIds::new(ns, db, tb, ix, fd).encode().unwrap();
+	// Remove the end of the array marker
+	k.pop();
+	k
+}
+
+/// Returns the suffix of the keys of every array starting with the given values
+pub fn prefix_ids_composite_end(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+	let mut k = PrefixIds::new(ns, db, tb, ix, fd).encode().unwrap();
+	// Remove the end of the array marker
+	k.pop();
+	k.push(0xff);
+	k
+}
+
 impl<'a> Index<'a> {
 	pub fn new(
 		ns: &'a str,
@@ -157,4 +174,20 @@ mod tests {
 		let dec = Index::decode(&enc).unwrap();
 		assert_eq!(val, dec);
 	}
+
+	#[test]
+	fn composite_prefix() {
+		use super::*;
+		let fd: Array = vec!["a", "b"].into();
+		let beg = prefix_ids_composite_beg("test", "test", "test", "test", &vec!["a"].into());
+		let end = prefix_ids_composite_end("test", "test", "test", "test", &vec!["a"].into());
+		let key = Index::new("test", "test", "test", "test", fd.clone(), None).encode().unwrap();
+		assert!(beg < key && key < end);
+		let key =
+			Index::new("test", "test", "test", "test", fd, Some("test".into())).encode().unwrap();
+		assert!(beg < key && key < end);
+		let fd: Array = vec!["ab", "b"].into();
+		let key = Index::new("test", "test", "test", "test", fd, None).encode().unwrap();
+		assert!(key > end);
+	}
 }
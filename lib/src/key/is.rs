@@ -0,0 +1,59 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Is stands for index statistics
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Is<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ix: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str) -> Is<'a> {
+	Is::new(ns, db, tb, ix)
+}
+
+impl<'a> Is<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str) -> Self {
+		Is {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'i',
+			_f: b's',
+			ix,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Is::new(
+			"test",
+			"test",
+			"test",
+			"test",
+		);
+		let enc = Is::encode(&val).unwrap();
+		assert_eq!(enc, b"/*test\0*test\0*test\0!istest\0");
+		let dec = Is::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,55 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Kl<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub us: &'a str,
+}
+
+pub fn new(us: &str) -> Kl<'_> {
+	Kl::new(us)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'k', b'l', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'k', b'l', 0xff]);
+	k
+}
+
+impl<'a> Kl<'a> {
+	pub fn new(us: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'k',
+			_c: b'l',
+			us,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Kl::new(
+			"test",
+		);
+		let enc = Kl::encode(&val).unwrap();
+		assert_eq!(enc, b"/!kltest\0");
+		let dec = Kl::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
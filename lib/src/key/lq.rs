@@ -24,6 +24,18 @@ pub fn new<'a>(nd: Uuid, ns: &'a str, db: &'a str, lq: Uuid) -> Lq<'a> {
 	Lq::new(nd, ns, db, lq)
 }
 
+pub fn prefix(nd: Uuid) -> Vec<u8> {
+	let mut k = super::nd::new(nd).encode().unwrap();
+	k.extend_from_slice(&[b'*', 0x00]);
+	k
+}
+
+pub fn suffix(nd: Uuid) -> Vec<u8> {
+	let mut k = super::nd::new(nd).encode().unwrap();
+	k.extend_from_slice(&[b'*', 0xff]);
+	k
+}
+
 impl<'a> Lq<'a> {
 	pub fn new(nd: Uuid, ns: &'a str, db: &'a str, lq: Uuid) -> Self {
 		Self {
@@ -4,10 +4,14 @@
 ///
 /// ND              /!nd{nd}
 /// LQ              /!nd{nd}*{ns}*{db}!lq{lq}
+/// NQ              /!nd{nd}!nq{ts}{id}
 ///
 /// HB              /!hb{ts}/{nd}
 ///
+/// KL              /!kl{us}
 /// NS              /!ns{ns}
+/// RS              /!rs{ns}{db}{sc}{tk}{id}
+/// RV              /!rv{ns}{db}{sc}{tk}{id}
 ///
 /// Namespace       /*{ns}
 /// NL              /*{ns}!nl{us}
@@ -16,12 +20,14 @@
 ///
 /// Database        /*{ns}*{db}
 /// AZ              /*{ns}*{db}!az{az}
-/// CF /*{ns}*{db}!cf{ts}
+/// CF              /*{ns}*{db}!cf{vs}{tb}
+/// DV              /*{ns}*{db}!tt
 /// DL              /*{ns}*{db}!dl{us}
 /// DT              /*{ns}*{db}!dt{tk}
 /// PA              /*{ns}*{db}!pa{pa}
 /// SC              /*{ns}*{db}!sc{sc}
 /// TB              /*{ns}*{db}!tb{tb}
+/// TS              /*{ns}*{db}!ts{ts}
 ///
 /// Scope           /*{ns}*{db}±{sc}
 /// ST              /*{ns}*{db}±{sc}!st{tk}
@@ -32,6 +38,9 @@
 /// EV              /*{ns}*{db}*{tb}!ev{ev}
 /// FD              /*{ns}*{db}*{tb}!fd{fd}
 /// FT              /*{ns}*{db}*{tb}!ft{ft}
+/// HS              /*{ns}*{db}*{tb}!hs{id}{vs}
+/// IB              /*{ns}*{db}*{tb}!ib{ix}
+/// IS              /*{ns}*{db}*{tb}!is{ix}
 /// IX              /*{ns}*{db}*{tb}!ix{ix}
 /// LV              /*{ns}*{db}*{tb}!lv{lv}
 ///
@@ -47,10 +56,14 @@
 /// BI              /*{ns}*{db}*{tb}!bi{ix}*{id}
 /// BK              /*{ns}*{db}*{tb}!bk{ix}*{id}
 /// BL              /*{ns}*{db}*{tb}!bl{ix}*{id}
+/// BM              /*{ns}*{db}*{tb}!bm{ix}*{id}
 /// BP              /*{ns}*{db}*{tb}!bp{ix}*{id}
 /// BS              /*{ns}*{db}*{tb}!bs{ix}
 /// BT              /*{ns}*{db}*{tb}!bt{ix}*{id}
 /// BU              /*{ns}*{db}*{tb}!bu{ix}*{id}
+/// BW              /*{ns}*{db}*{tb}!bw{ix}*{id}
+///
+/// VM              /*{ns}*{db}*{tb}!vm{ix}*{id}
 pub mod az; // Stores a DEFINE ANALYZER config definition
 pub mod bc; // Stores Doc list for each term
 pub mod bd; // Stores BTree nodes for doc ids
@@ -58,11 +71,13 @@ pub mod bf; // Stores Term/Doc frequency
 pub mod bi; // Stores doc keys for doc_ids
 pub mod bk; // Stores the term list for doc_ids
 pub mod bl; // Stores BTree nodes for doc lengths
+pub mod bm; // Stores the maximum frequency of each term
 pub mod bo; // Stores the offsets
 pub mod bp; // Stores BTree nodes for postings
 pub mod bs; // Stores FullText index states
 pub mod bt; // Stores BTree nodes for terms
 pub mod bu; // Stores terms for term_ids
+pub mod bw; // Stores the term frequencies of each field
 pub mod cf; // Stores change feeds
 pub mod cl; // Stores cluster membership information
 pub mod database; // Stores the key prefix for all keys under a database
@@ -76,22 +91,48 @@ pub mod fd; // Stores a DEFINE FIELD config definition
 pub mod ft; // Stores a DEFINE TABLE AS config definition
 pub mod graph; // Stores a graph edge pointer
 pub mod hb; // Stores a heartbeat per registered cluster node
+pub mod hs; // Stores the history of a record in a change feed
+pub mod ib; // Stores the progress of an index build
 pub mod index; // Stores an index entry
+pub mod is; // Stores the statistics of an index
 pub mod ix; // Stores a DEFINE INDEX config definition
+pub mod kl; // Stores a DEFINE LOGIN ON ROOT config definition
 pub mod kv; // Stores the key prefix for all keys
 pub mod lq; // Stores a LIVE SELECT query definition on the database
 pub mod lv; // Stores a LIVE SELECT query definition on the table
 pub mod namespace; // Stores the key prefix for all keys under a namespace
+pub mod nd; // Stores the key prefix for all keys under a node
 pub mod nl; // Stores a DEFINE LOGIN ON NAMESPACE config definition
+pub mod nq; // Stores the live query notifications queued for a node
 pub mod ns; // Stores a DEFINE NAMESPACE config definition
 pub mod nt; // Stores a DEFINE TOKEN ON NAMESPACE config definition
 pub mod pa; // Stores a DEFINE PARAM config definition
+#[cfg(any(
+	feature = "kv-mem",
+	feature = "kv-tikv",
+	feature = "kv-rocksdb",
+	feature = "kv-speedb",
+	feature = "kv-fdb",
+	feature = "kv-indxdb",
+))]
+pub mod rs; // Stores when the authentication tokens of a subject were revoked
+#[cfg(any(
+	feature = "kv-mem",
+	feature = "kv-tikv",
+	feature = "kv-rocksdb",
+	feature = "kv-speedb",
+	feature = "kv-fdb",
+	feature = "kv-indxdb",
+))]
+pub mod rv; // Stores a revoked authentication token
 pub mod sc; // Stores a DEFINE SCOPE config definition
 pub mod scope; // Stores the key prefix for all keys under a scope
 pub mod st; // Stores a DEFINE TOKEN ON SCOPE config definition
 pub mod table; // Stores the key prefix for all keys under a table
 pub mod tb; // Stores a DEFINE TABLE config definition
 pub mod thing;
+pub mod ts; // Stores the versionstamp of a database at a timestamp
+pub mod vm; // Stores MTree nodes and states for vector indexes
 
 const CHAR_PATH: u8 = 0xb1; // ±
 const CHAR_INDEX: u8 = 0xa4; // ¤
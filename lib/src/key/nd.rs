@@ -0,0 +1,50 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Nd stands for Node, which is the key prefix for all keys under a node.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Nd {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub nd: Uuid,
+}
+
+pub fn new(nd: Uuid) -> Nd {
+	Nd::new(nd)
+}
+
+pub fn suffix(nd: Uuid) -> Vec<u8> {
+	let mut k = Nd::new(nd).encode().unwrap();
+	k.push(0xff);
+	k
+}
+
+impl Nd {
+	pub fn new(nd: Uuid) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'n',
+			_c: b'd',
+			nd,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Nd::new(
+			Uuid::default(),
+		);
+		let enc = Nd::encode(&val).unwrap();
+		let dec = Nd::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
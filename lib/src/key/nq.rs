@@ -0,0 +1,81 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Nq stands for Node Queue, which holds the live query notifications
+// that other nodes have produced for the live queries of this node.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Nq {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub nd: Uuid,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ts: u64,
+	#[serde(with = "uuid::serde::compact")]
+	pub id: Uuid,
+}
+
+pub fn new(nd: Uuid, ts: u64, id: Uuid) -> Nq {
+	Nq::new(nd, ts, id)
+}
+
+pub fn prefix(nd: Uuid) -> Vec<u8> {
+	let mut k = super::nd::new(nd).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'n', b'q', 0x00]);
+	k
+}
+
+pub fn suffix(nd: Uuid) -> Vec<u8> {
+	let mut k = super::nd::new(nd).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'n', b'q', 0xff]);
+	k
+}
+
+impl Nq {
+	pub fn new(nd: Uuid, ts: u64, id: Uuid) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'n',
+			_c: b'd',
+			nd,
+			_d: b'!',
+			_e: b'n',
+			_f: b'q',
+			ts,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Nq::new(
+			Uuid::default(),
+			123,
+			Uuid::default(),
+		);
+		let enc = Nq::encode(&val).unwrap();
+		let dec = Nq::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn ordering() {
+		use super::*;
+		let nd = Uuid::new_v4();
+		let a = Nq::new(nd, 1, Uuid::new_v4()).encode().unwrap();
+		let b = Nq::new(nd, 256, Uuid::new_v4()).encode().unwrap();
+		assert!(a < b);
+		assert!(prefix(nd) < a);
+		assert!(b < suffix(nd));
+	}
+}
@@ -0,0 +1,67 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rs<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ns: &'a str,
+	pub db: &'a str,
+	pub sc: &'a str,
+	pub tk: &'a str,
+	pub id: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, sc: &'a str, tk: &'a str, id: &'a str) -> Rs<'a> {
+	Rs::new(ns, db, sc, tk, id)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'r', b's', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'r', b's', 0xff]);
+	k
+}
+
+impl<'a> Rs<'a> {
+	pub fn new(ns: &'a str, db: &'a str, sc: &'a str, tk: &'a str, id: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'r',
+			_c: b's',
+			ns,
+			db,
+			sc,
+			tk,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rs::new(
+			"testns",
+			"testdb",
+			"testsc",
+			"",
+			"user:test",
+		);
+		let enc = Rs::encode(&val).unwrap();
+		assert_eq!(enc, b"/!rstestns\0testdb\0testsc\0\0user:test\0");
+		let dec = Rs::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
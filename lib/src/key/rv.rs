@@ -0,0 +1,67 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rv<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ns: &'a str,
+	pub db: &'a str,
+	pub sc: &'a str,
+	pub tk: &'a str,
+	pub id: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, sc: &'a str, tk: &'a str, id: &'a str) -> Rv<'a> {
+	Rv::new(ns, db, sc, tk, id)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'r', b'v', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'r', b'v', 0xff]);
+	k
+}
+
+impl<'a> Rv<'a> {
+	pub fn new(ns: &'a str, db: &'a str, sc: &'a str, tk: &'a str, id: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'r',
+			_c: b'v',
+			ns,
+			db,
+			sc,
+			tk,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rv::new(
+			"testns",
+			"testdb",
+			"testsc",
+			"",
+			"test",
+		);
+		let enc = Rv::encode(&val).unwrap();
+		assert_eq!(enc, b"/!rvtestns\0testdb\0testsc\0\0test\0");
+		let dec = Rv::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
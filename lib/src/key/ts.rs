@@ -0,0 +1,76 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Ts stands for Database Timestamps that correspond to Versionstamps.
+// Each entry stores the versionstamp of the database at the given timestamp.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ts<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub ts: u64,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, ts: u64) -> Ts<'a> {
+	Ts::new(ns, db, ts)
+}
+
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::database::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b't', b's', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::database::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b't', b's', 0xff]);
+	k
+}
+
+impl<'a> Ts<'a> {
+	pub fn new(ns: &'a str, db: &'a str, ts: u64) -> Self {
+		Ts {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b't',
+			_e: b's',
+			ts,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ts::new(
+			"test",
+			"test",
+			123,
+		);
+		let enc = Ts::encode(&val).unwrap();
+		let dec = Ts::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn ordering() {
+		use super::*;
+		let a = Ts::new("test", "test", 1).encode().unwrap();
+		let b = Ts::new("test", "test", 256).encode().unwrap();
+		assert!(a < b);
+		assert!(prefix("test", "test") < a);
+		assert!(b < suffix("test", "test"));
+	}
+}
@@ -31,6 +31,7 @@ pub enum Entry {
 	Fds(Arc<[DefineFieldStatement]>),
 	Fts(Arc<[DefineTableStatement]>),
 	Ixs(Arc<[DefineIndexStatement]>),
+	Kls(Arc<[DefineLoginStatement]>),
 	Lvs(Arc<[LiveStatement]>),
 	Nls(Arc<[DefineLoginStatement]>),
 	Nss(Arc<[DefineNamespaceStatement]>),
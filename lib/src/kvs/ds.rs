@@ -1,6 +1,8 @@
 use super::tx::Transaction;
 use crate::ctx::Context;
+use crate::dbs::cl::Timestamp;
 use crate::dbs::Attach;
+use crate::dbs::Auth;
 use crate::dbs::Executor;
 use crate::dbs::Notification;
 use crate::dbs::Options;
@@ -8,15 +10,19 @@ use crate::dbs::Response;
 use crate::dbs::Session;
 use crate::dbs::Variables;
 use crate::err::Error;
+use crate::idx::build::IndexBuild;
 use crate::sql;
 use crate::sql::Query;
 use crate::sql::Value;
 use channel::Receiver;
 use channel::Sender;
 use futures::lock::Mutex;
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::instrument;
 use uuid::Uuid;
 
@@ -35,6 +41,8 @@ pub struct Datastore {
 	transaction_timeout: Option<Duration>,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// Whether a backup is being restored, during which writes are refused
+	restoring: Arc<AtomicBool>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -224,6 +232,7 @@ impl Datastore {
 			query_timeout: None,
 			transaction_timeout: None,
 			notification_channel: None,
+			restoring: Arc::default(),
 		})
 	}
 
@@ -233,6 +242,12 @@ impl Datastore {
 		self
 	}
 
+	/// Specify the unique id of this node within a cluster of nodes
+	pub fn with_node_id(mut self, id: Uuid) -> Self {
+		self.id = id;
+		self
+	}
+
 	/// Specify whether this datastore should enable live query notifications
 	pub fn with_notifications(mut self) -> Self {
 		self.notification_channel = Some(channel::bounded(100));
@@ -255,20 +270,228 @@ impl Datastore {
 	pub async fn register_membership(&self) -> Result<(), Error> {
 		let mut tx = self.transaction(true, false).await?;
 		tx.set_cl(self.id).await?;
-		tx.set_hb(self.id).await?;
+		tx.set_hb(tx.clock(), self.id).await?;
 		tx.commit().await?;
 		Ok(())
 	}
 
+	// Creates the initial root user from the provided credentials, but
+	// only when no root users have been defined in the datastore yet
+	pub async fn setup_initial_creds(&self, user: &str, pass: &str) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		if tx.all_kl().await?.is_empty() {
+			let dl =
+				sql::statements::DefineLoginStatement::from_password(user, sql::Base::Kv, pass);
+			tx.set(crate::key::kl::new(user), dl).await?;
+			tx.commit().await?;
+		} else {
+			tx.cancel().await?;
+		}
+		Ok(())
+	}
+
+	// Creates another node which shares the storage of this in-memory
+	// datastore, so that a cluster of nodes can be run in one process
+	#[cfg(all(test, feature = "kv-mem"))]
+	pub(crate) fn new_node(&self, id: Uuid) -> Result<Datastore, Error> {
+		match &self.inner {
+			Inner::Mem(v) => Ok(Self {
+				id,
+				inner: Inner::Mem(v.clone()),
+				strict: self.strict,
+				query_timeout: self.query_timeout,
+				transaction_timeout: self.transaction_timeout,
+				notification_channel: None,
+				restoring: self.restoring.clone(),
+			}),
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Ds("Only in-memory datastores can be shared by nodes".to_owned())),
+		}
+	}
+
 	// Creates a heartbeat entry for the member indicating to the cluster
 	// that the node is alive
 	pub async fn heartbeat(&self) -> Result<(), Error> {
 		let mut tx = self.transaction(true, false).await?;
-		tx.set_hb(self.id).await?;
+		tx.set_hb(tx.clock(), self.id).await?;
+		tx.commit().await?;
+		Ok(())
+	}
+
+	// Performs the periodic maintenance tasks of this datastore, using
+	// the current time. This should be called at regular intervals.
+	pub async fn tick(&self) -> Result<(), Error> {
+		self.tick_at(Self::now()).await
+	}
+
+	// The current timestamp in milliseconds
+	fn now() -> u64 {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		now.as_millis() as u64
+	}
+
+	// Performs the periodic maintenance tasks of this datastore, at the
+	// specified timestamp in milliseconds. This updates the heartbeat of
+	// this node, saves the current versionstamp of each database for the
+	// specified timestamp, and removes any expired change feed entries
+	// and revoked tokens. Each database is processed in its own separate
+	// transactions, so that the size of each transaction stays bounded.
+	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
+		// Update the heartbeat of this node
+		let mut tx = self.transaction(true, false).await?;
+		tx.set_hb(
+			Timestamp {
+				value: ts,
+			},
+			self.id,
+		)
+		.await?;
 		tx.commit().await?;
+		// Find all of the databases
+		let mut tx = self.transaction(false, false).await?;
+		let mut dbs = vec![];
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				dbs.push((ns.name.to_raw(), db.name.to_raw()));
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db) in dbs.iter() {
+			// Save the versionstamp of the database
+			let mut tx = self.transaction(true, false).await?;
+			tx.set_timestamp_for_versionstamp(ts, ns, db).await?;
+			tx.commit().await?;
+			// Remove expired change feed entries
+			let mut tx = self.transaction(true, false).await?;
+			crate::cf::gc_db(&mut tx, ns, db, ts).await?;
+			tx.commit().await?;
+		}
+		// Remove revoked tokens which have expired
+		#[cfg(any(
+			feature = "kv-mem",
+			feature = "kv-tikv",
+			feature = "kv-rocksdb",
+			feature = "kv-speedb",
+			feature = "kv-fdb",
+			feature = "kv-indxdb",
+		))]
+		crate::iam::revoke::gc_all_at(self, (ts / 1000) as i64).await?;
 		Ok(())
 	}
 
+	// Builds the indexes which were defined concurrently or rebuilt. The
+	// records of each table are indexed in batches, each batch running in
+	// its own transaction, and the progress of each build is saved, so an
+	// interrupted build resumes from the last indexed record. A build
+	// which fails to index a record is stopped, and the error is saved.
+	// Each build is claimed by one node at a time, and the claim expires
+	// unless it is renewed by the node, so a build which was interrupted
+	// on one node is resumed by another node. As a build can take a long
+	// time, this should be run separately from the periodic tick.
+	pub async fn build_indexes(&self) -> Result<(), Error> {
+		// Find the pending index builds
+		let mut tx = self.transaction(false, false).await?;
+		let mut builds = vec![];
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				for tb in tx.all_tb(&ns.name, &db.name).await?.iter() {
+					for (ix, b) in IndexBuild::all(&mut tx, &ns.name, &db.name, &tb.name).await? {
+						if !b.is_failed() {
+							builds.push((ns.name.to_raw(), db.name.to_raw(), tb.name.to_raw(), ix));
+						}
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Index the records of each table
+		for (ns, db, tb, ix) in builds {
+			let opt = Options::default()
+				.with_id(self.id)
+				.with_ns(Some(ns.into()))
+				.with_db(Some(db.into()))
+				.with_auth(Arc::new(Auth::Kv))
+				.with_strict(self.strict);
+			// Claim the build, unless another node is running it
+			let mut tx = self.transaction(true, false).await?;
+			if !IndexBuild::claim(&mut tx, &opt, &tb, &ix, Self::now()).await? {
+				tx.cancel().await?;
+				continue;
+			}
+			// Another node may have claimed the build meanwhile
+			if tx.commit().await.is_err() {
+				continue;
+			}
+			loop {
+				// Start a new transaction
+				let txn = self.transaction(true, false).await?;
+				let txn = Arc::new(Mutex::new(txn));
+				// Create a default context
+				let mut ctx = Context::default();
+				// Add the transaction
+				ctx.add_transaction(Some(&txn));
+				// Index the next batch of records
+				let res = IndexBuild::next_batch(&ctx, &opt, &tb, &ix, Self::now()).await;
+				let mut txn = txn.lock().await;
+				match res {
+					Ok(done) => {
+						txn.commit().await?;
+						if done {
+							break;
+						}
+					}
+					// The records can't be indexed, so the build is stopped
+					Err(e) => {
+						txn.cancel().await?;
+						let mut tx = self.transaction(true, false).await?;
+						IndexBuild::fail(&mut tx, &opt, &tb, &ix, &e).await?;
+						tx.commit().await?;
+						break;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// Removes any nodes which have not sent a heartbeat within the
+	// expiry, using the current time. This should be called at regular
+	// intervals, when this datastore is one of a cluster of nodes.
+	pub async fn expire_nodes(&self, expiry: Duration) -> Result<Vec<Uuid>, Error> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		self.expire_nodes_at(now.as_millis() as u64, expiry).await
+	}
+
+	// Removes any nodes which have not sent a heartbeat within the expiry,
+	// at the specified timestamp in milliseconds. The cluster membership,
+	// live queries, and queued notifications of each of these nodes are
+	// removed, along with any expired heartbeats. The ids of the removed
+	// nodes are returned.
+	pub async fn expire_nodes_at(&self, ts: u64, expiry: Duration) -> Result<Vec<Uuid>, Error> {
+		let cutoff = ts.saturating_sub(expiry.as_millis() as u64);
+		let mut tx = self.transaction(true, false).await?;
+		// Find the latest heartbeat of each node
+		let mut latest: HashMap<Uuid, u64> = HashMap::new();
+		for (hb, nd) in tx.all_hb().await? {
+			latest.insert(nd, hb.value);
+			// Remove the heartbeats which have expired
+			if hb.value < cutoff {
+				tx.del_hb(hb, nd).await?;
+			}
+		}
+		// Remove the nodes without a recent heartbeat
+		let mut dead = vec![];
+		for nd in tx.all_cl().await? {
+			if nd != self.id && latest.get(&nd).map_or(true, |hb| *hb < cutoff) {
+				tx.del_nd(nd).await?;
+				dead.push(nd);
+			}
+		}
+		// Commit the changes
+		tx.commit().await?;
+		Ok(dead)
+	}
+
 	/// Create a new transaction on this datastore
 	///
 	/// ```rust,no_run
@@ -283,7 +506,17 @@ impl Datastore {
 	///     Ok(())
 	/// }
 	/// ```
+	///
+	/// Write transactions are refused while a backup is being restored.
 	pub async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
+		if write && self.restoring.load(Ordering::Acquire) {
+			return Err(Error::RestoreInProgress);
+		}
+		self.begin(write, lock).await
+	}
+
+	// Create a new transaction, even while a backup is being restored
+	pub(super) async fn begin(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
 		#![allow(unused_variables)]
 		let inner = match &self.inner {
 			#[cfg(feature = "kv-mem")]
@@ -324,6 +557,7 @@ impl Datastore {
 		Ok(Transaction {
 			inner,
 			cache: super::cache::Cache::default(),
+			cf: crate::cf::Writer::new(),
 		})
 	}
 
@@ -472,6 +706,41 @@ impl Datastore {
 		Ok(res)
 	}
 
+	/// Deliver the live query notifications which other nodes have queued
+	/// for the live queries of this node. This should be called at regular
+	/// intervals, when this datastore is one of a cluster of nodes.
+	pub async fn process_notifications(&self) -> Result<(), Error> {
+		// Check if live query notifications are enabled
+		let Some((chn, _)) = &self.notification_channel else {
+			return Ok(());
+		};
+		// Fetch the queued notifications
+		let mut tx = self.transaction(false, false).await?;
+		let nots = tx.scan_nq(self.id).await?;
+		tx.cancel().await?;
+		// Send the notifications to the subscribers. A notification is only
+		// removed from the queue once it was sent, so that it is delivered
+		// at least once, even if this node stops while sending it.
+		let mut sent = Vec::with_capacity(nots.len());
+		let mut res = Ok(());
+		for (key, not) in nots {
+			if let Err(e) = chn.send(not).await {
+				res = Err(e.into());
+				break;
+			}
+			sent.push(key);
+		}
+		// Remove the notifications which were sent
+		if !sent.is_empty() {
+			let mut tx = self.transaction(true, false).await?;
+			for key in sent {
+				tx.del_nq(key).await?;
+			}
+			tx.commit().await?;
+		}
+		res
+	}
+
 	/// Subscribe to live notifications
 	///
 	/// ```rust,no_run
@@ -506,4 +775,34 @@ impl Datastore {
 		// Everything ok
 		Ok(())
 	}
+
+	/// Performs a full backup of the raw key space as a binary dump
+	///
+	/// The dump is read from a single snapshot of the datastore, and ends
+	/// with a checksum which is verified when the dump is restored.
+	#[instrument(skip(self, chn))]
+	pub async fn backup(&self, chn: Sender<Vec<u8>>) -> Result<(), Error> {
+		// Start a new transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Process the backup
+		let res = super::sync::backup(&mut txn, chn).await;
+		// Cancel the transaction
+		txn.cancel().await?;
+		// Return the result
+		res
+	}
+
+	/// Restores a binary dump, which was created by a backup, into an empty datastore
+	///
+	/// The dump is written in batches, each in its own transaction, so a
+	/// restore is not atomic, and the restored data can be read before the
+	/// restore has finished. Other write transactions on this datastore are
+	/// refused until then. If the checksum of the dump does not match, or
+	/// the dump is otherwise invalid, the restored data is removed again.
+	#[instrument(skip(self, chn))]
+	pub async fn restore(&self, chn: Receiver<Vec<u8>>) -> Result<(), Error> {
+		// Refuse other writes until the restore has finished
+		let _guard = super::sync::Guard::new(&self.restoring)?;
+		super::sync::restore(self, chn).await
+	}
 }
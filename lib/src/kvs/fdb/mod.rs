@@ -394,4 +394,44 @@ impl Transaction {
 		}
 		Ok(res)
 	}
+
+	/// Retrieve a range of keys from the databases, in descending order
+	pub async fn scan_rev<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys from the end of the range
+		let begin: Vec<u8> = rng.start;
+		let end: Vec<u8> = rng.end;
+		let opt = foundationdb::RangeOption {
+			limit: Some(limit.try_into().unwrap()),
+			reverse: true,
+			..foundationdb::RangeOption::from((begin.as_slice(), end.as_slice()))
+		};
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		let mut stream = tx.get_ranges_keyvalues(opt, self.snapshot());
+		let mut res: Vec<(Key, Val)> = vec![];
+		loop {
+			let x = stream.try_next().await;
+			match x {
+				Ok(Some(v)) => {
+					let x = (Key::from(v.key()), Val::from(v.value()));
+					res.push(x)
+				}
+				Ok(None) => break,
+				Err(e) => return Err(Error::Tx(format!("GetRanges failed: {}", e))),
+			}
+		}
+		Ok(res)
+	}
 }
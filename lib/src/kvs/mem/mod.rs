@@ -4,9 +4,11 @@ use crate::err::Error;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use std::ops::Range;
+use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct Datastore {
-	db: echodb::Db<Key, Val>,
+	db: Arc<echodb::Db<Key, Val>>,
 }
 
 pub struct Transaction {
@@ -22,7 +24,7 @@ impl Datastore {
 	/// Open a new database
 	pub async fn new() -> Result<Datastore, Error> {
 		Ok(Datastore {
-			db: echodb::db::new(),
+			db: Arc::new(echodb::db::new()),
 		})
 	}
 	/// Start a new transaction
@@ -20,6 +20,7 @@ mod kv;
 mod mem;
 mod rocksdb;
 mod speedb;
+mod sync;
 mod tikv;
 mod tx;
 
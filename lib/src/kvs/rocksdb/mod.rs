@@ -310,4 +310,60 @@ impl Transaction {
 		// Return result
 		Ok(res)
 	}
+
+	/// Retrieve a range of keys from the databases, in descending order
+	pub async fn scan_rev<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the transaction
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Set the ReadOptions with the snapshot
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&tx.snapshot());
+		// Create the iterator
+		let mut iter = tx.raw_iterator_opt(ro);
+		// Seek to the last key which is not after the end key
+		iter.seek_for_prev(&rng.end);
+		// Scan the keys in the iterator
+		while iter.valid() {
+			// Check the scan limit
+			if res.len() < limit as usize {
+				// Get the key and value
+				let (k, v) = (iter.key(), iter.value());
+				// Check the key and value
+				if let (Some(k), Some(v)) = (k, v) {
+					// The end key is excluded from the range
+					if k >= end {
+						iter.prev();
+						continue;
+					}
+					if k >= beg {
+						res.push((k.to_vec(), v.to_vec()));
+						iter.prev();
+						continue;
+					}
+				}
+			}
+			// Exit
+			break;
+		}
+		// Return result
+		Ok(res)
+	}
 }
@@ -0,0 +1,335 @@
+//! The binary format which is used to back up and restore the raw key space.
+//!
+//! A dump starts with a header, which is followed by every key-value pair
+//! in key order, each written as a big-endian `u32` length followed by the
+//! bytes of the key, and then the same for the value. The end of the dump
+//! is marked with a length of `u32::MAX`, followed by the SHA-256 checksum
+//! of all of the preceding bytes.
+//!
+//! The keys which describe the nodes of a cluster, along with the live
+//! queries which belong to those nodes, are not part of a dump, as they
+//! are only meaningful to the nodes which are currently running. The root
+//! logins are not part of a dump either, as they hold the password hashes
+//! of the users of each server, so a datastore which only has root logins
+//! is empty, and keeps its root logins when a dump is restored into it.
+//!
+//! A dump is restored in batches, each written in its own transaction, so
+//! that the size of each transaction stays within the limits of the storage
+//! engines. A restore is therefore not atomic, and the restored key-value
+//! pairs can be read before the restore has finished. Other write transactions
+//! are refused by the datastore while a restore runs, so that it stays empty
+//! apart from the restored key-value pairs. When the dump turns out to be
+//! invalid, the range of keys which were restored is removed again.
+use super::Datastore;
+use super::Key;
+use super::Transaction;
+use super::Val;
+use crate::err::Error;
+use crate::key;
+use channel::{Receiver, Sender};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The header which identifies a dump and its format version
+const HEADER: &[u8] = b"surrealdb-sync\x00\x01";
+/// The length which marks the end of the key-value pairs
+const END: u32 = u32::MAX;
+/// The number of key-value pairs which are sent in each chunk, and
+/// the maximum number which are restored in each transaction
+const BATCH: u32 = 1000;
+/// The maximum number of bytes which are restored in each transaction
+const BATCH_SIZE: usize = 1024 * 1024;
+
+/// Stream a dump of every key-value pair in the transaction snapshot
+pub(super) async fn backup(tx: &mut Transaction, chn: Sender<Vec<u8>>) -> Result<(), Error> {
+	let mut hash = Sha256::new();
+	// Find the keys which are not part of a dump
+	let skip = Skip::new(tx).await?;
+	// Output the header
+	hash.update(HEADER);
+	chn.send(HEADER.to_vec()).await?;
+	// Output the key-value pairs
+	let mut nxt: Option<Key> = None;
+	loop {
+		// Get records batch
+		let res = batch(tx, &mut nxt, &all()).await?;
+		// Exit when settled
+		if res.is_empty() {
+			break;
+		}
+		// Output the batch as one chunk
+		let mut buf = Vec::new();
+		for (k, v) in res.iter().filter(|(k, _)| !skip.contains(k)) {
+			put(&mut buf, k)?;
+			put(&mut buf, v)?;
+		}
+		if !buf.is_empty() {
+			hash.update(&buf);
+			chn.send(buf).await?;
+		}
+	}
+	// Output the checksum
+	let mut buf = END.to_be_bytes().to_vec();
+	hash.update(&buf);
+	buf.extend_from_slice(&hash.finalize());
+	chn.send(buf).await?;
+	Ok(())
+}
+
+/// Write the key-value pairs of a dump into an empty datastore. The other
+/// write transactions of the datastore must be refused while this runs.
+pub(super) async fn restore(ds: &Datastore, chn: Receiver<Vec<u8>>) -> Result<(), Error> {
+	// Check that the datastore is empty
+	let mut tx = ds.begin(false, false).await?;
+	let skip = Skip::new(&mut tx).await?;
+	let mut nxt: Option<Key> = None;
+	loop {
+		let res = batch(&mut tx, &mut nxt, &all()).await?;
+		if res.is_empty() {
+			break;
+		}
+		if res.iter().any(|(k, _)| !skip.contains(k)) {
+			tx.cancel().await?;
+			return Err(Error::RestoreNotEmpty);
+		}
+	}
+	tx.cancel().await?;
+	// Write the key-value pairs in batches
+	let mut tx = ds.begin(true, false).await?;
+	let mut rng: Option<(Key, Key)> = None;
+	let res = match write(ds, &mut tx, &skip, chn, &mut rng).await {
+		Ok(_) => tx.commit().await,
+		Err(e) => {
+			// The transaction may have failed to commit
+			let _ = tx.cancel().await;
+			Err(e)
+		}
+	};
+	// Remove the key-value pairs which were restored
+	if let (Err(_), Some((beg, mut end))) = (&res, rng) {
+		end.push(0x00);
+		clear(ds, &skip, beg..end).await?;
+	}
+	res
+}
+
+/// Write the key-value pairs of a dump, committing the transaction, and
+/// starting a new transaction, after each batch. The range of the keys
+/// which were written is kept, so that they can be removed on failure.
+async fn write(
+	ds: &Datastore,
+	tx: &mut Transaction,
+	skip: &Skip,
+	chn: Receiver<Vec<u8>>,
+	rng: &mut Option<(Key, Key)>,
+) -> Result<(), Error> {
+	// Read the header
+	let mut rdr = Reader::new(chn);
+	if rdr.take(HEADER.len()).await? != HEADER {
+		return Err(invalid("the header is not recognised"));
+	}
+	// Read the key-value pairs
+	let mut num = 0;
+	let mut size = 0;
+	loop {
+		let key = match rdr.len().await? {
+			END => break,
+			n => rdr.take(n as usize).await?,
+		};
+		let val = match rdr.len().await? {
+			END => return Err(invalid("a key has no value")),
+			n => rdr.take(n as usize).await?,
+		};
+		// The keys of a dump are in key order
+		match rng {
+			Some((_, end)) if key <= *end => return Err(invalid("the keys are not in order")),
+			Some((_, end)) => *end = key.clone(),
+			None => *rng = Some((key.clone(), key.clone())),
+		}
+		if !skip.contains(&key) {
+			num += 1;
+			size += key.len() + val.len();
+			tx.set(key, val).await?;
+		}
+		// Commit the batch
+		if num >= BATCH || size >= BATCH_SIZE {
+			tx.commit().await?;
+			*tx = ds.begin(true, false).await?;
+			num = 0;
+			size = 0;
+		}
+	}
+	// Check the checksum
+	let sum = rdr.hash.clone().finalize();
+	if rdr.take(sum.len()).await? != sum.as_slice() {
+		return Err(invalid("the checksum does not match"));
+	}
+	// Check that nothing follows the checksum
+	if rdr.fill(1).await.is_ok() {
+		return Err(invalid("there is data after the checksum"));
+	}
+	Ok(())
+}
+
+/// Remove every key-value pair in the range which is not skipped, in batches
+async fn clear(ds: &Datastore, skip: &Skip, rng: Range<Key>) -> Result<(), Error> {
+	let mut nxt: Option<Key> = None;
+	loop {
+		let mut tx = ds.begin(true, false).await?;
+		let res = batch(&mut tx, &mut nxt, &rng).await?;
+		if res.is_empty() {
+			tx.cancel().await?;
+			return Ok(());
+		}
+		for (k, _) in res.into_iter().filter(|(k, _)| !skip.contains(k)) {
+			tx.del(k).await?;
+		}
+		tx.commit().await?;
+	}
+}
+
+/// Scan the next batch of key-value pairs in the range after the last key
+async fn batch(
+	tx: &mut Transaction,
+	nxt: &mut Option<Key>,
+	rng: &Range<Key>,
+) -> Result<Vec<(Key, Val)>, Error> {
+	let beg = match nxt.take() {
+		Some(mut k) => {
+			k.push(0x00);
+			k
+		}
+		None => rng.start.clone(),
+	};
+	let res = tx.scan(beg..rng.end.clone(), BATCH).await?;
+	*nxt = res.last().map(|(k, _)| k.clone());
+	Ok(res)
+}
+
+/// The range of every key in the datastore
+fn all() -> Range<Key> {
+	vec![0x00]..vec![0xff]
+}
+
+fn invalid(message: &str) -> Error {
+	Error::InvalidBackup {
+		message: message.to_owned(),
+	}
+}
+
+fn put(buf: &mut Vec<u8>, v: &[u8]) -> Result<(), Error> {
+	let len = match u32::try_from(v.len()) {
+		Ok(len) if len != END => len,
+		_ => return Err(Error::TxValueTooLarge),
+	};
+	buf.extend_from_slice(&len.to_be_bytes());
+	buf.extend_from_slice(v);
+	Ok(())
+}
+
+/// Marks that a dump is being restored into a datastore, until dropped
+pub(super) struct Guard<'a> {
+	restoring: &'a AtomicBool,
+}
+
+impl<'a> Guard<'a> {
+	/// Only one dump can be restored into a datastore at a time
+	pub(super) fn new(restoring: &'a AtomicBool) -> Result<Self, Error> {
+		match restoring.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire) {
+			Ok(_) => Ok(Self {
+				restoring,
+			}),
+			Err(_) => Err(Error::RestoreInProgress),
+		}
+	}
+}
+
+impl Drop for Guard<'_> {
+	fn drop(&mut self) {
+		self.restoring.store(false, Ordering::Release);
+	}
+}
+
+/// The keys which are not part of a dump
+struct Skip {
+	lvs: HashSet<Key>,
+}
+
+impl Skip {
+	async fn new(tx: &mut Transaction) -> Result<Self, Error> {
+		// Find the table live queries of every node
+		let mut lvs = HashSet::new();
+		for nd in tx.all_cl().await? {
+			let beg = key::lq::prefix(nd);
+			let end = key::lq::suffix(nd);
+			for (k, v) in tx.getr(beg..end, u32::MAX).await? {
+				let lq = key::lq::Lq::decode(&k)?;
+				let tb = String::from_utf8(v)?;
+				lvs.insert(key::lv::new(lq.ns, lq.db, &tb, lq.lq).into());
+			}
+		}
+		Ok(Self {
+			lvs,
+		})
+	}
+
+	fn contains(&self, k: &[u8]) -> bool {
+		// The cluster membership, heartbeats, and node keys
+		k.starts_with(b"/!cl") || k.starts_with(b"/!hb") || k.starts_with(b"/!nd")
+			// The root logins
+			|| k.starts_with(b"/!kl")
+			// The table live queries of the nodes
+			|| self.lvs.contains(k)
+	}
+}
+
+/// Reads the chunks of a dump, while computing its checksum
+struct Reader {
+	chn: Receiver<Vec<u8>>,
+	buf: Vec<u8>,
+	pos: usize,
+	hash: Sha256,
+}
+
+impl Reader {
+	fn new(chn: Receiver<Vec<u8>>) -> Self {
+		Self {
+			chn,
+			buf: Vec::new(),
+			pos: 0,
+			hash: Sha256::new(),
+		}
+	}
+
+	/// Ensure that at least `n` unread bytes are buffered
+	async fn fill(&mut self, n: usize) -> Result<(), Error> {
+		while self.buf.len() - self.pos < n {
+			match self.chn.recv().await {
+				Ok(v) => {
+					self.buf.drain(..self.pos);
+					self.pos = 0;
+					self.buf.extend(v);
+				}
+				Err(_) => return Err(invalid("the dump ended unexpectedly")),
+			}
+		}
+		Ok(())
+	}
+
+	/// Read the next `n` bytes
+	async fn take(&mut self, n: usize) -> Result<Vec<u8>, Error> {
+		self.fill(n).await?;
+		let v = self.buf[self.pos..self.pos + n].to_vec();
+		self.pos += n;
+		self.hash.update(&v);
+		Ok(v)
+	}
+
+	/// Read the next length
+	async fn len(&mut self) -> Result<u32, Error> {
+		let v = self.take(4).await?;
+		Ok(u32::from_be_bytes([v[0], v[1], v[2], v[3]]))
+	}
+}
@@ -0,0 +1,35 @@
+#[tokio::test]
+#[serial]
+async fn changefeed_gc() {
+	// Create a new datastore
+	let ds = new_ds().await;
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	// Define a table with a change feed of one second
+	let sql = "DEFINE TABLE person CHANGEFEED 1s; DEFINE TABLE other;";
+	ds.execute(sql, &ses, None).await.unwrap();
+	// Write a change at the first versionstamp
+	ds.execute("CREATE person:one; CREATE other:one;", &ses, None).await.unwrap();
+	ds.tick_at(10_000).await.unwrap();
+	// Write a change at the second versionstamp
+	ds.execute("CREATE person:two", &ses, None).await.unwrap();
+	ds.tick_at(10_500).await.unwrap();
+	// Nothing has expired yet
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = crate::cf::read(&mut tx, "test", "test", None, None, None).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert_eq!(res.len(), 2);
+	// The first change expires once one second has passed
+	ds.tick_at(11_000).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = crate::cf::read(&mut tx, "test", "test", None, None, None).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert_eq!(res.len(), 1);
+	assert_eq!(crate::vs::try_to_u64_be(res[0].0).unwrap(), 2);
+	// All changes expire once the change feed is removed
+	ds.execute("DEFINE TABLE person", &ses, None).await.unwrap();
+	ds.tick_at(12_000).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = crate::cf::read(&mut tx, "test", "test", None, None, None).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert!(res.is_empty());
+}
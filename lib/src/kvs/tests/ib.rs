@@ -0,0 +1,38 @@
+#[tokio::test]
+#[serial]
+async fn index_builds_are_claimed_by_one_node() {
+	use crate::dbs::{Options, Session};
+	use crate::idx::build::IndexBuild;
+	use std::time::{SystemTime, UNIX_EPOCH};
+	use uuid::Uuid;
+	// Create two nodes which share one datastore
+	let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+	let one = new_ds().await.with_node_id(a);
+	let two = one.new_node(b).unwrap();
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE user:1 SET email = 'one@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email CONCURRENTLY;
+	";
+	one.execute(sql, &ses, None).await.unwrap();
+	// The first node claims the build
+	let opt =
+		Options::default().with_id(a).with_ns(Some("test".into())).with_db(Some("test".into()));
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+	let mut tx = one.transaction(true, false).await.unwrap();
+	assert!(IndexBuild::claim(&mut tx, &opt, "user", "test", now).await.unwrap());
+	tx.commit().await.unwrap();
+	// The second node does not run a build claimed by another node
+	two.build_indexes().await.unwrap();
+	let mut tx = two.transaction(false, false).await.unwrap();
+	assert_eq!(IndexBuild::all(&mut tx, "test", "test", "user").await.unwrap().len(), 1);
+	tx.cancel().await.unwrap();
+	// The second node runs the build once the claim has expired
+	let mut tx = one.transaction(true, false).await.unwrap();
+	assert!(IndexBuild::claim(&mut tx, &opt, "user", "test", now - 60_000).await.unwrap());
+	tx.commit().await.unwrap();
+	two.build_indexes().await.unwrap();
+	let mut tx = two.transaction(false, false).await.unwrap();
+	assert!(IndexBuild::all(&mut tx, "test", "test", "user").await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+}
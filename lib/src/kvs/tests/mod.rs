@@ -16,6 +16,11 @@ mod mem {
 	include!("raw.rs");
 	include!("snapshot.rs");
 	include!("multireader.rs");
+	include!("cf.rs");
+	include!("nq.rs");
+	include!("nd.rs");
+	include!("ib.rs");
+	include!("sync.rs");
 }
 
 #[cfg(feature = "kv-rocksdb")]
@@ -36,10 +41,12 @@ mod rocksdb {
 	}
 
 	include!("raw.rs");
+	include!("scan_rev.rs");
 	include!("snapshot.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
+	include!("cf.rs");
 }
 
 #[cfg(feature = "kv-speedb")]
@@ -60,10 +67,12 @@ mod speedb {
 	}
 
 	include!("raw.rs");
+	include!("scan_rev.rs");
 	include!("snapshot.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
+	include!("cf.rs");
 }
 
 #[cfg(feature = "kv-tikv")]
@@ -88,10 +97,12 @@ mod tikv {
 	}
 
 	include!("raw.rs");
+	include!("scan_rev.rs");
 	include!("snapshot.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
+	include!("cf.rs");
 }
 
 #[cfg(feature = "kv-fdb")]
@@ -116,8 +127,10 @@ mod fdb {
 	}
 
 	include!("raw.rs");
+	include!("scan_rev.rs");
 	include!("snapshot.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_allow.rs");
+	include!("cf.rs");
 }
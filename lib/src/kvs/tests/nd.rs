@@ -0,0 +1,48 @@
+#[tokio::test]
+#[serial]
+async fn expire_nodes_without_heartbeats() {
+	use crate::dbs::Session;
+	use std::time::{Duration, SystemTime, UNIX_EPOCH};
+	use uuid::Uuid;
+	// Create two nodes which share one datastore
+	let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+	let one = new_ds().await.with_node_id(a).with_notifications();
+	let two = one.new_node(b).unwrap().with_notifications();
+	one.register_membership().await.unwrap();
+	two.register_membership().await.unwrap();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	// Start a live query on the second node
+	two.execute("LIVE SELECT * FROM person", &ses, None).await.unwrap();
+	// Queue a notification for the second node
+	one.execute("CREATE person:test", &ses, None).await.unwrap();
+	// No nodes have expired yet
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+	let expiry = Duration::from_secs(10);
+	let res = one.expire_nodes_at(now + 5_000, expiry).await.unwrap();
+	assert!(res.is_empty());
+	// The second node expires once only the first node sends heartbeats
+	one.tick_at(now + 20_000).await.unwrap();
+	let res = one.expire_nodes_at(now + 20_000, expiry).await.unwrap();
+	assert_eq!(res, vec![b]);
+	// The second node, its live queries, and its notifications are removed
+	let mut tx = one.transaction(false, false).await.unwrap();
+	assert_eq!(tx.all_cl().await.unwrap(), vec![a]);
+	assert!(tx.get_cl(b).await.unwrap().is_none());
+	assert!(tx.all_lv("test", "test", "person").await.unwrap().is_empty());
+	let beg: crate::kvs::Key = crate::key::nd::new(b).into();
+	let end = crate::key::nd::suffix(b);
+	assert!(tx.getr(beg..end, u32::MAX).await.unwrap().is_empty());
+	// Only the latest heartbeat of the first node is kept
+	assert_eq!(tx.all_hb().await.unwrap().len(), 1);
+	tx.cancel().await.unwrap();
+	// Writes no longer queue notifications for the second node
+	one.execute("UPDATE person:test", &ses, None).await.unwrap();
+	let mut tx = one.transaction(false, false).await.unwrap();
+	let beg: crate::kvs::Key = crate::key::nd::new(b).into();
+	let end = crate::key::nd::suffix(b);
+	assert!(tx.getr(beg..end, u32::MAX).await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,63 @@
+#[tokio::test]
+#[serial]
+async fn live_query_notifications_across_nodes() {
+	use crate::dbs::{Action, Session};
+	use crate::sql::Value;
+	use uuid::Uuid;
+	// Create two nodes which share one datastore
+	let one = new_ds().await.with_node_id(Uuid::new_v4()).with_notifications();
+	let two = one.new_node(Uuid::new_v4()).unwrap().with_notifications();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	// Start a live query on the first node
+	let res = &mut one.execute("LIVE SELECT * FROM person", &ses, None).await.unwrap();
+	let Value::Uuid(id) = res.remove(0).result.unwrap() else {
+		panic!("expected a live query id");
+	};
+	// Write a record on the second node
+	two.execute("CREATE person:test SET name = 'Test'", &ses, None).await.unwrap();
+	// The notification is queued for the first node
+	let chn = one.notifications().unwrap();
+	assert!(chn.try_recv().is_err());
+	assert!(two.notifications().unwrap().try_recv().is_err());
+	// The first node delivers the queued notification
+	one.process_notifications().await.unwrap();
+	let not = chn.try_recv().unwrap();
+	assert_eq!(not.id, id.0);
+	assert_eq!(not.action, Action::Create);
+	assert_eq!(not.result, crate::sql::value("{ id: person:test, name: 'Test' }").unwrap());
+	// The queue is emptied once delivered
+	one.process_notifications().await.unwrap();
+	assert!(chn.try_recv().is_err());
+	// Writes on the first node are delivered directly
+	one.execute("DELETE person:test", &ses, None).await.unwrap();
+	let not = chn.try_recv().unwrap();
+	assert_eq!(not.action, Action::Delete);
+}
+
+#[tokio::test]
+#[serial]
+async fn live_query_notifications_are_kept_until_sent() {
+	use crate::dbs::Session;
+	use uuid::Uuid;
+	// Create two nodes which share one datastore
+	let nd = Uuid::new_v4();
+	let one = new_ds().await.with_node_id(nd).with_notifications();
+	let two = one.new_node(Uuid::new_v4()).unwrap().with_notifications();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	// Start a live query on the first node, and queue a notification from the second
+	one.execute("LIVE SELECT * FROM person", &ses, None).await.unwrap();
+	two.execute("CREATE person:test SET name = 'Test'", &ses, None).await.unwrap();
+	// The notification can't be sent once the channel is closed
+	one.notifications().unwrap().close();
+	assert!(one.process_notifications().await.is_err());
+	// The notification is still queued
+	let mut tx = one.transaction(false, false).await.unwrap();
+	assert_eq!(tx.scan_nq(nd).await.unwrap().len(), 1);
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,45 @@
+#[tokio::test]
+#[serial]
+async fn scan_rev() {
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	assert!(tx.put("test", "0").await.is_ok());
+	assert!(tx.put("test1", "1").await.is_ok());
+	assert!(tx.put("test2", "2").await.is_ok());
+	assert!(tx.put("test2\x00", "3").await.is_ok());
+	assert!(tx.put(b"test3\xff".to_vec(), b"4".to_vec()).await.is_ok());
+	assert!(tx.put("test4", "5").await.is_ok());
+	assert!(tx.put("test5", "6").await.is_ok());
+	tx.commit().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan_rev("test".."test9", u32::MAX).await.unwrap();
+	let mut exp = tx.scan("test".."test9", u32::MAX).await.unwrap();
+	exp.reverse();
+	assert_eq!(val.len(), 7);
+	assert_eq!(val, exp);
+	tx.cancel().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan_rev("test1".."test4", u32::MAX).await.unwrap();
+	assert_eq!(val.len(), 4);
+	assert_eq!(val[0].0, b"test3\xff");
+	assert_eq!(val[0].1, b"4");
+	assert_eq!(val[1].0, b"test2\x00");
+	assert_eq!(val[2].0, b"test2");
+	assert_eq!(val[3].0, b"test1");
+	tx.cancel().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan_rev("test".."test9", 2).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"test5");
+	assert_eq!(val[0].1, b"6");
+	assert_eq!(val[1].0, b"test4");
+	assert_eq!(val[1].1, b"5");
+	let val = tx.scan_rev("test6".."test9", 2).await.unwrap();
+	assert!(val.is_empty());
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,80 @@
+#[tokio::test]
+#[serial]
+async fn backup_and_restore() {
+	use crate::dbs::Session;
+	// Create some records, along with a live query which is not backed up
+	let one = new_ds().await.with_notifications();
+	one.register_membership().await.unwrap();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	one.execute("CREATE person:one, person:two SET name = 'test'", &ses, None).await.unwrap();
+	one.execute("LIVE SELECT * FROM person", &ses, None).await.unwrap();
+	// Create enough records to be restored in several transactions
+	let sql = (0..2500).map(|i| format!("CREATE item:{i};")).collect::<String>();
+	one.execute(&sql, &ses, None).await.unwrap();
+	// Create a root login, which is not backed up
+	let kvs = Session::for_kv();
+	one.execute("DEFINE LOGIN one ON ROOT PASSWORD 'one'", &kvs, None).await.unwrap();
+	// Take a backup of the datastore
+	let (snd, rcv) = channel::unbounded();
+	one.backup(snd).await.unwrap();
+	let mut dump = Vec::new();
+	while let Ok(v) = rcv.recv().await {
+		dump.extend(v);
+	}
+	// A datastore which only has root logins is empty
+	let two = new_ds().await;
+	two.execute("DEFINE LOGIN two ON ROOT PASSWORD 'two'", &kvs, None).await.unwrap();
+	// A corrupted dump is not restored, and the restored batches are removed
+	let mut bad = dump.clone();
+	let pos = bad.len() / 2;
+	bad[pos] ^= 0xff;
+	let (snd, rcv) = channel::unbounded();
+	snd.send(bad).await.unwrap();
+	drop(snd);
+	assert!(matches!(two.restore(rcv).await, Err(crate::err::Error::InvalidBackup { .. })));
+	// A truncated dump is not restored
+	let (snd, rcv) = channel::unbounded();
+	snd.send(dump[..dump.len() - 1].to_vec()).await.unwrap();
+	drop(snd);
+	assert!(matches!(two.restore(rcv).await, Err(crate::err::Error::InvalidBackup { .. })));
+	// The dump is restored into an empty datastore, and other writes are
+	// refused while it is being restored
+	let node = two.new_node(uuid::Uuid::new_v4()).unwrap();
+	let (snd, rcv) = channel::unbounded();
+	let (res, _) = futures::join!(two.restore(rcv), async {
+		let res = node.transaction(true, false).await;
+		assert!(matches!(res, Err(crate::err::Error::RestoreInProgress)));
+		node.transaction(false, false).await.unwrap().cancel().await.unwrap();
+		for v in dump.chunks(7) {
+			snd.send(v.to_vec()).await.unwrap();
+		}
+		drop(snd);
+	});
+	res.unwrap();
+	node.transaction(true, false).await.unwrap().cancel().await.unwrap();
+	let res = &mut two.execute("SELECT * FROM person", &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	let exp =
+		crate::sql::value("[{ id: person:one, name: 'test' }, { id: person:two, name: 'test' }]")
+			.unwrap();
+	assert_eq!(val, exp);
+	let res = &mut two.execute("SELECT count() FROM item GROUP ALL", &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[{ count: 2500 }]").unwrap());
+	// The live queries of the first node are not restored
+	let mut tx = two.transaction(false, false).await.unwrap();
+	assert!(tx.all_lv("test", "test", "person").await.unwrap().is_empty());
+	assert!(tx.all_cl().await.unwrap().is_empty());
+	// The root logins of the first datastore are not restored
+	let kl = tx.all_kl().await.unwrap();
+	assert_eq!(kl.iter().map(|v| v.name.to_string()).collect::<Vec<_>>(), vec!["two"]);
+	tx.cancel().await.unwrap();
+	// The dump is not restored into a datastore which is not empty
+	let (snd, rcv) = channel::unbounded();
+	snd.send(dump).await.unwrap();
+	drop(snd);
+	assert!(matches!(two.restore(rcv).await, Err(crate::err::Error::RestoreNotEmpty)));
+}
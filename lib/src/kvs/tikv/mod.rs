@@ -260,4 +260,24 @@ impl Transaction {
 		// Return result
 		Ok(res)
 	}
+	/// Retrieve a range of keys from the databases, in descending order
+	pub async fn scan_rev<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys from the end of the range
+		let res = self.tx.scan_reverse(rng, limit).await?;
+		let res = res.map(|kv| (Key::from(kv.0), kv.1)).collect();
+		// Return result
+		Ok(res)
+	}
 }
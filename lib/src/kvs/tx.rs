@@ -2,8 +2,11 @@ use super::kv::Add;
 use super::kv::Convert;
 use super::Key;
 use super::Val;
+use crate::cf;
+use crate::cf::TableMutation;
 use crate::dbs::cl::ClusterMembership;
 use crate::dbs::cl::Timestamp;
+use crate::dbs::Notification;
 use crate::err::Error;
 use crate::key::thing;
 use crate::kvs::cache::Cache;
@@ -14,6 +17,9 @@ use crate::sql::paths::IN;
 use crate::sql::paths::OUT;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
+use crate::vs::try_to_u64_be;
+use crate::vs::u64_to_versionstamp;
+use crate::vs::Versionstamp;
 use channel::Sender;
 use sql::permission::Permissions;
 use sql::statements::DefineAnalyzerStatement;
@@ -29,6 +35,8 @@ use sql::statements::DefineScopeStatement;
 use sql::statements::DefineTableStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::LiveStatement;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Range;
@@ -41,6 +49,7 @@ use uuid::Uuid;
 pub struct Transaction {
 	pub(super) inner: Inner,
 	pub(super) cache: Cache,
+	pub(super) cf: cf::Writer,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -179,6 +188,8 @@ impl Transaction {
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
+		// Write any buffered change feeds
+		self.complete_changes().await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -483,6 +494,72 @@ impl Transaction {
 		}
 	}
 
+	/// Check if the storage engine can scan a range of keys in descending order.
+	pub fn can_scan_rev(&self) -> bool {
+		match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(_),
+				..
+			} => true,
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
+
+	/// Retrieve a specific range of keys from the datastore, in descending order.
+	///
+	/// This function fetches at most `limit` of the last key-value pairs of the range.
+	/// It is only supported by the storage engines which can scan backwards.
+	#[allow(unused_variables)]
+	pub async fn scan_rev<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("Scan reverse {:?} - {:?}", rng.start, rng.end);
+		match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(v),
+				..
+			} => v.scan_rev(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(v),
+				..
+			} => v.scan_rev(rng, limit).await,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(v),
+				..
+			} => v.scan_rev(rng, limit).await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(v),
+				..
+			} => v.scan_rev(rng, limit).await,
+			// The other storage engines can only be scanned forwards
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Unreachable),
+		}
+	}
+
 	/// Update a key in the datastore if the current value matches a condition.
 	#[allow(unused_variables)]
 	pub async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
@@ -820,7 +897,35 @@ impl Transaction {
 		}
 	}
 
-	fn clock(&self) -> Timestamp {
+	// Retrieve the ids of all cluster members
+	pub async fn all_cl(&mut self) -> Result<Vec<Uuid>, Error> {
+		let beg = crate::key::cl::prefix();
+		let end = crate::key::cl::suffix();
+		let val = self.getr(beg..end, u32::MAX).await?;
+		val.iter().map(|(k, _)| Ok(crate::key::cl::Cl::decode(k)?.nd)).collect()
+	}
+
+	// Remove a node from the cluster, along with its live queries
+	// and any notifications which are queued for the node
+	pub async fn del_nd(&mut self, nd: Uuid) -> Result<(), Error> {
+		// Remove the live queries of the node from their tables
+		let beg = crate::key::lq::prefix(nd);
+		let end = crate::key::lq::suffix(nd);
+		for (k, v) in self.getr(beg..end, u32::MAX).await? {
+			let lq = crate::key::lq::Lq::decode(&k)?;
+			let tb = String::from_utf8(v)?;
+			self.del(crate::key::lv::new(lq.ns, lq.db, &tb, lq.lq)).await?;
+		}
+		// Remove all of the keys under the node
+		let beg: Key = crate::key::nd::new(nd).into();
+		let end = crate::key::nd::suffix(nd);
+		self.delr(beg..end, u32::MAX).await?;
+		// Remove the cluster membership of the node
+		self.del(crate::key::cl::new(nd)).await?;
+		Ok(())
+	}
+
+	pub(crate) fn clock(&self) -> Timestamp {
 		// Use a timestamp oracle if available
 		let now: u128 = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
 		Timestamp {
@@ -829,8 +934,8 @@ impl Transaction {
 	}
 
 	// Set heartbeat
-	pub async fn set_hb(&mut self, id: Uuid) -> Result<(), Error> {
-		let now = self.clock();
+	pub async fn set_hb(&mut self, timestamp: Timestamp, id: Uuid) -> Result<(), Error> {
+		let now = timestamp;
 		let key = crate::key::hb::Hb::new(now.clone(), id);
 		// We do not need to do a read, we always want to overwrite
 		self.put(
@@ -844,6 +949,174 @@ impl Transaction {
 		Ok(())
 	}
 
+	// Retrieve all heartbeats, ordered by time
+	pub async fn all_hb(&mut self) -> Result<Vec<(Timestamp, Uuid)>, Error> {
+		let beg = crate::key::hb::prefix();
+		let end = crate::key::hb::suffix();
+		let val = self.getr(beg..end, u32::MAX).await?;
+		val.iter()
+			.map(|(k, _)| {
+				let hb = crate::key::hb::Hb::decode(k)?;
+				Ok((hb.hb, hb.nd))
+			})
+			.collect()
+	}
+
+	// Remove a heartbeat
+	pub async fn del_hb(&mut self, timestamp: Timestamp, id: Uuid) -> Result<(), Error> {
+		let key = crate::key::hb::new(timestamp, id);
+		self.del(key).await?;
+		Ok(())
+	}
+
+	// Queue a live query notification for delivery by another node
+	pub async fn set_nq(&mut self, nd: Uuid, not: Notification) -> Result<(), Error> {
+		// Notifications are queued in the order in which they were produced
+		let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+		let key = crate::key::nq::new(nd, ts as u64, Uuid::new_v4());
+		self.put(key, not).await?;
+		Ok(())
+	}
+
+	// Retrieve all live query notifications queued for a node, in order
+	pub async fn scan_nq(&mut self, nd: Uuid) -> Result<Vec<(Key, Notification)>, Error> {
+		let beg = crate::key::nq::prefix(nd);
+		let end = crate::key::nq::suffix(nd);
+		let val = self.getr(beg..end, u32::MAX).await?;
+		Ok(val.into_iter().map(|(k, v)| (k, v.into())).collect())
+	}
+
+	// Remove a live query notification once it was delivered
+	pub async fn del_nq(&mut self, key: Key) -> Result<(), Error> {
+		self.del(key).await?;
+		Ok(())
+	}
+
+	/// Record a change to a record in a table which has a change feed.
+	///
+	/// The change is buffered, and is only written to the
+	/// datastore when the transaction is committed.
+	pub(crate) fn record_change(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &Thing,
+		v: Cow<'_, Value>,
+		new: bool,
+	) {
+		self.cf.update(ns, db, tb, id.clone(), v, new)
+	}
+
+	/// Allocate the next versionstamp for a specific database.
+	///
+	/// None of the storage engines expose commit versionstamps to us,
+	/// so we emulate them with a counter stored in the `dv` key. Any
+	/// two transactions writing change feeds to the same database will
+	/// conflict on this key, which keeps the versionstamps ordered.
+	pub(crate) async fn get_versionstamp(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Versionstamp, Error> {
+		let ver = match self.read_versionstamp(ns, db).await? {
+			Some(v) => try_to_u64_be(v).map_err(|_| Error::InvalidVersionstamp)? + 1,
+			None => 1,
+		};
+		let vs = u64_to_versionstamp(ver);
+		let key = crate::key::dv::new(ns, db);
+		self.set(key, vs.to_vec()).await?;
+		Ok(vs)
+	}
+
+	/// Retrieve the latest versionstamp allocated for a specific database.
+	pub(crate) async fn read_versionstamp(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Option<Versionstamp>, Error> {
+		let key = crate::key::dv::new(ns, db);
+		match self.get(key).await? {
+			Some(v) => {
+				let v: Versionstamp =
+					v.as_slice().try_into().map_err(|_| Error::InvalidVersionstamp)?;
+				Ok(Some(v))
+			}
+			None => Ok(None),
+		}
+	}
+
+	/// Save the latest versionstamp of a specific database at a timestamp.
+	///
+	/// These entries allow the change feed garbage collector to
+	/// work out which versionstamps were allocated before a point
+	/// in time, as versionstamps themselves do not contain a time.
+	pub(crate) async fn set_timestamp_for_versionstamp(
+		&mut self,
+		ts: u64,
+		ns: &str,
+		db: &str,
+	) -> Result<(), Error> {
+		if let Some(vs) = self.read_versionstamp(ns, db).await? {
+			let key = crate::key::ts::new(ns, db, ts);
+			self.set(key, vs.to_vec()).await?;
+		}
+		Ok(())
+	}
+
+	/// Write all buffered change feed mutations to the datastore.
+	///
+	/// All of the changes made to a database within this transaction
+	/// are stored under the same versionstamp, with one entry per table.
+	/// The final value of each changed record is also stored in the
+	/// history of the record, along with the time of the commit.
+	pub(crate) async fn complete_changes(&mut self) -> Result<(), Error> {
+		// Check if there is anything to write
+		if self.cf.is_empty() {
+			return Ok(());
+		}
+		let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+		// Versionstamp of the last processed database
+		let mut last: Option<(String, String, Versionstamp)> = None;
+		// Changes are ordered by namespace and database
+		for (k, muts) in self.cf.drain() {
+			let vs = match last {
+				Some((ref ns, ref db, vs)) if *ns == k.ns && *db == k.db => vs,
+				_ => {
+					let vs = self.get_versionstamp(&k.ns, &k.db).await?;
+					last = Some((k.ns.clone(), k.db.clone(), vs));
+					vs
+				}
+			};
+			// Keep the final value of each record changed in this transaction
+			let mut his: BTreeMap<Key, cf::Change> = BTreeMap::new();
+			for m in muts.1.iter() {
+				let (id, new, val) = match m {
+					TableMutation::Set(t, v) => (&t.id, false, Some(v.clone())),
+					TableMutation::Create(t, v) => (&t.id, true, Some(v.clone())),
+					TableMutation::Del(t) => (&t.id, false, None),
+				};
+				let key = crate::key::hs::new(&k.ns, &k.db, &k.tb, id, vs).encode()?;
+				// A record created in this transaction did not exist before it
+				let new = new || his.get(&key).map_or(false, |c| c.new);
+				his.insert(
+					key,
+					cf::Change {
+						ts: ts as u64,
+						new,
+						val,
+					},
+				);
+			}
+			for (key, chg) in his {
+				self.set(key, chg).await?;
+			}
+			let key = crate::key::cf::Cf::new(&k.ns, &k.db, vs, &k.tb);
+			self.set(key, muts).await?;
+		}
+		Ok(())
+	}
+
 	/// Retrieve all namespace definitions in a datastore.
 	pub async fn all_ns(&mut self) -> Result<Arc<[DefineNamespaceStatement]>, Error> {
 		let key = crate::key::ns::prefix();
@@ -863,6 +1136,25 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all root login definitions.
+	pub async fn all_kl(&mut self) -> Result<Arc<[DefineLoginStatement]>, Error> {
+		let key = crate::key::kl::prefix();
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Kls(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::kl::prefix();
+			let end = crate::key::kl::suffix();
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Kls(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all namespace login definitions for a specific namespace.
 	pub async fn all_nl(&mut self, ns: &str) -> Result<Arc<[DefineLoginStatement]>, Error> {
 		let key = crate::key::nl::prefix(ns);
@@ -1242,6 +1534,15 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve a specific root login definition.
+	pub async fn get_kl(&mut self, us: &str) -> Result<DefineLoginStatement, Error> {
+		let key = crate::key::kl::new(us);
+		let val = self.get(key).await?.ok_or(Error::KlNotFound {
+			value: us.to_owned(),
+		})?;
+		Ok(val.into())
+	}
+
 	/// Retrieve a specific namespace token definition.
 	pub async fn get_nt(&mut self, ns: &str, nt: &str) -> Result<DefineTokenStatement, Error> {
 		let key = crate::key::nt::new(ns, nt);
@@ -20,6 +20,7 @@ pub enum Algorithm {
 	Rs256,
 	Rs384,
 	Rs512,
+	Jwks,
 }
 
 impl Default for Algorithm {
@@ -44,6 +45,7 @@ impl fmt::Display for Algorithm {
 			Self::Rs256 => "RS256",
 			Self::Rs384 => "RS384",
 			Self::Rs512 => "RS512",
+			Self::Jwks => "JWKS",
 		})
 	}
 }
@@ -63,5 +65,6 @@ pub fn algorithm(i: &str) -> IResult<&str, Algorithm> {
 		map(tag("RS256"), |_| Algorithm::Rs256),
 		map(tag("RS384"), |_| Algorithm::Rs384),
 		map(tag("RS512"), |_| Algorithm::Rs512),
+		map(tag("JWKS"), |_| Algorithm::Jwks),
 	))(i)
 }
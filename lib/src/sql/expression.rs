@@ -182,6 +182,7 @@ impl Expression {
 			Operator::Outside => fnc::operate::outside(&l, &r),
 			Operator::Intersects => fnc::operate::intersects(&l, &r),
 			Operator::Matches(_) => fnc::operate::matches(ctx, self).await,
+			Operator::Knn(_) => fnc::operate::knn(ctx, self).await,
 			_ => unreachable!(),
 		}
 	}
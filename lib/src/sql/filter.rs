@@ -1,12 +1,20 @@
-use crate::sql::comment::shouldbespace;
-use crate::sql::common::{closeparentheses, commas, openparentheses};
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{
+	closebraces, closebracket, closeparentheses, commas, openbraces, openbracket, openparentheses,
+};
 use crate::sql::error::IResult;
+use crate::sql::escape::{escape_key, quote_str};
+use crate::sql::fmt::Fmt;
 use crate::sql::language::{language, Language};
+use crate::sql::object::key;
+use crate::sql::strand::strand_raw;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::character::complete::u16;
-use nom::multi::separated_list1;
+use nom::character::complete::{char, u16};
+use nom::combinator::{map, opt};
+use nom::multi::{separated_list0, separated_list1};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Display;
 
@@ -18,6 +26,28 @@ pub enum Filter {
 	Ngram(u16, u16),
 	Snowball(Language),
 	Uppercase,
+	Stopwords(Stopwords),
+	Synonyms(BTreeMap<String, Vec<String>>),
+	Mapper(BTreeMap<String, String>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub enum Stopwords {
+	/// The built-in list of stop words of a language
+	Language(Language),
+	/// A user-provided list of stop words
+	Custom(Vec<String>),
+}
+
+impl Display for Stopwords {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Language(lang) => Display::fmt(lang, f),
+			Self::Custom(words) => {
+				write!(f, "[{}]", Fmt::comma_separated(words.iter().map(|w| quote_str(w))))
+			}
+		}
+	}
 }
 
 impl Display for Filter {
@@ -26,8 +56,27 @@ impl Display for Filter {
 			Self::Ascii => f.write_str("ASCII"),
 			Self::EdgeNgram(min, max) => write!(f, "EDGENGRAM({},{})", min, max),
 			Self::Lowercase => f.write_str("LOWERCASE"),
+			Self::Mapper(terms) => write!(
+				f,
+				"MAPPER({{ {} }})",
+				Fmt::comma_separated(terms.iter().map(|(k, v)| format!(
+					"{}: {}",
+					escape_key(k),
+					quote_str(v)
+				)))
+			),
 			Self::Ngram(min, max) => write!(f, "NGRAM({},{})", min, max),
 			Self::Snowball(lang) => write!(f, "SNOWBALL({})", lang),
+			Self::Stopwords(words) => write!(f, "STOPWORDS({})", words),
+			Self::Synonyms(terms) => write!(
+				f,
+				"SYNONYMS({{ {} }})",
+				Fmt::comma_separated(terms.iter().map(|(k, v)| format!(
+					"{}: [{}]",
+					escape_key(k),
+					Fmt::comma_separated(v.iter().map(|s| quote_str(s)))
+				)))
+			),
 			Self::Uppercase => f.write_str("UPPERCASE"),
 		}
 	}
@@ -71,13 +120,67 @@ fn snowball(i: &str) -> IResult<&str, Filter> {
 	Ok((i, Filter::Snowball(language)))
 }
 
+fn stopwords(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("STOPWORDS")(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, words) = alt((map(language, Stopwords::Language), map(strings, Stopwords::Custom)))(i)?;
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, Filter::Stopwords(words)))
+}
+
+fn synonyms(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("SYNONYMS")(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, terms) = terms(i, alt((strings, map(strand_raw, |s| vec![s]))))?;
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, Filter::Synonyms(terms)))
+}
+
+fn mapper(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("MAPPER")(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, terms) = terms(i, strand_raw)?;
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, Filter::Mapper(terms)))
+}
+
 fn uppercase(i: &str) -> IResult<&str, Filter> {
 	let (i, _) = tag_no_case("UPPERCASE")(i)?;
 	Ok((i, Filter::Uppercase))
 }
 
+/// A list of strings: `['a', 'b']`
+fn strings(i: &str) -> IResult<&str, Vec<String>> {
+	let (i, _) = openbracket(i)?;
+	let (i, v) = separated_list0(commas, strand_raw)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = opt(char(','))(i)?;
+	let (i, _) = closebracket(i)?;
+	Ok((i, v))
+}
+
+/// A map of terms: `{ term: value, 'other term': value }`
+fn terms<'a, V, F>(i: &'a str, mut value: F) -> IResult<&'a str, BTreeMap<String, V>>
+where
+	F: FnMut(&'a str) -> IResult<&'a str, V>,
+{
+	let (i, _) = openbraces(i)?;
+	let (i, v) = separated_list0(commas, |i| {
+		let (i, k) = key(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, _) = char(':')(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, v) = value(i)?;
+		Ok((i, (String::from(k), v)))
+	})(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = opt(char(','))(i)?;
+	let (i, _) = closebraces(i)?;
+	Ok((i, v.into_iter().collect()))
+}
+
 fn filter(i: &str) -> IResult<&str, Filter> {
-	alt((ascii, edgengram, lowercase, ngram, snowball, uppercase))(i)
+	alt((ascii, edgengram, lowercase, mapper, ngram, snowball, stopwords, synonyms, uppercase))(i)
 }
 
 pub(super) fn filters(i: &str) -> IResult<&str, Vec<Filter>> {
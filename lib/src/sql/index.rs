@@ -1,14 +1,20 @@
 use crate::idx::ft::analyzer::Analyzers;
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{closeparentheses, commas, openparentheses};
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::scoring::{scoring, Scoring};
+use crate::sql::Error::Parser;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
-use nom::character::complete::u32;
+use nom::character::complete::{u16, u32};
 use nom::combinator::{map, opt};
+use nom::multi::separated_list1;
+use nom::number::complete::recognize_float;
+use nom::Err::Failure;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum Index {
@@ -22,7 +28,69 @@ pub enum Index {
 		hl: bool,
 		sc: Scoring,
 		order: u32,
+		/// The weight of each column when scoring
+		wt: Option<Weights>,
 	},
+	/// Index with vector similarity search capabilities
+	MTree(MTreeParams),
+}
+
+/// The weights applied to the score of each column of a multi-column full-text index
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Weights(pub Vec<f32>);
+
+impl Eq for Weights {}
+
+impl PartialEq for Weights {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.len() == other.0.len()
+			&& self.0.iter().zip(other.0.iter()).all(|(a, b)| a.to_bits() == b.to_bits())
+	}
+}
+
+impl Hash for Weights {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		for w in &self.0 {
+			w.to_bits().hash(state);
+		}
+	}
+}
+
+impl fmt::Display for Weights {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("WEIGHTS(")?;
+		for (i, w) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str(",")?;
+			}
+			write!(f, "{}", w)?;
+		}
+		f.write_str(")")
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct MTreeParams {
+	pub dimension: u16,
+	pub distance: Distance,
+	pub capacity: u16,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub enum Distance {
+	Euclidean,
+	Cosine,
+	Manhattan,
+}
+
+impl fmt::Display for Distance {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Euclidean => f.write_str("EUCLIDEAN"),
+			Self::Cosine => f.write_str("COSINE"),
+			Self::Manhattan => f.write_str("MANHATTAN"),
+		}
+	}
 }
 
 impl Default for Index {
@@ -41,19 +109,31 @@ impl fmt::Display for Index {
 				hl,
 				sc,
 				order,
+				wt,
 			} => {
-				write!(f, "SEARCH ANALYZER {} {} ORDER {}", az, sc, order)?;
+				write!(f, "SEARCH ANALYZER {} {}", az, sc)?;
+				if let Some(wt) = wt {
+					write!(f, " {}", wt)?;
+				}
+				write!(f, " ORDER {}", order)?;
 				if *hl {
 					f.write_str(" HIGHLIGHTS")?
 				}
 				Ok(())
 			}
+			Self::MTree(p) => {
+				write!(
+					f,
+					"MTREE DIMENSION {} DIST {} CAPACITY {}",
+					p.dimension, p.distance, p.capacity
+				)
+			}
 		}
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, Index> {
-	alt((unique, search, non_unique))(i)
+	alt((unique, search, mtree, non_unique))(i)
 }
 
 pub fn non_unique(i: &str) -> IResult<&str, Index> {
@@ -82,6 +162,23 @@ pub fn order(i: &str) -> IResult<&str, u32> {
 	Ok((i, order))
 }
 
+pub fn weights(i: &str) -> IResult<&str, Weights> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = tag_no_case("WEIGHTS")(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, w) = separated_list1(commas, weight)(i)?;
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, Weights(w)))
+}
+
+fn weight(i: &str) -> IResult<&str, f32> {
+	let (i, w) = recognize_float(i)?;
+	match w.parse::<f32>() {
+		Ok(w) if w >= 0.0 => Ok((i, w)),
+		_ => Err(Failure(Parser(i))),
+	}
+}
+
 pub fn highlights(i: &str) -> IResult<&str, bool> {
 	let (i, _) = mightbespace(i)?;
 	alt((map(tag("HIGHLIGHTS"), |_| true), map(tag(""), |_| false)))(i)
@@ -93,6 +190,7 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 	let (i, az) = opt(analyzer)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, sc) = scoring(i)?;
+	let (i, wt) = opt(weights)(i)?;
 	let (i, o) = opt(order)(i)?;
 	let (i, hl) = highlights(i)?;
 	Ok((
@@ -102,6 +200,50 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 			sc,
 			hl,
 			order: o.unwrap_or(100),
+			wt,
 		},
 	))
 }
+
+pub fn distance(i: &str) -> IResult<&str, Distance> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = tag_no_case("DIST")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	alt((
+		map(tag_no_case("EUCLIDEAN"), |_| Distance::Euclidean),
+		map(tag_no_case("COSINE"), |_| Distance::Cosine),
+		map(tag_no_case("MANHATTAN"), |_| Distance::Manhattan),
+	))(i)
+}
+
+pub fn dimension(i: &str) -> IResult<&str, u16> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = tag_no_case("DIMENSION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, dim) = u16(i)?;
+	Ok((i, dim))
+}
+
+pub fn capacity(i: &str) -> IResult<&str, u16> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = tag_no_case("CAPACITY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, capacity) = u16(i)?;
+	Ok((i, capacity))
+}
+
+pub fn mtree(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("MTREE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, dimension) = dimension(i)?;
+	let (i, distance) = opt(distance)(i)?;
+	let (i, capacity) = opt(capacity)(i)?;
+	Ok((
+		i,
+		Index::MTree(MTreeParams {
+			dimension,
+			distance: distance.unwrap_or(Distance::Euclidean),
+			capacity: capacity.unwrap_or(40),
+		}),
+	))
+}
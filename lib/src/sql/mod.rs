@@ -51,6 +51,7 @@ pub(crate) mod permission;
 pub(crate) mod query;
 pub(crate) mod range;
 pub(crate) mod regex;
+pub(crate) mod role;
 pub(crate) mod scoring;
 pub(crate) mod script;
 pub(crate) mod special;
@@ -122,6 +123,7 @@ pub use self::permission::Permissions;
 pub use self::query::Query;
 pub use self::range::Range;
 pub use self::regex::Regex;
+pub use self::role::Role;
 pub use self::script::Script;
 pub use self::split::Split;
 pub use self::split::Splits;
@@ -6,6 +6,7 @@ use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
+use nom::character::complete::u32 as uint32;
 use nom::character::complete::u8 as uint8;
 use nom::combinator::{map, opt};
 use serde::{Deserialize, Serialize};
@@ -63,6 +64,8 @@ pub enum Operator {
 	//
 	Outside,
 	Intersects,
+	//
+	Knn(u32), // <|{k}|>
 }
 
 impl Default for Operator {
@@ -137,6 +140,7 @@ impl fmt::Display for Operator {
 					f.write_str("@@")
 				}
 			}
+			Self::Knn(k) => write!(f, "<|{}|>", k),
 		}
 	}
 }
@@ -190,6 +194,7 @@ pub fn binary_symbols(i: &str) -> IResult<&str, Operator> {
 			matches,
 		)),
 		alt((
+			knn,
 			map(tag("<="), |_| Operator::LessThanOrEqual),
 			map(char('<'), |_| Operator::LessThan),
 			map(tag(">="), |_| Operator::MoreThanOrEqual),
@@ -260,6 +265,13 @@ pub fn matches(i: &str) -> IResult<&str, Operator> {
 	Ok((i, Operator::Matches(reference)))
 }
 
+pub fn knn(i: &str) -> IResult<&str, Operator> {
+	let (i, _) = tag("<|")(i)?;
+	let (i, k) = uint32(i)?;
+	let (i, _) = tag("|>")(i)?;
+	Ok((i, Operator::Knn(k)))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -287,4 +299,19 @@ mod tests {
 		let res = matches("@256@");
 		assert!(res.is_err());
 	}
+
+	#[test]
+	fn knn_with_k() {
+		let res = knn("<|10|>");
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("<|10|>", format!("{}", out));
+		assert_eq!(out, Operator::Knn(10));
+	}
+
+	#[test]
+	fn knn_without_k() {
+		let res = knn("<||>");
+		assert!(res.is_err());
+	}
 }
@@ -0,0 +1,82 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
+use crate::sql::error::IResult;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
+use nom::multi::separated_list1;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The role granted to a namespace or database login. The roles are ordered
+/// from the least to the most permissive, and each role grants the
+/// permissions of the roles which precede it.
+#[derive(
+	Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Hash,
+)]
+pub enum Role {
+	/// Can read the data and the table definitions
+	#[default]
+	Viewer,
+	/// Can also create, update and delete the data
+	Editor,
+	/// Can also define and remove resources
+	Owner,
+}
+
+impl fmt::Display for Role {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Viewer => f.write_str("VIEWER"),
+			Self::Editor => f.write_str("EDITOR"),
+			Self::Owner => f.write_str("OWNER"),
+		}
+	}
+}
+
+pub fn role(i: &str) -> IResult<&str, Role> {
+	alt((
+		map(tag_no_case("VIEWER"), |_| Role::Viewer),
+		map(tag_no_case("EDITOR"), |_| Role::Editor),
+		map(tag_no_case("OWNER"), |_| Role::Owner),
+	))(i)
+}
+
+pub fn roles(i: &str) -> IResult<&str, Vec<Role>> {
+	let (i, _) = tag_no_case("ROLES")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	separated_list1(commas, role)(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::fmt::Fmt;
+
+	#[test]
+	fn roles_single() {
+		let sql = "ROLES viewer";
+		let res = roles(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, vec![Role::Viewer]);
+		assert_eq!("VIEWER", format!("{}", Fmt::comma_separated(&out)));
+	}
+
+	#[test]
+	fn roles_multiple() {
+		let sql = "ROLES EDITOR, OWNER";
+		let res = roles(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, vec![Role::Editor, Role::Owner]);
+		assert_eq!("EDITOR, OWNER", format!("{}", Fmt::comma_separated(&out)));
+	}
+
+	#[test]
+	fn roles_ordered() {
+		assert!(Role::Viewer < Role::Editor);
+		assert!(Role::Editor < Role::Owner);
+	}
+}
@@ -20,6 +20,7 @@ use crate::sql::statements::kill::{kill, KillStatement};
 use crate::sql::statements::live::{live, LiveStatement};
 use crate::sql::statements::option::{option, OptionStatement};
 use crate::sql::statements::output::{output, OutputStatement};
+use crate::sql::statements::rebuild::{rebuild, RebuildStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
 use crate::sql::statements::select::{select, SelectStatement};
@@ -89,6 +90,7 @@ pub enum Statement {
 	Live(LiveStatement),
 	Option(OptionStatement),
 	Output(OutputStatement),
+	Rebuild(RebuildStatement),
 	Relate(RelateStatement),
 	Remove(RemoveStatement),
 	Select(SelectStatement),
@@ -115,7 +117,7 @@ impl Statement {
 	/// Check if we require a writeable transaction
 	pub(crate) fn writeable(&self) -> bool {
 		match self {
-			Self::Analyze(_) => false,
+			Self::Analyze(_) => true,
 			Self::Create(v) => v.writeable(),
 			Self::Define(_) => true,
 			Self::Delete(v) => v.writeable(),
@@ -125,6 +127,7 @@ impl Statement {
 			Self::Kill(_) => true,
 			Self::Live(_) => true,
 			Self::Output(v) => v.writeable(),
+			Self::Rebuild(_) => true,
 			Self::Option(_) => false,
 			Self::Relate(v) => v.writeable(),
 			Self::Remove(_) => true,
@@ -150,6 +153,7 @@ impl Statement {
 			Self::Kill(v) => v.compute(ctx, opt).await,
 			Self::Live(v) => v.compute(ctx, opt).await,
 			Self::Output(v) => v.compute(ctx, opt).await,
+			Self::Rebuild(v) => v.compute(ctx, opt).await,
 			Self::Relate(v) => v.compute(ctx, opt).await,
 			Self::Remove(v) => v.compute(ctx, opt).await,
 			Self::Select(v) => v.compute(ctx, opt).await,
@@ -179,6 +183,7 @@ impl Display for Statement {
 			Self::Live(v) => write!(Pretty::from(f), "{v}"),
 			Self::Option(v) => write!(Pretty::from(f), "{v}"),
 			Self::Output(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rebuild(v) => write!(Pretty::from(f), "{v}"),
 			Self::Relate(v) => write!(Pretty::from(f), "{v}"),
 			Self::Remove(v) => write!(Pretty::from(f), "{v}"),
 			Self::Select(v) => write!(Pretty::from(f), "{v}"),
@@ -209,13 +214,17 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 			map(live, Statement::Live),
 			map(option, Statement::Option),
 			map(output, Statement::Output),
+			map(rebuild, Statement::Rebuild),
 			map(relate, Statement::Relate),
 			map(remove, Statement::Remove),
 			map(select, Statement::Select),
 			map(set, Statement::Set),
 			map(show, Statement::Show),
-			map(sleep, Statement::Sleep),
-			alt((map(update, Statement::Update), map(yuse, Statement::Use))),
+			alt((
+				map(sleep, Statement::Sleep),
+				map(update, Statement::Update),
+				map(yuse, Statement::Use),
+			)),
 		)),
 		mightbespace,
 	)(i)
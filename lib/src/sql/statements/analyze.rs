@@ -3,11 +3,14 @@ use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::idx::ft::FtIndex;
+use crate::idx::mtree::MTree;
+use crate::idx::stats::IndexStatistics;
 use crate::idx::IndexKeyBase;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::index::Index;
+use crate::sql::role::Role;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::bytes::complete::tag_no_case;
@@ -29,6 +32,7 @@ impl AnalyzeStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Db)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -44,19 +48,26 @@ impl AnalyzeStatement {
 						order,
 						sc,
 						hl,
+						wt,
 					} => {
 						let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
-						let ft = FtIndex::new(&mut run, az, ikb, *order, sc, *hl).await?;
-						ft.statistics(&mut run).await?
+						let ft =
+							FtIndex::new(&mut run, az, ikb, *order, sc, *hl, wt.as_ref()).await?;
+						Value::from(ft.statistics(&mut run).await?)
 					}
-					_ => {
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "Statistics on unique and non-unique indexes.",
-						})
+					Index::MTree(p) => {
+						let mt = MTree::new(&mut run, ikb, p).await?;
+						Value::from(mt.statistics(&mut run).await?)
+					}
+					Index::Idx | Index::Uniq => {
+						// Compute and save the cardinality statistics
+						let stats = IndexStatistics::compute(&mut run, opt, &ix).await?;
+						stats.set(&mut run, opt, &ix).await?;
+						Value::from(stats)
 					}
 				};
 				// Return the result object
-				Value::from(stats).ok()
+				stats.ok()
 			}
 		}
 	}
@@ -2,8 +2,9 @@ use crate::ctx::Context;
 use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::err::Error;
+use crate::idx::build::IndexBuild;
 use crate::sql::algorithm::{algorithm, Algorithm};
-use crate::sql::base::{base, base_or_scope, Base};
+use crate::sql::base::{base_or_root, base_or_scope, Base};
 use crate::sql::block::{block, Block};
 use crate::sql::changefeed::{changefeed, ChangeFeed};
 use crate::sql::comment::{mightbespace, shouldbespace};
@@ -14,17 +15,20 @@ use crate::sql::escape::quote_str;
 use crate::sql::filter::{filters, Filter};
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
+use crate::sql::fmt::Fmt;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::{Idiom, Idioms};
 use crate::sql::index::Index;
 use crate::sql::kind::{kind, Kind};
 use crate::sql::permission::{permissions, Permissions};
+use crate::sql::role::{roles, Role};
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::strand_raw;
 use crate::sql::tokenizer::{tokenizers, Tokenizer};
 use crate::sql::value::{value, values, Value, Values};
 use crate::sql::view::{view, View};
+use crate::sql::Error::Parser;
 use crate::sql::{ident, index};
 use argon2::password_hash::{PasswordHasher, SaltString};
 use argon2::Argon2;
@@ -36,7 +40,8 @@ use nom::character::complete::char;
 use nom::combinator::{map, opt};
 use nom::multi::many0;
 use nom::multi::separated_list0;
-use nom::sequence::tuple;
+use nom::sequence::{preceded, tuple};
+use nom::Err::Failure;
 use rand::distributions::Alphanumeric;
 use rand::rngs::OsRng;
 use rand::Rng;
@@ -131,6 +136,7 @@ impl DefineNamespaceStatement {
 		opt.needs(Level::Kv)?;
 		// Allowed to run?
 		opt.check(Level::Kv)?;
+		opt.check_role(Role::Owner)?;
 		// Process the statement
 		let key = crate::key::ns::new(&self.name);
 		// Clone transaction
@@ -180,6 +186,7 @@ impl DefineDatabaseStatement {
 		opt.needs(Level::Ns)?;
 		// Allowed to run?
 		opt.check(Level::Ns)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -188,6 +195,9 @@ impl DefineDatabaseStatement {
 		let key = crate::key::db::new(opt.ns(), &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.set(key, self).await?;
+		// Clear the cache
+		let key = crate::key::db::new(opt.ns(), &self.name);
+		run.clr(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -257,6 +267,7 @@ impl DefineFunctionStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -335,6 +346,7 @@ impl DefineAnalyzerStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -390,23 +402,65 @@ pub(crate) fn analyzer(i: &str) -> IResult<&str, DefineAnalyzerStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineLoginStatement {
 	pub name: Ident,
 	pub base: Base,
 	pub hash: String,
 	pub code: String,
+	pub roles: Vec<Role>,
 }
 
 impl DefineLoginStatement {
+	/// The most permissive role granted to this login
+	pub fn role(&self) -> Role {
+		self.roles.iter().max().copied().unwrap_or_default()
+	}
+	/// Create a login with the specified password, which is an owner login
+	/// unless it is a root login, as root logins have no roles
+	pub(crate) fn from_password(name: &str, base: Base, pass: &str) -> Self {
+		Self {
+			name: name.into(),
+			code: rand::thread_rng()
+				.sample_iter(&Alphanumeric)
+				.take(128)
+				.map(char::from)
+				.collect::<String>(),
+			hash: Argon2::default()
+				.hash_password(pass.as_ref(), &SaltString::generate(&mut OsRng))
+				.unwrap()
+				.to_string(),
+			roles: match base {
+				Base::Kv => vec![],
+				_ => vec![Role::Owner],
+			},
+			base,
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(&self, ctx: &Context<'_>, opt: &Options) -> Result<Value, Error> {
 		match self.base {
+			Base::Kv => {
+				// No need for NS/DB
+				opt.needs(Level::Kv)?;
+				// Allowed to run?
+				opt.check(Level::Kv)?;
+				// Clone transaction
+				let txn = ctx.try_clone_transaction()?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Process the statement
+				let key = crate::key::kl::new(&self.name);
+				run.set(key, self).await?;
+				// Ok all good
+				Ok(Value::None)
+			}
 			Base::Ns => {
 				// Selected DB?
 				opt.needs(Level::Ns)?;
 				// Allowed to run?
 				opt.check(Level::Kv)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -423,6 +477,7 @@ impl DefineLoginStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Ns)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -442,7 +497,17 @@ impl DefineLoginStatement {
 
 impl Display for DefineLoginStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE LOGIN {} ON {} PASSHASH {}", self.name, self.base, quote_str(&self.hash))
+		write!(
+			f,
+			"DEFINE LOGIN {} ON {} PASSHASH {}",
+			self.name,
+			self.base,
+			quote_str(&self.hash)
+		)?;
+		if !self.roles.is_empty() {
+			write!(f, " ROLES {}", Fmt::comma_separated(&self.roles))?;
+		}
+		Ok(())
 	}
 }
 
@@ -455,8 +520,13 @@ fn login(i: &str) -> IResult<&str, DefineLoginStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("ON")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, base) = base(i)?;
+	let (i, base) = base_or_root(i)?;
 	let (i, opts) = login_opts(i)?;
+	// Root logins always have full root permissions
+	let (i, roles) = match base {
+		Base::Kv => (i, Some(vec![])),
+		_ => opt(preceded(shouldbespace, roles))(i)?,
+	};
 	Ok((
 		i,
 		DefineLoginStatement {
@@ -474,6 +544,7 @@ fn login(i: &str) -> IResult<&str, DefineLoginStatement> {
 					.unwrap()
 					.to_string(),
 			},
+			roles: roles.unwrap_or_else(|| vec![Role::Owner]),
 		},
 	))
 }
@@ -525,6 +596,7 @@ impl DefineTokenStatement {
 				opt.needs(Level::Ns)?;
 				// Allowed to run?
 				opt.check(Level::Kv)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -541,6 +613,7 @@ impl DefineTokenStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Ns)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -558,6 +631,7 @@ impl DefineTokenStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Db)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -622,13 +696,14 @@ fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineScopeStatement {
 	pub name: Ident,
 	pub code: String,
 	pub session: Option<Duration>,
 	pub signup: Option<Value>,
 	pub signin: Option<Value>,
+	pub refresh: Option<Duration>,
 }
 
 impl DefineScopeStatement {
@@ -638,6 +713,7 @@ impl DefineScopeStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -664,6 +740,9 @@ impl Display for DefineScopeStatement {
 		if let Some(ref v) = self.signin {
 			write!(f, " SIGNIN {v}")?
 		}
+		if let Some(ref v) = self.refresh {
+			write!(f, " REFRESH {v}")?
+		}
 		Ok(())
 	}
 }
@@ -696,6 +775,10 @@ fn scope(i: &str) -> IResult<&str, DefineScopeStatement> {
 				DefineScopeOption::Signin(ref v) => Some(v.to_owned()),
 				_ => None,
 			}),
+			refresh: opts.iter().find_map(|x| match x {
+				DefineScopeOption::Refresh(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
 		},
 	))
 }
@@ -705,10 +788,11 @@ pub enum DefineScopeOption {
 	Session(Duration),
 	Signup(Value),
 	Signin(Value),
+	Refresh(Duration),
 }
 
 fn scope_opts(i: &str) -> IResult<&str, DefineScopeOption> {
-	alt((scope_session, scope_signup, scope_signin))(i)
+	alt((scope_session, scope_signup, scope_signin, scope_refresh))(i)
 }
 
 fn scope_session(i: &str) -> IResult<&str, DefineScopeOption> {
@@ -735,6 +819,14 @@ fn scope_signin(i: &str) -> IResult<&str, DefineScopeOption> {
 	Ok((i, DefineScopeOption::Signin(v)))
 }
 
+fn scope_refresh(i: &str) -> IResult<&str, DefineScopeOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("REFRESH")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = duration(i)?;
+	Ok((i, DefineScopeOption::Refresh(v)))
+}
+
 // --------------------------------------------------
 // --------------------------------------------------
 // --------------------------------------------------
@@ -752,6 +844,7 @@ impl DefineParamStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -812,6 +905,7 @@ impl DefineTableStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -821,6 +915,9 @@ impl DefineTableStatement {
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.set(key, self).await?;
+		// Clear the cache
+		let key = crate::key::tb::new(opt.ns(), opt.db(), &self.name);
+		run.clr(key).await?;
 		// Check if table is a view
 		if let Some(view) = &self.view {
 			// Remove the table data
@@ -1011,6 +1108,7 @@ impl DefineEventStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -1091,6 +1189,7 @@ impl DefineFieldStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -1232,12 +1331,13 @@ fn field_permissions(i: &str) -> IResult<&str, DefineFieldOption> {
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
 	pub index: Index,
+	pub concurrently: bool,
 }
 
 impl DefineIndexStatement {
@@ -1247,6 +1347,7 @@ impl DefineIndexStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -1264,6 +1365,20 @@ impl DefineIndexStatement {
 		let beg = crate::key::index::prefix(opt.ns(), opt.db(), &self.what, &self.name);
 		let end = crate::key::index::suffix(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delr(beg..end, u32::MAX).await?;
+		// Remove the index statistics
+		let key = crate::key::is::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Remove the vector index data
+		let beg = crate::key::vm::prefix(opt.ns(), opt.db(), &self.what, &self.name);
+		let end = crate::key::vm::suffix(opt.ns(), opt.db(), &self.what, &self.name);
+		run.delr(beg..end, u32::MAX).await?;
+		// Build the index data in the background
+		if self.concurrently {
+			IndexBuild::queue(&mut run, opt, self).await?;
+			return Ok(Value::None);
+		}
+		// Cancel any pending background build
+		IndexBuild::cancel(&mut run, opt, self).await?;
 		// Release the transaction
 		drop(run);
 		// Force queries to run
@@ -1291,6 +1406,9 @@ impl Display for DefineIndexStatement {
 		if Index::Idx != self.index {
 			write!(f, " {}", self.index)?;
 		}
+		if self.concurrently {
+			write!(f, " CONCURRENTLY")?;
+		}
 		Ok(())
 	}
 }
@@ -1312,6 +1430,21 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 	let (i, cols) = idiom::locals(i)?;
 	let (i, _) = mightbespace(i)?;
 	let (i, index) = index::index(i)?;
+	// There must be one weight for each column
+	if let Index::Search {
+		wt: Some(wt),
+		..
+	} = &index
+	{
+		if wt.0.len() != cols.len() {
+			return Err(Failure(Parser(i)));
+		}
+	}
+	// A vector index can only be defined on a single column
+	if matches!(index, Index::MTree(_)) && cols.len() != 1 {
+		return Err(Failure(Parser(i)));
+	}
+	let (i, concurrently) = opt(tuple((mightbespace, tag_no_case("CONCURRENTLY"))))(i)?;
 	Ok((
 		i,
 		DefineIndexStatement {
@@ -1319,6 +1452,7 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 			what,
 			cols,
 			index,
+			concurrently: concurrently.is_some(),
 		},
 	))
 }
@@ -1326,9 +1460,100 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::sql::index::{Distance, MTreeParams, Weights};
 	use crate::sql::scoring::Scoring;
 	use crate::sql::Part;
 
+	#[test]
+	fn define_analyzer_with_tokenizers() {
+		let sql = r"DEFINE ANALYZER test TOKENIZERS words,cjk,regex('[A-Z]{2,}-\\d+'),blank";
+		let res = analyzer(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			out.to_string(),
+			r"DEFINE ANALYZER test TOKENIZERS WORDS,CJK,REGEX('[A-Z]{2,}-\\d+'),BLANK"
+		);
+		let sql = out.to_string();
+		assert_eq!(analyzer(&sql).unwrap().1, out);
+		// Invalid regular expressions are rejected
+		assert!(analyzer("DEFINE ANALYZER test TOKENIZERS regex('[a-z')").is_err());
+	}
+
+	#[test]
+	fn define_analyzer_with_filters() {
+		let sql = "DEFINE ANALYZER test TOKENIZERS blank FILTERS lowercase,stopwords(english),stopwords(['a', \"it's\"]),synonyms({ car: ['auto', 'automobile'], \"big car\": 'truck' }),mapper({ ran: 'run' })";
+		let res = analyzer(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			out.to_string(),
+			"DEFINE ANALYZER test TOKENIZERS BLANK FILTERS LOWERCASE,STOPWORDS(ENGLISH),STOPWORDS(['a', \"it's\"]),SYNONYMS({ \"big car\": ['truck'], car: ['auto', 'automobile'] }),MAPPER({ ran: 'run' })"
+		);
+		let sql = out.to_string();
+		assert_eq!(analyzer(&sql).unwrap().1, out);
+	}
+
+	#[test]
+	fn define_login_with_roles() {
+		let sql = "DEFINE LOGIN test ON DATABASE PASSHASH 'hash' ROLES VIEWER, EDITOR";
+		let res = login(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out.roles, vec![Role::Viewer, Role::Editor]);
+		assert_eq!(out.role(), Role::Editor);
+		assert_eq!(
+			out.to_string(),
+			"DEFINE LOGIN test ON DATABASE PASSHASH 'hash' ROLES VIEWER, EDITOR"
+		);
+		// A login is an owner by default
+		let sql = "DEFINE LOGIN test ON NAMESPACE PASSHASH 'hash'";
+		let out = login(sql).unwrap().1;
+		assert_eq!(out.role(), Role::Owner);
+		assert_eq!(out.to_string(), "DEFINE LOGIN test ON NAMESPACE PASSHASH 'hash' ROLES OWNER");
+	}
+
+	#[test]
+	fn define_login_on_root() {
+		let sql = "DEFINE LOGIN test ON ROOT PASSHASH 'hash'";
+		let res = login(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out.base, Base::Kv);
+		assert_eq!(out.to_string(), "DEFINE LOGIN test ON ROOT PASSHASH 'hash'");
+		// Root logins always have full root permissions, so they have no roles
+		let sql = "DEFINE LOGIN test ON ROOT PASSHASH 'hash' ROLES VIEWER";
+		assert!(crate::sql::parse(sql).is_err());
+		// Logins can not be defined on a scope
+		let sql = "DEFINE LOGIN test ON SCOPE test PASSHASH 'hash'";
+		assert!(login(sql).is_err());
+	}
+
+	#[test]
+	fn define_scope_with_refresh() {
+		let sql = "DEFINE SCOPE test SESSION 1h REFRESH 7d";
+		let res = scope(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out.session, Some(Duration::from_hours(1)));
+		assert_eq!(out.refresh, Some(Duration::from_days(7)));
+		assert_eq!(out.to_string(), "DEFINE SCOPE test SESSION 1h REFRESH 1w");
+	}
+
+	#[test]
+	fn define_token_jwks() {
+		let sql = "DEFINE TOKEN test ON DATABASE TYPE JWKS VALUE 'https://example.com/.well-known/jwks.json'";
+		let res = token(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out.kind, Algorithm::Jwks);
+		assert_eq!(out.code, "https://example.com/.well-known/jwks.json");
+		assert_eq!(
+			out.to_string(),
+			"DEFINE TOKEN test ON DATABASE TYPE JWKS VALUE 'https://example.com/.well-known/jwks.json'"
+		);
+	}
+
 	#[test]
 	fn check_define_serialize() {
 		let stm = DefineStatement::Namespace(DefineNamespaceStatement {
@@ -1348,6 +1573,7 @@ mod tests {
 				what: Ident("my_table".to_string()),
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Idx,
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col");
@@ -1364,6 +1590,7 @@ mod tests {
 				what: Ident("my_table".to_string()),
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Uniq,
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE");
@@ -1386,8 +1613,10 @@ mod tests {
 						k1: 1.2,
 						b: 0.75,
 					},
-					order: 1000
+					order: 1000,
+					wt: None,
 				},
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) ORDER 1000 HIGHLIGHTS");
@@ -1408,8 +1637,10 @@ mod tests {
 					az: Ident("my_analyzer".to_string()),
 					hl: false,
 					sc: Scoring::Vs,
-					order: 100
+					order: 100,
+					wt: None,
 				},
+				concurrently: false,
 			}
 		);
 		assert_eq!(
@@ -1418,6 +1649,108 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn check_create_search_index_with_weights() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS title, body SEARCH ANALYZER my_analyzer BM25 WEIGHTS(2, 0.5) HIGHLIGHTS";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![
+					Idiom(vec![Part::Field(Ident("title".to_string()))]),
+					Idiom(vec![Part::Field(Ident("body".to_string()))]),
+				]),
+				index: Index::Search {
+					az: Ident("my_analyzer".to_string()),
+					hl: true,
+					sc: Scoring::bm25(),
+					order: 100,
+					wt: Some(Weights(vec![2.0, 0.5])),
+				},
+				concurrently: false,
+			}
+		);
+		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS title, body SEARCH ANALYZER my_analyzer BM25(1.2,0.75) WEIGHTS(2,0.5) ORDER 100 HIGHLIGHTS");
+		// There must be one weight per column
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS title, body SEARCH ANALYZER my_analyzer BM25 WEIGHTS(2)";
+		assert!(index(sql).is_err());
+		// Weights can't be negative
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS title, body SEARCH ANALYZER my_analyzer BM25 WEIGHTS(2, -1)";
+		assert!(index(sql).is_err());
+	}
+
+	#[test]
+	fn check_create_index_concurrently() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS my_col CONCURRENTLY";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::Idx,
+				concurrently: true,
+			}
+		);
+		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col CONCURRENTLY");
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS my_col UNIQUE CONCURRENTLY";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.index, Index::Uniq);
+		assert!(idx.concurrently);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE CONCURRENTLY"
+		);
+	}
+
+	#[test]
+	fn check_create_mtree_index() {
+		let sql =
+			"DEFINE INDEX my_index ON TABLE my_table COLUMNS my_col MTREE DIMENSION 4 DIST COSINE";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::MTree(MTreeParams {
+					dimension: 4,
+					distance: Distance::Cosine,
+					capacity: 40,
+				}),
+				concurrently: false,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col MTREE DIMENSION 4 DIST COSINE CAPACITY 40"
+		);
+	}
+
+	#[test]
+	fn check_create_mtree_index_with_default_distance() {
+		let sql = "DEFINE INDEX my_index ON my_table FIELDS my_col MTREE DIMENSION 2 CAPACITY 10";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.index,
+			Index::MTree(MTreeParams {
+				dimension: 2,
+				distance: Distance::Euclidean,
+				capacity: 10,
+			})
+		);
+	}
+
+	#[test]
+	fn check_create_mtree_index_with_several_columns() {
+		let sql = "DEFINE INDEX my_index ON my_table FIELDS a, b MTREE DIMENSION 2";
+		assert!(index(sql).is_err());
+	}
+
 	#[test]
 	fn define_database_with_changefeed() {
 		let sql = "DEFINE DATABASE mydatabase CHANGEFEED 1h";
@@ -1443,4 +1776,16 @@ mod tests {
 		let deserializled = DefineTableStatement::try_from(&serialized).unwrap();
 		assert_eq!(out, deserializled);
 	}
+
+	#[test]
+	fn define_field_stored_before_knn_operator() {
+		// DEFINE FIELD age ON person TYPE int ASSERT $value < 120
+		let stored: Vec<u8> = vec![
+			1, 3, 3, 97, 103, 101, 6, 112, 101, 114, 115, 111, 110, 0, 1, 7, 0, 1, 26, 1, 13, 5,
+			118, 97, 108, 117, 101, 24, 3, 0, 240, 1, 1, 1, 1,
+		];
+		let out = DefineFieldStatement::from(&stored);
+		assert_eq!(out.to_string(), "DEFINE FIELD age ON person TYPE int ASSERT $value < 120");
+		assert_eq!(out.to_vec(), stored);
+	}
 }
@@ -2,10 +2,12 @@ use crate::ctx::Context;
 use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::err::Error;
+use crate::idx::build::IndexBuild;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::object::Object;
+use crate::sql::role::Role;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
@@ -38,7 +40,13 @@ impl InfoStatement {
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
 				let mut run = txn.lock().await;
-				// Process the statement
+				// Process the logins
+				let mut tmp = Object::default();
+				for v in run.all_kl().await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("logins".to_owned(), tmp.into());
+				// Process the namespaces
 				let mut tmp = Object::default();
 				for v in run.all_ns().await?.iter() {
 					tmp.insert(v.name.to_string(), v.to_string().into());
@@ -52,6 +60,8 @@ impl InfoStatement {
 				opt.needs(Level::Ns)?;
 				// Allowed to run?
 				opt.check(Level::Ns)?;
+				// Lists the secrets of the tokens and logins
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -84,6 +94,8 @@ impl InfoStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Db)?;
+				// Lists the secrets of the tokens and logins
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -140,6 +152,8 @@ impl InfoStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Db)?;
+				// Lists the secrets of the tokens
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -190,6 +204,12 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("indexes".to_owned(), tmp.into());
+				// Process the index builds
+				let mut tmp = Object::default();
+				for (ix, v) in IndexBuild::all(&mut run, opt.ns(), opt.db(), tb).await? {
+					tmp.insert(ix, v.into());
+				}
+				res.insert("builds".to_owned(), tmp.into());
 				// Ok all good
 				Value::from(res).ok()
 			}
@@ -0,0 +1,252 @@
+//! The layouts of the definitions which were stored by earlier versions.
+//!
+//! Definitions are stored with a positional encoding, so a definition which
+//! was stored before one of its fields was added can not be read with its
+//! current layout. These definitions are read with their current layout
+//! first, and otherwise with each of their earlier layouts in turn, which
+//! are then converted to the current layout.
+use crate::sql::base::Base;
+use crate::sql::duration::Duration;
+use crate::sql::ident::Ident;
+use crate::sql::idiom::Idioms;
+use crate::sql::index::{Index, MTreeParams};
+use crate::sql::role::Role;
+use crate::sql::scoring::Scoring;
+use crate::sql::statements::{DefineIndexStatement, DefineLoginStatement, DefineScopeStatement};
+use crate::sql::value::Value;
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+/// Implements the conversions from and to the stored bytes, like the `Store`
+/// derive macro, but falling back on the earlier layouts of the definition.
+macro_rules! store {
+	($name:ty, $($legacy:ty),+) => {
+		impl $name {
+			pub fn to_vec(&self) -> Vec<u8> {
+				self.into()
+			}
+		}
+
+		impl From<Vec<u8>> for $name {
+			fn from(v: Vec<u8>) -> Self {
+				Self::from(&v)
+			}
+		}
+
+		impl From<$name> for Vec<u8> {
+			fn from(v: $name) -> Vec<u8> {
+				Self::from(&v)
+			}
+		}
+
+		impl From<&Vec<u8>> for $name {
+			fn from(v: &Vec<u8>) -> Self {
+				decode::<Self>(v)
+					$(.or_else(|_| decode::<$legacy>(v).map(Self::from)))+
+					.unwrap()
+			}
+		}
+
+		impl From<&$name> for Vec<u8> {
+			fn from(v: &$name) -> Vec<u8> {
+				encode(v)
+			}
+		}
+	};
+}
+
+fn decode<'a, T: Deserialize<'a>>(v: &'a [u8]) -> bincode::Result<T> {
+	bincode::options()
+		.with_no_limit()
+		.with_little_endian()
+		.with_varint_encoding()
+		.reject_trailing_bytes()
+		.deserialize(v)
+}
+
+fn encode<T: Serialize>(v: &T) -> Vec<u8> {
+	bincode::options()
+		.with_no_limit()
+		.with_little_endian()
+		.with_varint_encoding()
+		.reject_trailing_bytes()
+		.serialize(v)
+		.unwrap_or_default()
+}
+
+store!(DefineIndexStatement, DefineIndexStatementV1);
+
+/// An index defined before the weights of search indexes, and before
+/// indexes could be defined concurrently
+#[derive(Deserialize)]
+struct DefineIndexStatementV1 {
+	name: Ident,
+	what: Ident,
+	cols: Idioms,
+	index: IndexV1,
+}
+
+#[derive(Deserialize)]
+enum IndexV1 {
+	Idx,
+	Uniq,
+	Search {
+		az: Ident,
+		hl: bool,
+		sc: Scoring,
+		order: u32,
+	},
+	MTree(MTreeParams),
+}
+
+impl From<DefineIndexStatementV1> for DefineIndexStatement {
+	fn from(v: DefineIndexStatementV1) -> Self {
+		Self {
+			name: v.name,
+			what: v.what,
+			cols: v.cols,
+			index: match v.index {
+				IndexV1::Idx => Index::Idx,
+				IndexV1::Uniq => Index::Uniq,
+				IndexV1::Search {
+					az,
+					hl,
+					sc,
+					order,
+				} => Index::Search {
+					az,
+					hl,
+					sc,
+					order,
+					wt: None,
+				},
+				IndexV1::MTree(p) => Index::MTree(p),
+			},
+			// The index was built when it was defined
+			concurrently: false,
+		}
+	}
+}
+
+store!(DefineLoginStatement, DefineLoginStatementV1);
+
+/// A login defined before logins were granted roles
+#[derive(Deserialize)]
+struct DefineLoginStatementV1 {
+	name: Ident,
+	base: Base,
+	hash: String,
+	code: String,
+}
+
+impl From<DefineLoginStatementV1> for DefineLoginStatement {
+	fn from(v: DefineLoginStatementV1) -> Self {
+		Self {
+			name: v.name,
+			base: v.base,
+			hash: v.hash,
+			code: v.code,
+			// Every login had full access to its namespace or database
+			roles: vec![Role::Owner],
+		}
+	}
+}
+
+store!(DefineScopeStatement, DefineScopeStatementV1);
+
+/// A scope defined before scope tokens could be refreshed
+#[derive(Deserialize)]
+struct DefineScopeStatementV1 {
+	name: Ident,
+	code: String,
+	session: Option<Duration>,
+	signup: Option<Value>,
+	signin: Option<Value>,
+}
+
+impl From<DefineScopeStatementV1> for DefineScopeStatement {
+	fn from(v: DefineScopeStatementV1) -> Self {
+		Self {
+			name: v.name,
+			code: v.code,
+			session: v.session,
+			signup: v.signup,
+			signin: v.signin,
+			// The tokens of the scope can not be refreshed
+			refresh: None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::sql::role::Role;
+	use crate::sql::statements::{
+		DefineIndexStatement, DefineLoginStatement, DefineScopeStatement,
+	};
+
+	#[test]
+	fn define_index_stored_by_earlier_versions() {
+		// DEFINE INDEX ix ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS
+		let stored: Vec<u8> = vec![
+			2, 105, 120, 4, 98, 108, 111, 103, 1, 1, 3, 5, 116, 105, 116, 108, 101, 2, 6, 115, 105,
+			109, 112, 108, 101, 1, 0, 154, 153, 153, 63, 0, 0, 64, 63, 100,
+		];
+		let out = DefineIndexStatement::from(&stored);
+		assert_eq!(
+			out.to_string(),
+			"DEFINE INDEX ix ON blog FIELDS title SEARCH ANALYZER simple BM25(1.2,0.75) ORDER 100 HIGHLIGHTS"
+		);
+		// DEFINE INDEX ix ON user FIELDS email UNIQUE
+		let stored: Vec<u8> =
+			vec![2, 105, 120, 4, 117, 115, 101, 114, 1, 1, 3, 5, 101, 109, 97, 105, 108, 1];
+		let out = DefineIndexStatement::from(&stored);
+		assert_eq!(out.to_string(), "DEFINE INDEX ix ON user FIELDS email UNIQUE");
+		// The definition is stored with its current layout again
+		assert_eq!(DefineIndexStatement::from(&out.to_vec()), out);
+	}
+	#[test]
+	fn define_login_stored_by_earlier_versions() {
+		// DEFINE LOGIN test ON NAMESPACE PASSHASH '$argon2id$v=19$m=4096,t=3,p=1$c2FsdA$aGFzaA'
+		let stored: Vec<u8> = vec![
+			4, 116, 101, 115, 116, 1, 43, 36, 97, 114, 103, 111, 110, 50, 105, 100, 36, 118, 61,
+			49, 57, 36, 109, 61, 52, 48, 57, 54, 44, 116, 61, 51, 44, 112, 61, 49, 36, 99, 50, 70,
+			115, 100, 65, 36, 97, 71, 70, 122, 97, 65, 128, 100, 99, 115, 98, 69, 118, 81, 55, 118,
+			110, 53, 50, 66, 83, 111, 109, 71, 83, 90, 122, 53, 76, 56, 53, 67, 122, 110, 118, 89,
+			69, 76, 79, 117, 81, 49, 120, 57, 110, 56, 53, 83, 79, 78, 83, 122, 74, 88, 116, 53,
+			75, 66, 112, 52, 113, 119, 78, 111, 105, 67, 89, 102, 111, 116, 107, 117, 88, 116, 81,
+			119, 68, 111, 113, 109, 74, 77, 49, 51, 67, 99, 102, 84, 56, 57, 116, 75, 54, 106, 99,
+			52, 79, 50, 101, 97, 116, 88, 78, 48, 109, 68, 82, 121, 112, 108, 83, 116, 68, 122,
+			114, 103, 115, 116, 83, 97, 118, 85, 74, 115, 98, 102, 66, 73, 102, 57, 88, 82, 78, 81,
+			73,
+		];
+		let out = DefineLoginStatement::from(&stored);
+		assert_eq!(
+			out.to_string(),
+			"DEFINE LOGIN test ON NAMESPACE PASSHASH '$argon2id$v=19$m=4096,t=3,p=1$c2FsdA$aGFzaA' ROLES OWNER"
+		);
+		assert_eq!(out.role(), Role::Owner);
+		// The definition is stored with its current layout again
+		assert_eq!(DefineLoginStatement::from(&out.to_vec()), out);
+	}
+	#[test]
+	fn define_scope_stored_by_earlier_versions() {
+		// DEFINE SCOPE account SESSION 1d SIGNUP (CREATE user SET email = $email) SIGNIN (SELECT * FROM user WHERE email = $email)
+		let stored: Vec<u8> = vec![
+			7, 97, 99, 99, 111, 117, 110, 116, 3, 97, 98, 99, 1, 252, 128, 81, 1, 0, 0, 1, 25, 4,
+			1, 15, 4, 117, 115, 101, 114, 1, 1, 1, 1, 3, 5, 101, 109, 97, 105, 108, 14, 13, 5, 101,
+			109, 97, 105, 108, 0, 0, 0, 1, 25, 3, 1, 0, 0, 1, 15, 4, 117, 115, 101, 114, 1, 26, 1,
+			14, 1, 3, 5, 101, 109, 97, 105, 108, 14, 13, 5, 101, 109, 97, 105, 108, 0, 0, 0, 0, 0,
+			0, 0, 0, 0, 0,
+		];
+		let out = DefineScopeStatement::from(&stored);
+		assert_eq!(
+			out.to_string(),
+			"DEFINE SCOPE account SESSION 1d SIGNUP (CREATE user SET email = $email) SIGNIN (SELECT * FROM user WHERE email = $email)"
+		);
+		assert_eq!(out.code, "abc");
+		assert_eq!(out.refresh, None);
+		// The definition is stored with its current layout again
+		assert_eq!(DefineScopeStatement::from(&out.to_vec()), out);
+	}
+}
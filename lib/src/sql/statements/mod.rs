@@ -9,9 +9,11 @@ pub(crate) mod ifelse;
 pub(crate) mod info;
 pub(crate) mod insert;
 pub(crate) mod kill;
+mod legacy;
 pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
+pub(crate) mod rebuild;
 pub(crate) mod relate;
 pub(crate) mod remove;
 pub(crate) mod select;
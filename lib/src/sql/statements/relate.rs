@@ -12,6 +12,7 @@ use crate::sql::data::{data, Data};
 use crate::sql::error::IResult;
 use crate::sql::output::{output, Output};
 use crate::sql::param::param;
+use crate::sql::role::Role;
 use crate::sql::subquery::subquery;
 use crate::sql::table::table;
 use crate::sql::thing::thing;
@@ -60,6 +61,7 @@ impl RelateStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::No)?;
+		opt.check_role(Role::Editor)?;
 		// Create a new iterator
 		let mut i = Iterator::new();
 		// Ensure futures are stored
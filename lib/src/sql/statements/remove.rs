@@ -2,13 +2,14 @@ use crate::ctx::Context;
 use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::err::Error;
-use crate::sql::base::{base, base_or_scope, Base};
+use crate::sql::base::{base_or_root, base_or_scope, Base};
 use crate::sql::comment::{mightbespace, shouldbespace};
 use crate::sql::error::IResult;
 use crate::sql::ident;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::Idiom;
+use crate::sql::role::Role;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
@@ -108,6 +109,7 @@ impl RemoveNamespaceStatement {
 		opt.needs(Level::Kv)?;
 		// Allowed to run?
 		opt.check(Level::Kv)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -159,6 +161,7 @@ impl RemoveDatabaseStatement {
 		opt.needs(Level::Ns)?;
 		// Allowed to run?
 		opt.check(Level::Ns)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -210,6 +213,7 @@ impl RemoveFunctionStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -265,6 +269,7 @@ impl RemoveAnalyzerStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -312,11 +317,27 @@ impl RemoveLoginStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(&self, ctx: &Context<'_>, opt: &Options) -> Result<Value, Error> {
 		match self.base {
+			Base::Kv => {
+				// No need for NS/DB
+				opt.needs(Level::Kv)?;
+				// Allowed to run?
+				opt.check(Level::Kv)?;
+				// Clone transaction
+				let txn = ctx.try_clone_transaction()?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Delete the definition
+				let key = crate::key::kl::new(&self.name);
+				run.del(key).await?;
+				// Ok all good
+				Ok(Value::None)
+			}
 			Base::Ns => {
 				// Selected NS?
 				opt.needs(Level::Ns)?;
 				// Allowed to run?
 				opt.check(Level::Kv)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -332,6 +353,7 @@ impl RemoveLoginStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Ns)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -362,7 +384,7 @@ fn login(i: &str) -> IResult<&str, RemoveLoginStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("ON")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, base) = base(i)?;
+	let (i, base) = base_or_root(i)?;
 	Ok((
 		i,
 		RemoveLoginStatement {
@@ -391,6 +413,7 @@ impl RemoveTokenStatement {
 				opt.needs(Level::Ns)?;
 				// Allowed to run?
 				opt.check(Level::Kv)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -406,6 +429,7 @@ impl RemoveTokenStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Ns)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -421,6 +445,7 @@ impl RemoveTokenStatement {
 				opt.needs(Level::Db)?;
 				// Allowed to run?
 				opt.check(Level::Db)?;
+				opt.check_role(Role::Owner)?;
 				// Clone transaction
 				let txn = ctx.try_clone_transaction()?;
 				// Claim transaction
@@ -477,6 +502,7 @@ impl RemoveScopeStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -528,6 +554,7 @@ impl RemoveParamStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -577,6 +604,7 @@ impl RemoveTableStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -629,6 +657,7 @@ impl RemoveEventStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -687,6 +716,7 @@ impl RemoveFieldStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -746,6 +776,7 @@ impl RemoveIndexStatement {
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		opt.check_role(Role::Owner)?;
 		// Clone transaction
 		let txn = ctx.try_clone_transaction()?;
 		// Claim transaction
@@ -760,6 +791,16 @@ impl RemoveIndexStatement {
 		let beg = crate::key::index::prefix(opt.ns(), opt.db(), &self.what, &self.name);
 		let end = crate::key::index::suffix(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delr(beg..end, u32::MAX).await?;
+		// Remove the index statistics
+		let key = crate::key::is::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Remove the vector index data
+		let beg = crate::key::vm::prefix(opt.ns(), opt.db(), &self.what, &self.name);
+		let end = crate::key::vm::suffix(opt.ns(), opt.db(), &self.what, &self.name);
+		run.delr(beg..end, u32::MAX).await?;
+		// Cancel any pending background build
+		let key = crate::key::ib::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -82,15 +82,29 @@ impl SelectStatement {
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false);
 
+		// Records can only be read in the order of an index
+		// when the first records of a single table are needed
+		let (order, limit) = match (&self.limit, &self.group, &self.split, self.what.len()) {
+			(Some(l), None, None, 1) if self.order.is_some() => {
+				let s = match &self.start {
+					Some(s) => s.process(ctx, opt).await?,
+					None => 0,
+				};
+				(self.order.as_ref(), Some(l.process(ctx, opt).await? + s))
+			}
+			_ => (None, None),
+		};
 		// Get a query planner
-		let mut planner = QueryPlanner::new(opt, &self.cond);
+		let mut planner = QueryPlanner::new(opt, &self.cond, order, &self.expr, limit);
 		// Loop over the select targets
 		for w in self.what.0.iter() {
 			let v = w.compute(ctx, opt).await?;
 			match v {
-				Value::Table(t) => {
-					i.ingest(planner.get_iterable(ctx, t).await?);
-				}
+				Value::Table(t) => match self.version {
+					// The indexes only contain the current records
+					Some(_) => i.ingest(Iterable::Table(t)),
+					None => i.ingest(planner.get_iterable(ctx, t).await?),
+				},
 				Value::Thing(v) => i.ingest(Iterable::Thing(v)),
 				Value::Range(v) => i.ingest(Iterable::Range(*v)),
 				Value::Edges(v) => i.ingest(Iterable::Edges(*v)),
@@ -1,4 +1,5 @@
 use crate::ctx::Context;
+use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
@@ -27,10 +28,20 @@ pub struct ShowStatement {
 
 impl ShowStatement {
 	/// Process this type returning a computed simple Value
-	pub(crate) async fn compute(&self, _ctx: &Context<'_>, _opt: &Options) -> Result<Value, Error> {
-		Err(Error::FeatureNotYetImplemented {
-			feature: "change feed",
-		})
+	pub(crate) async fn compute(&self, ctx: &Context<'_>, opt: &Options) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Clone transaction
+		let txn = ctx.try_clone_transaction()?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Process the show query
+		let tb = self.table.as_ref().map(|v| v.0.as_str());
+		let res = crate::cf::read(&mut run, opt.ns(), opt.db(), tb, self.since, self.limit).await?;
+		// Return the changes
+		Ok(Value::from(res.into_iter().map(|v| v.into_value()).collect::<Vec<_>>()))
 	}
 }
 
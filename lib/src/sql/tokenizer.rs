@@ -1,6 +1,8 @@
 use crate::sql::comment::shouldbespace;
-use crate::sql::common::commas;
+use crate::sql::common::{closeparentheses, commas, openparentheses};
 use crate::sql::error::IResult;
+use crate::sql::escape::quote_str;
+use crate::sql::strand::strand_raw;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::map;
@@ -15,25 +17,49 @@ pub enum Tokenizer {
 	Camel,
 	Class,
 	Punct,
+	/// Unicode (UAX#29) word boundaries
+	Words,
+	/// The matches of a regular expression
+	Regex(String),
+	/// Bigrams of Chinese, Japanese and Korean characters
+	Cjk,
 }
 
 impl Display for Tokenizer {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		f.write_str(match self {
-			Self::Blank => "BLANK",
-			Self::Camel => "CAMEL",
-			Self::Class => "CLASS",
-			Self::Punct => "PUNCT",
-		})
+		match self {
+			Self::Blank => f.write_str("BLANK"),
+			Self::Camel => f.write_str("CAMEL"),
+			Self::Class => f.write_str("CLASS"),
+			Self::Punct => f.write_str("PUNCT"),
+			Self::Words => f.write_str("WORDS"),
+			Self::Regex(r) => write!(f, "REGEX({})", quote_str(r)),
+			Self::Cjk => f.write_str("CJK"),
+		}
 	}
 }
 
+fn regex(i: &str) -> IResult<&str, Tokenizer> {
+	let (i, _) = tag_no_case("REGEX")(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, r) = strand_raw(i)?;
+	// Reject the expressions which won't compile when the analyzer is used
+	if regex::Regex::new(&r).is_err() {
+		return Err(nom::Err::Failure(crate::sql::Error::Parser(i)));
+	}
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, Tokenizer::Regex(r)))
+}
+
 fn tokenizer(i: &str) -> IResult<&str, Tokenizer> {
 	let (i, t) = alt((
 		map(tag_no_case("BLANK"), |_| Tokenizer::Blank),
 		map(tag_no_case("CAMEL"), |_| Tokenizer::Camel),
 		map(tag_no_case("CLASS"), |_| Tokenizer::Class),
 		map(tag_no_case("PUNCT"), |_| Tokenizer::Punct),
+		map(tag_no_case("WORDS"), |_| Tokenizer::Words),
+		regex,
+		map(tag_no_case("CJK"), |_| Tokenizer::Cjk),
 	))(i)?;
 	Ok((i, t))
 }
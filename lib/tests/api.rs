@@ -138,8 +138,8 @@ mod api_integration {
 			let db = Surreal::new::<Mem>(()).await.unwrap();
 			db.use_ns("namespace").use_db("database").await.unwrap();
 			let Some(record): Option<RecordId> = db.create(("item", "foo")).await.unwrap() else {
-                panic!("record not found");
-            };
+				panic!("record not found");
+			};
 			assert_eq!(record.id.to_string(), "item:foo");
 		}
 
@@ -147,14 +147,16 @@ mod api_integration {
 		async fn cant_sign_into_default_root_account() {
 			init_logger();
 			let db = Surreal::new::<Mem>(()).await.unwrap();
-			let Error::Db(DbError::InvalidAuth) = db.signin(Root {
-				username: ROOT_USER,
-				password: ROOT_PASS,
-			})
-			.await
-			.unwrap_err() else {
-                panic!("unexpected successful login");
-            };
+			let Error::Db(DbError::InvalidAuth) = db
+				.signin(Root {
+					username: ROOT_USER,
+					password: ROOT_PASS,
+				})
+				.await
+				.unwrap_err()
+			else {
+				panic!("unexpected successful login");
+			};
 		}
 
 		#[tokio::test]
@@ -167,9 +169,11 @@ mod api_integration {
 			.await
 			.unwrap();
 			db.use_ns("namespace").use_db("database").await.unwrap();
-			let Error::Db(DbError::QueryPermissions) = db.create(Resource::from("item:foo")).await.unwrap_err() else {
-                panic!("record not found");
-            };
+			let Error::Db(DbError::QueryPermissions) =
+				db.create(Resource::from("item:foo")).await.unwrap_err()
+			else {
+				panic!("record not found");
+			};
 		}
 
 		include!("api/mod.rs");
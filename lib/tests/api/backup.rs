@@ -22,3 +22,32 @@ async fn export_import() {
 	db.import(&file).await.unwrap();
 	remove_file(file).await.unwrap();
 }
+
+#[tokio::test]
+async fn export_db_viewer() {
+	let db = new_db().await;
+	let database = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&database).await.unwrap();
+	let user = Ulid::new().to_string();
+	let pass = "password123";
+	let sql = format!("DEFINE LOGIN {user} ON DATABASE PASSWORD '{pass}' ROLES VIEWER");
+	let response = db.query(sql).await.unwrap();
+	response.check().unwrap();
+	db.signin(Database {
+		namespace: NS,
+		database: &database,
+		username: &user,
+		password: pass,
+	})
+	.await
+	.unwrap();
+	// A viewer can't export the secrets of the tokens and logins
+	let file = format!("{database}.sql");
+	match db.export(&file).await.unwrap_err() {
+		// Local engines return this error
+		Error::Db(DbError::QueryPermissions) => {}
+		// Remote engines return this error
+		Error::Api(ApiError::Http(error)) if error.contains("403 Forbidden") => {}
+		error => panic!("{:?}", error),
+	}
+}
@@ -104,6 +104,70 @@ async fn signin_db() {
 	.unwrap();
 }
 
+#[tokio::test]
+async fn signin_db_viewer() {
+	let db = new_db().await;
+	let database = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&database).await.unwrap();
+	let user = Ulid::new().to_string();
+	let pass = "password123";
+	let sql = format!(
+		"
+        DEFINE LOGIN {user} ON DATABASE PASSWORD '{pass}' ROLES VIEWER;
+        CREATE user:john;
+    "
+	);
+	let response = db.query(sql).await.unwrap();
+	response.check().unwrap();
+	db.signin(Database {
+		namespace: NS,
+		database: &database,
+		username: &user,
+		password: pass,
+	})
+	.await
+	.unwrap();
+	// A viewer can read the data
+	let mut response = db.query("SELECT * FROM user").await.unwrap();
+	let users: Vec<RecordId> = response.take(0).unwrap();
+	assert_eq!(users.len(), 1);
+	// But it can't change the data
+	let error = db.query("CREATE user:jane").await.unwrap().check().unwrap_err();
+	assert!(error.to_string().contains("You don't have permission to perform this query type"));
+	// Nor the definitions
+	let error = db.query("REMOVE TABLE user").await.unwrap().check().unwrap_err();
+	assert!(error.to_string().contains("You don't have permission to perform this query type"));
+	// Nor list the secrets of the tokens and logins
+	let error = db.query("INFO FOR DB").await.unwrap().check().unwrap_err();
+	assert!(error.to_string().contains("You don't have permission to perform this query type"));
+}
+
+#[tokio::test]
+async fn signin_db_editor() {
+	let db = new_db().await;
+	let database = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&database).await.unwrap();
+	let user = Ulid::new().to_string();
+	let pass = "password123";
+	let sql = format!("DEFINE LOGIN {user} ON DATABASE PASSWORD '{pass}' ROLES EDITOR");
+	let response = db.query(sql).await.unwrap();
+	response.check().unwrap();
+	db.signin(Database {
+		namespace: NS,
+		database: &database,
+		username: &user,
+		password: pass,
+	})
+	.await
+	.unwrap();
+	// An editor can change the data
+	let response = db.query("CREATE user:john; UPDATE user:john SET name = 'John'").await.unwrap();
+	response.check().unwrap();
+	// But it can't change the definitions
+	let error = db.query("DEFINE TABLE user SCHEMAFULL").await.unwrap().check().unwrap_err();
+	assert!(error.to_string().contains("You don't have permission to perform this query type"));
+}
+
 #[tokio::test]
 async fn signin_scope() {
 	let db = new_db().await;
@@ -0,0 +1,361 @@
+mod parse;
+use chrono::Utc;
+use parse::Parse;
+use std::time::Duration;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Datetime;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn table_change_feeds() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:test CONTENT { name: 'Name: Tobie' };
+		UPDATE person:test CONTENT { name: 'Name: Jaime' };
+		DELETE person:test;
+		CREATE other:test CONTENT { name: 'Name: Other' };
+		SHOW CHANGES FOR TABLE person SINCE 0 LIMIT 10;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	// DEFINE TABLE
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// CREATE
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test, name: 'Name: Tobie' }]");
+	assert_eq!(tmp, val);
+	// UPDATE
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test, name: 'Name: Jaime' }]");
+	assert_eq!(tmp, val);
+	// DELETE
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// CREATE on a table without a change feed
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// SHOW CHANGES
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 1,
+				changes: [
+					{
+						update: {
+							id: person:test,
+							name: 'Name: Tobie'
+						}
+					}
+				]
+			},
+			{
+				versionstamp: 2,
+				changes: [
+					{
+						update: {
+							id: person:test,
+							name: 'Name: Jaime'
+						}
+					}
+				]
+			},
+			{
+				versionstamp: 3,
+				changes: [
+					{
+						delete: {
+							id: person:test
+						}
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn database_change_feeds() -> Result<(), Error> {
+	let sql = "
+		DEFINE DATABASE test CHANGEFEED 1h;
+		BEGIN TRANSACTION;
+		CREATE person:one SET name = 'One';
+		CREATE animal:one SET name = 'One';
+		COMMIT TRANSACTION;
+		CREATE person:two SET name = 'Two';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for r in res.drain(..) {
+		assert!(r.result.is_ok());
+	}
+	// All changes in a transaction share a versionstamp
+	let sql = "SHOW CHANGES FOR DATABASE SINCE 0";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 1,
+				changes: [
+					{ update: { id: animal:one, name: 'One' } },
+					{ update: { id: person:one, name: 'One' } }
+				]
+			},
+			{
+				versionstamp: 2,
+				changes: [
+					{ update: { id: person:two, name: 'Two' } }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Changes can be read from a specific versionstamp
+	let sql = "SHOW CHANGES FOR TABLE person SINCE 2";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 2,
+				changes: [
+					{ update: { id: person:two, name: 'Two' } }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The number of change sets can be limited
+	let sql = "SHOW CHANGES FOR DATABASE LIMIT 1";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 1,
+				changes: [
+					{ update: { id: animal:one, name: 'One' } },
+					{ update: { id: person:one, name: 'One' } }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn database_change_feeds_expire() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE DATABASE test CHANGEFEED 1m;
+		CREATE person:one SET name = 'One';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	dbs.tick_at(100_000).await?;
+	dbs.execute("CREATE person:two SET name = 'Two'", &ses, None).await?;
+	dbs.tick_at(130_000).await?;
+	// Only the changes older than the expiry are removed
+	dbs.tick_at(160_000).await?;
+	let sql = "SHOW CHANGES FOR DATABASE SINCE 0";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 2,
+				changes: [
+					{ update: { id: person:two, name: 'Two' } }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// All changes are removed once they have expired
+	dbs.tick_at(190_000).await?;
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_table_at_version() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:one SET name = 'One';
+		CREATE person:two SET name = 'Two';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	let before = Datetime::from(Utc::now());
+	dbs.tick_at(before.timestamp_millis() as u64).await?;
+	let sql = "
+		UPDATE person:one SET name = 'Uno';
+		DELETE person:two;
+		CREATE person:three SET name = 'Three';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	let after = Datetime::from(Utc::now());
+	dbs.tick_at(after.timestamp_millis() as u64).await?;
+	let sql = format!(
+		"
+		SELECT * FROM person ORDER BY id VERSION {before};
+		SELECT * FROM person:two, person:three VERSION {before};
+		SELECT * FROM person:one..=two VERSION {before};
+		SELECT * FROM person ORDER BY id VERSION {after};
+		SELECT * FROM person ORDER BY id;
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:one, name: 'One' },
+			{ id: person:two, name: 'Two' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:two, name: 'Two' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:one, name: 'One' },
+			{ id: person:two, name: 'Two' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let val = Value::parse(
+		"[
+			{ id: person:one, name: 'Uno' },
+			{ id: person:three, name: 'Three' }
+		]",
+	);
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_record_at_version_between_ticks() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:one SET name = 'One';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	tokio::time::sleep(Duration::from_millis(10)).await;
+	let before = Datetime::from(Utc::now());
+	tokio::time::sleep(Duration::from_millis(10)).await;
+	dbs.execute("UPDATE person:one SET name = 'Uno';", &ses, None).await?;
+	// The version is exact, even though the datastore has not ticked
+	let sql = format!(
+		"
+		SELECT * FROM person:one VERSION {before};
+		SELECT * FROM person:one;
+	"
+	);
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:one, name: 'One' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:one, name: 'Uno' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_table_at_version_without_history() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:one SET name = 'One';
+		CREATE other:one SET name = 'One';
+		SELECT * FROM other VERSION '2020-01-01T00:00:00Z';
+		SELECT * FROM person VERSION '2020-01-01T00:00:00Z';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::VersionNotRetained { table }) if table == "other"));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::VersionExpired { table, .. }) if table == "person"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_table_at_version_before_changefeed() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:one SET name = 'One';
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:two SET name = 'Two';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	let before = Datetime::from(Utc::now());
+	dbs.tick_at(before.timestamp_millis() as u64).await?;
+	let sql = "
+		CREATE person:three SET name = 'Three';
+	";
+	dbs.execute(sql, &ses, None).await?;
+	let sql = format!("SELECT * FROM person ORDER BY id VERSION {before};");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	// Records created afterwards did not exist at the version
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:one, name: 'One' },
+			{ id: person:two, name: 'Two' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The value of a record which predates the change feed is unknown
+	dbs.execute("UPDATE person:one SET name = 'Uno';", &ses, None).await?;
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::VersionExpired { table, .. }) if table == "person"));
+	//
+	Ok(())
+}
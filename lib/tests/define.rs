@@ -23,6 +23,7 @@ async fn define_statement_namespace() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			logins: {},
 			namespaces: { test: 'DEFINE NAMESPACE test' },
 		}",
 	);
@@ -250,6 +251,7 @@ async fn define_statement_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN true THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
@@ -307,6 +309,7 @@ async fn define_statement_event_when_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			builds: {},
 			events: { test: "DEFINE EVENT test ON user WHEN $event = 'CREATE' THEN (CREATE activity SET user = $this, value = $after.email, action = $event)" },
 			fields: {},
 			tables: {},
@@ -364,6 +367,7 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN $before.email != $after.email THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
@@ -413,6 +417,7 @@ async fn define_statement_field() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user' },
 			tables: {},
@@ -445,6 +450,7 @@ async fn define_statement_field_type() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user TYPE string' },
 			tables: {},
@@ -477,6 +483,7 @@ async fn define_statement_field_value() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			builds: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user VALUE $value OR 'GBR'" },
 			tables: {},
@@ -509,6 +516,7 @@ async fn define_statement_field_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user ASSERT $value != NONE AND $value = /[A-Z]{3}/' },
 			tables: {},
@@ -541,6 +549,7 @@ async fn define_statement_field_type_value_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			builds: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user TYPE string VALUE $value OR 'GBR' ASSERT $value != NONE AND $value = /[A-Z]{3}/" },
 			tables: {},
@@ -583,6 +592,7 @@ async fn define_statement_index_single_simple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -625,6 +635,7 @@ async fn define_statement_index_single() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -669,6 +680,7 @@ async fn define_statement_index_multiple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -721,6 +733,7 @@ async fn define_statement_index_single_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -779,6 +792,7 @@ async fn define_statement_index_multiple_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -865,6 +879,7 @@ async fn define_statement_index_single_unique_existing() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -912,6 +927,7 @@ async fn define_statement_index_multiple_unique_existing() -> Result<(), Error>
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -923,6 +939,203 @@ async fn define_statement_index_multiple_unique_existing() -> Result<(), Error>
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_index_concurrently() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE user:1 SET email = 'one@surrealdb.com';
+		CREATE user:2 SET email = 'two@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY;
+		CREATE user:3 SET email = 'three@surrealdb.com';
+		INFO FOR TABLE user;
+		SELECT id FROM user WHERE email = 'two@surrealdb.com' EXPLAIN;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			builds: { test: { processed: 0, status: 'pending' } },
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The index is not used until it is built
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: user:2
+			},
+			{
+				explain: [
+					{
+						detail: {
+							table: 'user'
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Build the index in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		SELECT id FROM user WHERE email = 'two@surrealdb.com' EXPLAIN;
+		CREATE user:4 SET email = 'one@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			builds: {},
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: user:2
+			},
+			{
+				explain: [
+					{
+						detail: {
+							plan: {
+								index: 'test',
+								operator: '=',
+								value: 'two@surrealdb.com'
+							},
+							table: 'user'
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `test` already contains 'one@surrealdb.com', with record `user:4`"#
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently_failed() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE user:1 SET email = 'test@surrealdb.com';
+		CREATE user:2 SET email = 'test@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The build can't be completed
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		UPDATE user:2 SET email = 'other@surrealdb.com';
+		REBUILD INDEX test ON user;
+		INFO FOR TABLE user;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			builds: { test: {
+				error: 'Database index `test` already contains \\'test@surrealdb.com\\', with record `user:2`',
+				processed: 0,
+				status: 'failed'
+			} },
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			builds: { test: { processed: 0, status: 'pending' } },
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The index is rebuilt in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		SELECT id FROM user WHERE email = 'other@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			builds: {},
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_analyzer() -> Result<(), Error> {
 	let sql = "
@@ -961,6 +1174,43 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_analyzer_with_tokenizers() -> Result<(), Error> {
+	let sql = "
+		DEFINE ANALYZER cjk TOKENIZERS cjk,words FILTERS lowercase;
+		DEFINE ANALYZER sku TOKENIZERS regex('[A-Z]{2,}-[0-9]+');
+		INFO FOR DB;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {
+				cjk: 'DEFINE ANALYZER cjk TOKENIZERS CJK,WORDS FILTERS LOWERCASE',
+				sku: \"DEFINE ANALYZER sku TOKENIZERS REGEX('[A-Z]{2,}-[0-9]+')\",
+			},
+			logins: {},
+			tokens: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			tables: {}
+		}",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_search_index() -> Result<(), Error> {
 	let sql = r#"
@@ -987,6 +1237,7 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -1031,3 +1282,87 @@ where
 		check(v);
 	}
 }
+
+#[tokio::test]
+async fn define_statement_login_on_root() -> Result<(), Error> {
+	let sql = "
+		DEFINE LOGIN test ON ROOT PASSHASH 'hash';
+		INFO FOR KV;
+		REMOVE LOGIN test ON ROOT;
+		INFO FOR KV;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv();
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			logins: { test: 'DEFINE LOGIN test ON ROOT PASSHASH \\'hash\\'' },
+			namespaces: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			logins: {},
+			namespaces: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let ses = Session::for_kv().with_ns("test");
+	let res = &mut dbs.execute("DEFINE LOGIN test ON ROOT PASSHASH 'hash'", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let ses = Session::for_ns("test");
+	let res = &mut dbs.execute("DEFINE LOGIN other ON ROOT PASSHASH 'hash'", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryPermissions)));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_login_on_root_signin() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	// The initial credentials create the first root user
+	dbs.setup_initial_creds("root", "root").await?;
+	// The initial credentials are ignored once a root user exists
+	dbs.setup_initial_creds("other", "other").await?;
+	let ses = Session::for_kv();
+	let res = &mut dbs.execute("INFO FOR KV", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let logins = tmp.pick(&[Part::from("logins")]);
+	assert!(logins.pick(&[Part::from("root")]).is_some());
+	assert!(logins.pick(&[Part::from("other")]).is_none());
+	// Stored root users can sign in with their password
+	let signin = |user: &str, pass: &str| {
+		let mut vars = surrealdb::sql::Object::default();
+		vars.insert("user".into(), user.into());
+		vars.insert("pass".into(), pass.into());
+		vars
+	};
+	let mut ses = Session::default();
+	let res = surrealdb::iam::signin::signin(&dbs, &None, &mut ses, signin("root", "root")).await;
+	assert!(res.is_ok());
+	assert!(ses.au.is_kv());
+	let mut ses = Session::default();
+	let res = surrealdb::iam::signin::signin(&dbs, &None, &mut ses, signin("root", "wrong")).await;
+	assert!(matches!(res, Err(Error::InvalidAuth)));
+	let mut ses = Session::default();
+	let res = surrealdb::iam::signin::signin(&dbs, &None, &mut ses, signin("other", "other")).await;
+	assert!(matches!(res, Err(Error::InvalidAuth)));
+	//
+	Ok(())
+}
@@ -3118,6 +3118,60 @@ async fn function_rand_uuid_v7() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// search
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_search_analyze() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,stopwords(english);
+		RETURN search::analyze('simple', 'The Quick brown fox');
+		RETURN search::analyze('simple', ['Hello', 'World 42']);
+		RETURN search::analyze('unknown', 'Hello');
+	"#;
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ token: 'quick', start: 4, end: 9, position: 0 },
+			{ token: 'brown', start: 10, end: 15, position: 1 },
+			{ token: 'fox', start: 16, end: 19, position: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[{ token: 'hello', start: 0, end: 5, position: 0 }],
+			[
+				{ token: 'world', start: 0, end: 5, position: 0 },
+				{ token: '42', start: 6, end: 8, position: 1 }
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "The analyzer 'unknown' does not exist"
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // string
 // --------------------------------------------------
@@ -93,3 +93,163 @@ async fn select_limit_fetch() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_order_limit_with_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD ts ON event TYPE int;
+		DEFINE INDEX event_ts ON event FIELDS ts;
+		CREATE event:1 SET ts = 30, kind = 'a';
+		CREATE event:2 SET ts = 10, kind = 'b';
+		CREATE event:3 SET ts = 20, kind = 'a';
+		CREATE event:4 SET ts = 20, kind = 'a';
+		CREATE event:5 SET ts = 50, kind = 'b';
+		SELECT id, ts FROM event ORDER BY ts LIMIT 3 EXPLAIN;
+		SELECT id, ts FROM event ORDER BY ts DESC LIMIT 3 START 1 EXPLAIN;
+		SELECT id, ts FROM event WHERE kind = 'a' ORDER BY ts DESC LIMIT 2;
+		SELECT id, ts FROM event ORDER BY ts DESC EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..7 {
+		let _ = res.remove(0).result?;
+	}
+	// The index is read in ascending order
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: event:2, ts: 10 },
+			{ id: event:3, ts: 20 },
+			{ id: event:4, ts: 20 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'event_ts',
+								order: 'ASC'
+							},
+							table: 'event',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The index can't be read in descending order by the memory storage engine
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: event:1, ts: 30 },
+			{ id: event:3, ts: 20 },
+			{ id: event:4, ts: 20 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							table: 'event',
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The records are filtered before they are ordered
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: event:1, ts: 30 },
+			{ id: event:3, ts: 20 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The index is not used without a limit
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: event:5, ts: 50 },
+			{ id: event:1, ts: 30 },
+			{ id: event:3, ts: 20 },
+			{ id: event:4, ts: 20 },
+			{ id: event:2, ts: 10 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							table: 'event',
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_limit_without_index() -> Result<(), Error> {
+	let sql = "
+		CREATE item:1 SET n = 3;
+		CREATE item:2 SET n = 1;
+		CREATE item:3 SET n = 2.5;
+		CREATE item:4 SET n = 3;
+		CREATE item:5 SET n = 5;
+		CREATE item:6 SET n = 0;
+		SELECT id, n FROM item ORDER BY n DESC LIMIT 3;
+		SELECT id, n FROM item ORDER BY n LIMIT 2 START 2;
+		SELECT id, n FROM item ORDER BY n DESC, id DESC LIMIT 100;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let _ = res.remove(0).result?;
+	}
+	// Equal results keep their original order
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: item:5, n: 5 },
+			{ id: item:1, n: 3 },
+			{ id: item:4, n: 3 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: item:3, n: 2.5 },
+			{ id: item:1, n: 3 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The limit can be larger than the number of results
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: item:5, n: 5 },
+			{ id: item:4, n: 3 },
+			{ id: item:1, n: 3 },
+			{ id: item:3, n: 2.5 },
+			{ id: item:2, n: 1 },
+			{ id: item:6, n: 0 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
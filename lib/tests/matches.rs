@@ -3,6 +3,7 @@ use parse::Parse;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::kvs::Datastore;
+use surrealdb::sql::json;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -51,7 +52,7 @@ async fn select_where_matches_using_index() -> Result<(), Error> {
 }
 
 #[tokio::test]
-async fn select_where_matches_without_using_index_iterator() -> Result<(), Error> {
+async fn select_where_matches_using_index_union() -> Result<(), Error> {
 	let sql = r"
 		CREATE blog:1 SET title = 'Hello World!';
 		CREATE blog:2 SET title = 'Foo Bar!';
@@ -68,27 +69,41 @@ async fn select_where_matches_without_using_index_iterator() -> Result<(), Error
 	let _ = res.remove(0).result?;
 	let _ = res.remove(0).result?;
 	let _ = res.remove(0).result?;
-	let tmp = res.remove(0).result?;
-	let val = Value::parse(
-		"[
-			{
-				id: blog:1,
-				title: 'Hello <em>World</em>!'
-			},
-			{
-				explain:
-				[
-					{
-						detail: {
-							table: 'blog',
+	let Value::Array(mut tmp) = res.remove(0).result? else {
+		panic!("expected an array");
+	};
+	// The explanation is too deeply nested to be parsed quickly as a value
+	let exp = tmp.0.pop();
+	let val = Value::parse("[{ id: blog:1, title: 'Hello <em>World</em>!' }]");
+	assert_eq!(Value::from(tmp), val);
+	let val = json(
+		"{
+			explain:
+			[
+				{
+					detail: {
+						plan: {
+							union: [
+								{
+									index: 'blog_title',
+									operator: '@0@',
+									value: 'hello'
+								},
+								{
+									index: 'blog_title',
+									operator: '@1@',
+									value: 'world'
+								}
+							]
 						},
-						operation: 'Iterate Table'
-					}
-				]
-			}
-		]",
-	);
-	assert_eq!(tmp, val);
+						table: 'blog',
+					},
+					operation: 'Iterate Index'
+				}
+			]
+		}",
+	)?;
+	assert_eq!(exp, Some(val));
 	Ok(())
 }
 
@@ -264,3 +279,357 @@ async fn select_where_matches_without_using_index_and_score() -> Result<(), Erro
 	assert_eq!(tmp, val);
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_phrases() -> Result<(), Error> {
+	let sql = r#"
+		CREATE blog:1 SET title = 'The city of New York never sleeps';
+		CREATE blog:2 SET title = 'A new bakery opened in York';
+		CREATE blog:3 SET title = 'The quick brown fox';
+		CREATE blog:4 SET title = 'Hello World!';
+		CREATE blog:5 SET title = 'Foo Bar!';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id FROM blog WHERE title @@ 'new york';
+		SELECT id, search::score(1) > 0 AS scored FROM blog WHERE title @1@ '"new york"';
+		SELECT id FROM blog WHERE title @@ '"quick fox"';
+		SELECT id FROM blog WHERE title @@ '"quick fox"~1';
+		SELECT id FROM blog WHERE title @@ '"fox quick"~3';
+		SELECT id FROM blog WHERE (title @@ '"new york"' AND id > 0) OR (title @@ '"new york"' AND id < 99);
+	"#;
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for _ in 0..7 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1, scored: true }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:3 }]");
+	assert_eq!(tmp, val);
+	// The terms of a phrase must appear in order
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// Phrases are also checked when the index is not iterated
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_phrase_without_highlights() -> Result<(), Error> {
+	let sql = r#"
+		CREATE blog:1 SET title = 'The city of New York never sleeps';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25;
+		SELECT id FROM blog WHERE title @@ '"new york"';
+	"#;
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::PhraseWithoutHighlights)));
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_boolean_query() -> Result<(), Error> {
+	let sql = r#"
+		CREATE product:1 SET name = 'Laptop with a fast CPU';
+		CREATE product:2 SET name = 'Refurbished laptop';
+		CREATE product:3 SET name = 'Tablet with a slow CPU';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX product_name ON product FIELDS name SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id FROM product WHERE name @@ 'laptop -refurbished';
+		SELECT id FROM product WHERE name @@ '(laptop OR tablet) -"slow cpu"';
+		SELECT id, search::highlight('<em>', '</em>', 1) AS name FROM product WHERE name @1@ 'refurbished OR tablet -slow';
+		SELECT id, search::score(1) * 2 = search::score(2) AS boosted FROM product WHERE name @1@ 'fast' AND name @2@ 'fast^2';
+	"#;
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }, { id: product:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:2, name: '<em>Refurbished</em> laptop' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1, boosted: true }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_expanded_terms() -> Result<(), Error> {
+	let sql = r"
+		CREATE product:1 SET name = 'Laptop with a fast CPU';
+		CREATE product:2 SET name = 'Refurbished laptops';
+		CREATE product:3 SET name = 'Desk lamp';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX product_name ON product FIELDS name SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS name FROM product WHERE name @1@ 'lap*';
+		SELECT id FROM product WHERE name @@ 'lapttop~1';
+		SELECT id FROM product WHERE name @@ 'lap* -refurbished';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: product:1, name: '<em>Laptop</em> with a fast CPU' },
+			{ id: product:2, name: 'Refurbished <em>laptops</em>' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_filters() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'The red car went fast';
+		CREATE blog:2 SET title = 'An automobile is parked';
+		CREATE blog:3 SET title = 'Walk to the park';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,stopwords(english),synonyms({ car: ['automobile'] }),mapper({ went: 'go', parked: 'park' });
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id FROM blog WHERE title @@ 'automobile';
+		SELECT id FROM blog WHERE title @@ 'the park';
+		SELECT id FROM blog WHERE title @@ 'go';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:2 }, { id: blog:3 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_cjk_bigrams() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = '東京都に住む';
+		CREATE blog:2 SET title = '京都の寺';
+		DEFINE ANALYZER cjk TOKENIZERS cjk,blank FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER cjk BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS title FROM blog WHERE title @1@ '東京都';
+		SELECT id FROM blog WHERE title @@ '京都';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1, title: '<em>東京都</em>に住む' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_weighted_fields() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'Rust database', content = 'a short rust note';
+		CREATE blog:2 SET title = 'Cooking tips', content = 'rust is great here';
+		CREATE blog:3 SET title = 'Hello', content = 'nothing to see';
+		CREATE blog:4 SET title = 'World', content = 'nothing to see';
+		CREATE blog:5 SET title = 'Again', content = 'nothing to see';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_search ON blog FIELDS title, content SEARCH ANALYZER simple BM25 WEIGHTS(3,1) HIGHLIGHTS;
+		SELECT id, search::score(1) AS score FROM blog WHERE content @1@ 'rust';
+		SELECT id, search::highlight('<b>', '</b>', 1) AS content FROM blog WHERE content @1@ 'rust';
+		SELECT id, search::highlight('<b>', '</b>', 1) AS title FROM blog WHERE title @1@ 'rust';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..7 {
+		let _ = res.remove(0).result?;
+	}
+	// The match in the title is boosted by its weight, along with the match in the content
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:1, score: 1.2210124731063843 },
+			{ id: blog:2, score: 0.30525311827659607 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Each field is highlighted on its own
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:1, content: 'a short <b>rust</b> note' },
+			{ id: blog:2, content: '<b>rust</b> is great here' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Only the documents matching within the title are returned
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:1, title: '<b>Rust</b> database' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_top_scores() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'the quick brown fox jumped over the lazy dog', published = true;
+		CREATE blog:2 SET title = 'the fast fox jumped over the lazy dog', published = false;
+		CREATE blog:3 SET title = 'the other animals sat there watching', published = true;
+		CREATE blog:4 SET title = 'the dog sat there and did nothing', published = true;
+		CREATE blog:5 SET title = 'a fox is a fox', published = true;
+		CREATE blog:6 SET title = 'nothing to see here', published = true;
+		CREATE blog:7 SET title = 'a cat sat on the mat', published = true;
+		CREATE blog:8 SET title = 'birds are singing', published = true;
+		CREATE blog:9 SET title = 'the sun is shining', published = true;
+		CREATE blog:10 SET title = 'rain is coming', published = true;
+		DEFINE ANALYZER simple TOKENIZERS blank,class;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::score(1) AS score FROM blog WHERE title @1@ 'dog OR fox' ORDER BY score DESC;
+		SELECT id, search::score(1) AS score FROM blog WHERE title @1@ 'dog OR fox' ORDER BY score DESC LIMIT 2;
+		SELECT id, search::score(1) AS score FROM blog WHERE title @1@ 'dog OR fox' ORDER BY score DESC LIMIT 1 START 1;
+		SELECT id, search::score(1) AS score FROM blog WHERE title @1@ 'dog OR fox' AND published = true ORDER BY score DESC LIMIT 3;
+		SELECT id, search::score(1) AS score FROM blog WHERE title @1@ 'dog OR fox' ORDER BY score DESC LIMIT 2 EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 17);
+	//
+	for _ in 0..12 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:5, score: 1.3402597904205322 },
+			{ id: blog:2, score: 1.2852814197540283 },
+			{ id: blog:1, score: 1.2094286680221558 },
+			{ id: blog:4, score: 0.6856427192687988 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The first records are read by descending score
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:5, score: 1.3402597904205322 },
+			{ id: blog:2, score: 1.2852814197540283 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:2, score: 1.2852814197540283 }]");
+	assert_eq!(tmp, val);
+	// The records filtered out by the other condition are replaced by the next ones
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:5, score: 1.3402597904205322 },
+			{ id: blog:1, score: 1.2094286680221558 },
+			{ id: blog:4, score: 0.6856427192687988 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: blog:5, score: 1.3402597904205322 },
+			{ id: blog:2, score: 1.2852814197540283 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'blog_title',
+								operator: '@1@',
+								top: 2,
+								value: 'dog OR fox'
+							},
+							table: 'blog'
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
@@ -3,6 +3,7 @@ use parse::Parse;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::kvs::Datastore;
+use surrealdb::sql::json;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -391,3 +392,677 @@ async fn select_where_and_with_fulltext_index() -> Result<(), Error> {
 	assert_eq!(tmp, val);
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_range_with_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:a SET age = 18;
+		CREATE person:b SET age = 30;
+		CREATE person:c SET age = 30.5;
+		CREATE person:d SET age = 31;
+		CREATE person:e SET age = 45;
+		CREATE person:f SET age = 'unknown';
+		CREATE person:g SET name = 'none';
+		DEFINE INDEX person_age ON TABLE person COLUMNS age;
+		SELECT id FROM person WHERE age > 30 ORDER BY id EXPLAIN;
+		SELECT id FROM person WHERE age <= 30.5 ORDER BY id;
+		SELECT id FROM person WHERE 31 > age ORDER BY id;
+		SELECT id FROM person WHERE age >= 'a' ORDER BY id;
+		SELECT id FROM person WHERE age INSIDE [18, 31.0, 'unknown'] ORDER BY id EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for _ in 0..8 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:c },
+			{ id: person:d },
+			{ id: person:e },
+			{ id: person:f },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_age',
+								operator: '>',
+								value: 30
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a },
+			{ id: person:b },
+			{ id: person:c },
+			{ id: person:g }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a },
+			{ id: person:b },
+			{ id: person:c },
+			{ id: person:g }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:f }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a },
+			{ id: person:d },
+			{ id: person:f },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_age',
+								operator: 'INSIDE',
+								value: [18, 31.0, 'unknown']
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_inside_with_index_and_mixed_numbers() -> Result<(), Error> {
+	let sql = "
+		CREATE person:a SET age = 1;
+		CREATE person:b SET age = 1.5;
+		CREATE person:c SET age = 2;
+		CREATE person:d SET age = 1.0;
+		DEFINE INDEX person_age ON TABLE person COLUMNS age;
+		SELECT id FROM person WHERE age INSIDE [1, 1.5] ORDER BY id;
+		SELECT id FROM person WHERE age INSIDE [1, 1.0, 1.2, 2] ORDER BY id;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	// Each matching record is only returned once
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a },
+			{ id: person:b },
+			{ id: person:d }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a },
+			{ id: person:c },
+			{ id: person:d }
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_range_with_unique_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		CREATE person:lizzie SET name = 'Lizzie';
+		DEFINE INDEX person_name ON TABLE person COLUMNS name UNIQUE;
+		SELECT name FROM person WHERE name < 'Tobie' AND name >= 'Jaime' ORDER BY name EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Jaime' },
+			{ name: 'Lizzie' },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_name',
+								operator: '>=',
+								value: 'Jaime'
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_with_composite_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:a SET name = 'Tobie', genre = 'm', age = 30;
+		CREATE person:b SET name = 'Tobie', genre = 'm', age = 40;
+		CREATE person:c SET name = 'Tobie', genre = 'f', age = 50;
+		CREATE person:d SET name = 'Jaime', genre = 'm', age = 40;
+		DEFINE INDEX person_name_genre_age ON TABLE person COLUMNS name, genre, age;
+		SELECT id FROM person WHERE age > 35 AND name = 'Tobie' AND genre = 'm' EXPLAIN;
+		SELECT id FROM person WHERE name = 'Tobie' AND age > 35 ORDER BY id EXPLAIN;
+		SELECT id FROM person WHERE age = 40 ORDER BY id EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	// Equality conditions followed by a range condition
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:b },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_name_genre_age',
+								operator: '>',
+								prefix: ['name', 'genre', 'age'],
+								value: ['Tobie', 'm', 35]
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The prefix stops at the first column without an equality condition
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:b },
+			{ id: person:c },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_name_genre_age',
+								operator: '=',
+								prefix: ['name'],
+								value: ['Tobie']
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// A composite index can't be used without its leading column
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:b },
+			{ id: person:d },
+			{
+				explain:
+				[
+					{
+						detail: {
+							table: 'person',
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_with_the_most_selective_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET email = 'tobie@surrealdb.com', genre = 'm';
+		CREATE person:jaime SET email = 'jaime@surrealdb.com', genre = 'm';
+		DEFINE INDEX person_genre ON TABLE person COLUMNS genre;
+		DEFINE INDEX person_email ON TABLE person COLUMNS email UNIQUE;
+		SELECT id FROM person WHERE email = 'jaime@surrealdb.com' AND genre = 'm' EXPLAIN;
+		SELECT id FROM person WHERE genre = 'm' AND email = 'jaime@surrealdb.com' EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The unique index is chosen whatever the order of the conditions
+	let val = Value::parse(
+		"[
+			{ id: person:jaime },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'person_email',
+								operator: '=',
+								value: 'jaime@surrealdb.com'
+							},
+							table: 'person',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, val);
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_with_index_intersection() -> Result<(), Error> {
+	let mut sql = String::new();
+	for i in 0..24 {
+		sql.push_str(&format!("CREATE item:{i} SET color = {}, size = {};", i % 2, i % 3));
+	}
+	sql.push_str(
+		"
+		DEFINE INDEX item_color ON TABLE item COLUMNS color;
+		DEFINE INDEX item_size ON TABLE item COLUMNS size;
+		ANALYZE INDEX item_color ON item;
+		ANALYZE INDEX item_size ON item;
+		SELECT id FROM item WHERE color = 0 AND size = 0 EXPLAIN;
+		REMOVE INDEX item_color ON item;
+		SELECT id FROM item WHERE color = 0 AND size = 0 EXPLAIN;
+	",
+	);
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 31);
+	//
+	for _ in 0..26 {
+		let _ = res.remove(0).result?;
+	}
+	// The statistics of the indexes
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ entries: 24, distinct: [2] }");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ entries: 24, distinct: [3] }");
+	assert_eq!(tmp, val);
+	// The most selective index is intersected with the other index
+	let Value::Array(mut tmp) = res.remove(0).result? else {
+		panic!("expected an array");
+	};
+	// The explanation is too deeply nested to be parsed quickly as a value
+	let exp = tmp.0.pop();
+	let val = Value::parse("[{ id: item:0 }, { id: item:6 }, { id: item:12 }, { id: item:18 }]");
+	assert_eq!(Value::from(tmp), val);
+	let val = json(
+		"{
+			explain:
+			[
+				{
+					detail: {
+						plan: {
+							index: 'item_size',
+							intersect: [
+								{
+									index: 'item_color',
+									operator: '=',
+									value: 0
+								}
+							],
+							operator: '=',
+							value: 0
+						},
+						table: 'item',
+					},
+					operation: 'Iterate Index'
+				}
+			]
+		}",
+	)?;
+	assert_eq!(exp, Some(val));
+	//
+	let _ = res.remove(0).result?;
+	// A removed index is not intersected
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: item:0 },
+			{ id: item:6 },
+			{ id: item:12 },
+			{ id: item:18 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'item_size',
+								operator: '=',
+								value: 0
+							},
+							table: 'item',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_by_alias_with_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD x ON TABLE point TYPE int;
+		DEFINE INDEX point_x ON TABLE point COLUMNS x;
+		CREATE point:1 SET x = 1, y = 3;
+		CREATE point:2 SET x = 2, y = 2;
+		CREATE point:3 SET x = 3, y = 1;
+		SELECT y AS x FROM point ORDER BY x LIMIT 2 EXPLAIN;
+		SELECT x FROM point ORDER BY x LIMIT 2 EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	// The records are ordered by the aliased value, not by the indexed field
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ x: 1 },
+			{ x: 2 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							table: 'point',
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The index is used when the field itself is selected
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ x: 1 },
+			{ x: 2 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'point_x',
+								order: 'ASC'
+							},
+							table: 'point',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_with_partially_analyzed_indexes() -> Result<(), Error> {
+	let mut sql = String::new();
+	for i in 0..24 {
+		sql.push_str(&format!("CREATE item:{i} SET color = {}, size = {};", i % 2, i % 3));
+	}
+	sql.push_str(
+		"
+		DEFINE INDEX item_color ON TABLE item COLUMNS color;
+		DEFINE INDEX item_size ON TABLE item COLUMNS size;
+		ANALYZE INDEX item_size ON item;
+		SELECT id FROM item WHERE size = 0 AND color = 0 EXPLAIN;
+	",
+	);
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 28);
+	//
+	for _ in 0..27 {
+		let _ = res.remove(0).result?;
+	}
+	// The index which was not analyzed is estimated with the number of entries of the other
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: item:0 },
+			{ id: item:6 },
+			{ id: item:12 },
+			{ id: item:18 },
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'item_size',
+								operator: '=',
+								value: 0
+							},
+							table: 'item',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_or_with_indexes() -> Result<(), Error> {
+	let sql = "
+		CREATE person:a SET name = 'Tobie', age = 30;
+		CREATE person:b SET name = 'Jaime', age = 40;
+		CREATE person:c SET name = 'Tobie', age = 50;
+		CREATE person:d SET name = 'Lizzie', age = 20;
+		DEFINE INDEX person_name ON TABLE person COLUMNS name;
+		DEFINE INDEX person_age ON TABLE person COLUMNS age;
+		SELECT id FROM person WHERE name = 'Tobie' OR age >= 40 OR name = 'Tobie' EXPLAIN;
+		SELECT id FROM person WHERE name = 'Lizzie' OR (age > 45 AND name = 'Tobie') EXPLAIN;
+		SELECT id FROM person WHERE name = 'Lizzie' OR genre = 'm' EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let _ = res.remove(0).result?;
+	}
+	// Each record is returned once, even if it matches several branches
+	let Value::Array(mut tmp) = res.remove(0).result? else {
+		panic!("expected an array");
+	};
+	// The explanation is too deeply nested to be parsed quickly as a value
+	let exp = tmp.0.pop();
+	let val = Value::parse("[{ id: person:a }, { id: person:c }, { id: person:b }]");
+	assert_eq!(Value::from(tmp), val);
+	let val = json(
+		"{
+			explain:
+			[
+				{
+					detail: {
+						plan: {
+							union: [
+								{
+									index: 'person_name',
+									operator: '=',
+									value: 'Tobie'
+								},
+								{
+									index: 'person_age',
+									operator: '>=',
+									value: 40
+								},
+								{
+									index: 'person_name',
+									operator: '=',
+									value: 'Tobie'
+								}
+							]
+						},
+						table: 'person',
+					},
+					operation: 'Iterate Index'
+				}
+			]
+		}",
+	)?;
+	assert_eq!(exp, Some(val));
+	// A branch can be made of several AND-ed conditions
+	let Value::Array(mut tmp) = res.remove(0).result? else {
+		panic!("expected an array");
+	};
+	let exp = tmp.0.pop();
+	let val = Value::parse("[{ id: person:d }, { id: person:c }]");
+	assert_eq!(Value::from(tmp), val);
+	let val = json(
+		"{
+			explain:
+			[
+				{
+					detail: {
+						plan: {
+							union: [
+								{
+									index: 'person_name',
+									operator: '=',
+									value: 'Lizzie'
+								},
+								{
+									index: 'person_name',
+									operator: '=',
+									value: 'Tobie'
+								}
+							]
+						},
+						table: 'person',
+					},
+					operation: 'Iterate Index'
+				}
+			]
+		}",
+	)?;
+	assert_eq!(exp, Some(val));
+	// Every branch must be covered by an index
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:d },
+			{
+				explain:
+				[
+					{
+						detail: {
+							table: 'person',
+						},
+						operation: 'Iterate Table'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
@@ -232,6 +232,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			logins: {},
 			namespaces: { test: 'DEFINE NAMESPACE test' },
 		}",
 	);
@@ -264,6 +265,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			builds: {},
 			events: {},
 			fields: { extra: 'DEFINE FIELD extra ON test VALUE true' },
 			tables: {},
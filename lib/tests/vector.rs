@@ -0,0 +1,152 @@
+mod parse;
+use parse::Parse;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn select_where_knn_using_index() -> Result<(), Error> {
+	let sql = r"
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		CREATE pts:3 SET point = [8,9,10,11];
+		CREATE pts:4;
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 4;
+		LET $pt = [2,3,4,5];
+		SELECT id FROM pts WHERE point <|2|> $pt EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: pts:1
+			},
+			{
+				id: pts:2
+			},
+			{
+				explain:
+				[
+					{
+						detail: {
+							plan: {
+								index: 'mt_pts',
+								operator: '<|2|>',
+								value: [2,3,4,5]
+							},
+							table: 'pts',
+						},
+						operation: 'Iterate Index'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_knn_with_distances() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 2 DIST MANHATTAN CAPACITY 2;
+		CREATE pts:1 SET point = [0,0];
+		CREATE pts:2 SET point = [3,1];
+		CREATE pts:3 SET point = [-2,-1];
+		CREATE pts:4 SET point = [1,1];
+		CREATE pts:5 SET point = [10,10];
+		SELECT id FROM pts WHERE point <|3|> [0,0];
+		UPDATE pts:5 SET point = [0,1];
+		DELETE pts:4;
+		SELECT id FROM pts WHERE point <|3|> [0,0];
+		SELECT id FROM pts WHERE point <|3|> [0,0] AND point[0] >= 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..6 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: pts:1 }, { id: pts:4 }, { id: pts:3 }]");
+	assert_eq!(tmp, val);
+	//
+	let _ = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: pts:1 }, { id: pts:5 }, { id: pts:3 }]");
+	assert_eq!(tmp, val);
+	// The nearest neighbours are found before the other conditions are checked
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: pts:1 }, { id: pts:5 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_knn_cosine() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 2 DIST COSINE;
+		CREATE pts:1 SET point = [10,0];
+		CREATE pts:2 SET point = [1,1];
+		CREATE pts:3 SET point = [-1,0.1];
+		SELECT id FROM pts WHERE point <|2|> [1,0];
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: pts:1 }, { id: pts:2 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn vector_index_errors() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 2;
+		CREATE pts:1 SET point = [1,2,3];
+		CREATE pts:2 SET point = 'test';
+		CREATE pts:3 SET point = [1,2];
+		SELECT id FROM pts WHERE point <|2|> [1,2,3];
+		SELECT id FROM pts WHERE other <|2|> [1,2];
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::InvalidVectorDimension {
+			current: 3,
+			expected: 2
+		})
+	));
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidVectorValue { .. })));
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidVectorDimension { .. })));
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::NoIndexFoundForKnn { .. })));
+	Ok(())
+}
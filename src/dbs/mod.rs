@@ -1,9 +1,11 @@
 use crate::cli::CF;
+use crate::cnf::NODE_NOTIFICATION_FREQUENCY;
 use crate::err::Error;
 use clap::Args;
 use once_cell::sync::OnceCell;
 use std::time::Duration;
 use surrealdb::kvs::Datastore;
+use uuid::Uuid;
 
 pub static DB: OnceCell<Datastore> = OnceCell::new();
 
@@ -21,6 +23,16 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_TRANSACTION_TIMEOUT", long)]
 	#[arg(value_parser = super::cli::validator::duration)]
 	transaction_timeout: Option<Duration>,
+	#[arg(help = "The interval at which to run node agent tick (including garbage collection)")]
+	#[arg(env = "SURREAL_TICK_INTERVAL", long)]
+	#[arg(default_value = "10s")]
+	#[arg(value_parser = super::cli::validator::duration)]
+	tick_interval: Duration,
+	#[arg(help = "The duration after which a cluster node without a heartbeat is removed")]
+	#[arg(env = "SURREAL_NODE_EXPIRY", long)]
+	#[arg(default_value = "60s")]
+	#[arg(value_parser = super::cli::validator::duration)]
+	node_expiry: Duration,
 }
 
 pub async fn init(
@@ -28,6 +40,8 @@ pub async fn init(
 		strict_mode,
 		query_timeout,
 		transaction_timeout,
+		tick_interval,
+		node_expiry,
 	}: StartCommandDbsOptions,
 ) -> Result<(), Error> {
 	// Get local copy of options
@@ -42,15 +56,71 @@ pub async fn init(
 	if let Some(v) = transaction_timeout {
 		debug!("Maximum transaction processing timeout is {v:?}");
 	}
+	// Log specified tick interval
+	debug!("Node agent tick interval is {tick_interval:?}");
+	// Log specified node expiry
+	debug!("Cluster node expiry is {node_expiry:?}");
 	// Parse and setup the desired kv datastore
 	let dbs = Datastore::new(&opt.path)
 		.await?
+		.with_node_id(Uuid::new_v4())
 		.with_notifications()
 		.with_strict_mode(strict_mode)
 		.with_query_timeout(query_timeout)
 		.with_transaction_timeout(transaction_timeout);
+	// Register this node with the cluster
+	dbs.register_membership().await?;
+	// Bootstrap the initial root user if none exists
+	if let Some(pass) = &opt.pass {
+		dbs.setup_initial_creds(&opt.user, pass).await?;
+	}
 	// Store database instance
 	let _ = DB.set(dbs);
+	// Start the node agent
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(tick_interval);
+		loop {
+			interval.tick().await;
+			if let Err(e) = DB.get().unwrap().tick().await {
+				error!("Error running node agent tick: {}", e);
+			}
+		}
+	});
+	// Build the indexes which were defined concurrently or rebuilt
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(tick_interval);
+		loop {
+			interval.tick().await;
+			if let Err(e) = DB.get().unwrap().build_indexes().await {
+				error!("Error building indexes: {}", e);
+			}
+		}
+	});
+	// Remove the nodes which have stopped sending heartbeats
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(tick_interval);
+		loop {
+			interval.tick().await;
+			match DB.get().unwrap().expire_nodes(node_expiry).await {
+				Ok(v) => {
+					for nd in v {
+						info!("Removed expired cluster node {}", nd);
+					}
+				}
+				Err(e) => error!("Error removing expired cluster nodes: {}", e),
+			}
+		}
+	});
+	// Deliver the notifications queued by other nodes
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(NODE_NOTIFICATION_FREQUENCY);
+		loop {
+			interval.tick().await;
+			if let Err(e) = DB.get().unwrap().process_notifications().await {
+				error!("Error delivering node notifications: {}", e);
+			}
+		}
+	});
 	// All ok
 	Ok(())
 }
@@ -14,7 +14,7 @@ pub async fn init() -> Result<(), Error> {
 			info!("Root authentication is enabled");
 			info!("Root username is '{}'", opt.user);
 		}
-		None => info!("Root authentication is disabled"),
+		None => info!("Root authentication is limited to the defined root logins"),
 	};
 	// All ok
 	Ok(())
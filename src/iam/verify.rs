@@ -1,4 +1,3 @@
-use crate::cli::CF;
 use crate::dbs::DB;
 use crate::err::Error;
 use crate::iam::BASIC;
@@ -16,8 +15,6 @@ pub async fn basic(session: &mut Session, auth: String) -> Result<(), Error> {
 	let auth = auth.trim_start_matches(BASIC).trim();
 	// Get a database reference
 	let kvs = DB.get().unwrap();
-	// Get the config options
-	let opts = CF.get().unwrap();
 	// Decode the encoded auth data
 	let auth = BASE64.decode(auth)?;
 	// Convert the auth data to String
@@ -28,20 +25,22 @@ pub async fn basic(session: &mut Session, auth: String) -> Result<(), Error> {
 		if user.is_empty() || pass.is_empty() {
 			return Err(Error::InvalidAuth);
 		}
+		// Create a new readonly transaction
+		let mut tx = kvs.transaction(false, false).await?;
 		// Check if this is root authentication
-		if let Some(root) = &opts.pass {
-			if user == opts.user && pass == root {
+		if let Ok(kl) = tx.get_kl(user).await {
+			// Compute the hash and verify the password
+			let hash = PasswordHash::new(&kl.hash).unwrap();
+			if Argon2::default().verify_password(pass.as_ref(), &hash).is_ok() {
 				// Log the authentication type
-				debug!("Authenticated as super user");
+				debug!("Authenticated as super user: {}", user);
 				// Store the authentication data
 				session.au = Arc::new(Auth::Kv);
 				return Ok(());
 			}
-		}
+		};
 		// Check if this is NS authentication
 		if let Some(ns) = &session.ns {
-			// Create a new readonly transaction
-			let mut tx = kvs.transaction(false, false).await?;
 			// Check if the supplied NS Login exists
 			if let Ok(nl) = tx.get_nl(ns, user).await {
 				// Compute the hash and verify the password
@@ -50,7 +49,7 @@ pub async fn basic(session: &mut Session, auth: String) -> Result<(), Error> {
 					// Log the successful namespace authentication
 					debug!("Authenticated as namespace user: {}", user);
 					// Store the authentication data
-					session.au = Arc::new(Auth::Ns(ns.to_owned()));
+					session.au = Arc::new(Auth::Ns(ns.to_owned(), nl.role()));
 					return Ok(());
 				}
 			};
@@ -64,7 +63,7 @@ pub async fn basic(session: &mut Session, auth: String) -> Result<(), Error> {
 						// Log the successful namespace authentication
 						debug!("Authenticated as database user: {}", user);
 						// Store the authentication data
-						session.au = Arc::new(Auth::Db(ns.to_owned(), db.to_owned()));
+						session.au = Arc::new(Auth::Db(ns.to_owned(), db.to_owned(), dl.role()));
 						return Ok(());
 					}
 				};
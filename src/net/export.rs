@@ -4,6 +4,7 @@ use crate::net::session;
 use bytes::Bytes;
 use hyper::body::Body;
 use surrealdb::dbs::Session;
+use surrealdb::sql::Role;
 use warp::Filter;
 
 #[allow(opaque_hidden_inferred_bound)]
@@ -16,8 +17,8 @@ pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejecti
 }
 
 async fn handler(session: Session) -> Result<impl warp::Reply, warp::Rejection> {
-	// Check the permissions
-	match session.au.is_db() {
+	// Check the permissions, as the export includes the token and login secrets
+	match session.au.is_db() && session.au.check_role(Role::Owner) {
 		true => {
 			// Get the datastore reference
 			let db = DB.get().unwrap();
@@ -1,4 +1,3 @@
-use crate::cli::CF;
 use crate::cnf::MAX_CONCURRENT_CALLS;
 use crate::cnf::PKG_NAME;
 use crate::cnf::PKG_VERSION;
@@ -19,10 +18,10 @@ use std::sync::Arc;
 use surrealdb::channel;
 use surrealdb::channel::Sender;
 use surrealdb::dbs::{QueryType, Response, Session};
-use surrealdb::opt::auth::Root;
 use surrealdb::sql::Array;
 use surrealdb::sql::Object;
 use surrealdb::sql::Strand;
+use surrealdb::sql::Thing;
 use surrealdb::sql::Value;
 use tokio::sync::RwLock;
 use tracing::instrument;
@@ -312,6 +311,21 @@ impl Rpc {
 				Ok(Value::Strand(v)) => rpc.write().await.authenticate(v).await,
 				_ => return res::failure(id, Failure::INVALID_PARAMS).send(out, chn).await,
 			},
+			// Exchange a scope authentication token for a new token
+			"refresh" => match params.needs_one() {
+				Ok(Value::Strand(v)) => rpc.write().await.refresh(v).await,
+				_ => return res::failure(id, Failure::INVALID_PARAMS).send(out, chn).await,
+			},
+			// Revoke the tokens of the current user, or of a scope record, on all devices
+			"revoke" => match params.len() {
+				0 => rpc.read().await.revoke(None).await,
+				_ => match params.needs_two() {
+					Ok((Value::Strand(sc), Value::Thing(rid))) => {
+						rpc.read().await.revoke(Some((sc, rid))).await
+					}
+					_ => return res::failure(id, Failure::INVALID_PARAMS).send(out, chn).await,
+				},
+			},
 			// Kill a live query using a query id
 			"kill" => match params.needs_one() {
 				Ok(v) if v.is_uuid() => rpc.read().await.kill(v).await,
@@ -444,18 +458,15 @@ impl Rpc {
 	#[instrument(skip_all, name = "rpc signin", fields(websocket=self.uuid.to_string()))]
 	async fn signin(&mut self, vars: Object) -> Result<Value, Error> {
 		let kvs = DB.get().unwrap();
-		let opts = CF.get().unwrap();
-		let root = opts.pass.as_ref().map(|pass| Root {
-			username: &opts.user,
-			password: pass,
-		});
-		surrealdb::iam::signin::signin(kvs, &root, &mut self.session, vars)
+		surrealdb::iam::signin::signin(kvs, &None, &mut self.session, vars)
 			.await
 			.map(Into::into)
 			.map_err(Into::into)
 	}
 	#[instrument(skip_all, name = "rpc invalidate", fields(websocket=self.uuid.to_string()))]
 	async fn invalidate(&mut self) -> Result<Value, Error> {
+		let kvs = DB.get().unwrap();
+		surrealdb::iam::revoke::revoke(kvs, &self.session).await?;
 		surrealdb::iam::clear::clear(&mut self.session)?;
 		Ok(Value::None)
 	}
@@ -467,6 +478,27 @@ impl Rpc {
 		Ok(Value::None)
 	}
 
+	#[instrument(skip_all, name = "rpc refresh", fields(websocket=self.uuid.to_string()))]
+	async fn refresh(&mut self, token: Strand) -> Result<Value, Error> {
+		let kvs = DB.get().unwrap();
+		surrealdb::iam::refresh::refresh(kvs, &mut self.session, token.0)
+			.await
+			.map(Into::into)
+			.map_err(Into::into)
+	}
+
+	#[instrument(skip_all, name = "rpc revoke", fields(websocket=self.uuid.to_string()))]
+	async fn revoke(&self, record: Option<(Strand, Thing)>) -> Result<Value, Error> {
+		let kvs = DB.get().unwrap();
+		match record {
+			Some((sc, id)) => {
+				surrealdb::iam::revoke::revoke_record(kvs, &self.session, &sc, &id).await?
+			}
+			None => surrealdb::iam::revoke::revoke_all(kvs, &self.session).await?,
+		}
+		Ok(Value::None)
+	}
+
 	// ------------------------------
 	// Methods for identification
 	// ------------------------------
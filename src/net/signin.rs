@@ -3,11 +3,9 @@ use crate::err::Error;
 use crate::net::input::bytes_to_utf8;
 use crate::net::output;
 use crate::net::session;
-use crate::net::CF;
 use bytes::Bytes;
 use serde::Serialize;
 use surrealdb::dbs::Session;
-use surrealdb::opt::auth::Root;
 use surrealdb::sql::Value;
 use warp::Filter;
 
@@ -55,19 +53,13 @@ async fn handler(
 ) -> Result<impl warp::Reply, warp::Rejection> {
 	// Get a database reference
 	let kvs = DB.get().unwrap();
-	// Get the config options
-	let opts = CF.get().unwrap();
 	// Convert the HTTP body into text
 	let data = bytes_to_utf8(&body)?;
 	// Parse the provided data as JSON
 	match surrealdb::sql::json(data) {
 		// The provided value was an object
 		Ok(Value::Object(vars)) => {
-			let root = opts.pass.as_ref().map(|pass| Root {
-				username: &opts.user,
-				password: pass,
-			});
-			match surrealdb::iam::signin::signin(kvs, &root, &mut session, vars)
+			match surrealdb::iam::signin::signin(kvs, &None, &mut session, vars)
 				.await
 				.map_err(Error::from)
 			{
@@ -1,4 +1,11 @@
-use warp::http;
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::net::output;
+use crate::net::session;
+use bytes::{Buf, Bytes};
+use futures::{Stream, StreamExt, TryStreamExt};
+use hyper::body::Body;
+use surrealdb::dbs::Session;
 use warp::Filter;
 
 #[allow(opaque_hidden_inferred_bound)]
@@ -6,17 +13,76 @@ pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejecti
 	// Set base path
 	let base = warp::path("sync").and(warp::path::end());
 	// Set save method
-	let save = base.and(warp::get()).and_then(save);
+	let save = base.and(warp::get()).and(session::build()).and_then(save);
 	// Set load method
-	let load = base.and(warp::post()).and_then(load);
+	let load =
+		base.and(warp::post()).and(warp::body::stream()).and(session::build()).and_then(load);
 	// Specify route
 	save.or(load)
 }
 
-pub async fn load() -> Result<impl warp::Reply, warp::Rejection> {
-	Ok(warp::reply::with_status("Load", http::StatusCode::OK))
+pub async fn load(
+	body: impl Stream<Item = Result<impl Buf, warp::Error>> + Send + 'static,
+	session: Session,
+) -> Result<impl warp::Reply, warp::Rejection> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Create a new bounded channel
+			let (snd, rcv) = surrealdb::channel::new(1);
+			// Process all received chunks
+			tokio::spawn(async move {
+				let mut body = Box::pin(body.map_ok(|mut v| v.copy_to_bytes(v.remaining())));
+				while let Some(Ok(v)) = body.next().await {
+					if snd.send(v.to_vec()).await.is_err() {
+						break;
+					}
+				}
+			});
+			// Restore the received backup
+			match db.restore(rcv).await {
+				Ok(_) => Ok(output::none()),
+				// There was an error when restoring the backup
+				Err(err) => Err(warp::reject::custom(Error::from(err))),
+			}
+		}
+		// There was an error with permissions
+		_ => Err(warp::reject::custom(Error::InvalidAuth)),
+	}
 }
 
-pub async fn save() -> Result<impl warp::Reply, warp::Rejection> {
-	Ok(warp::reply::with_status("Save", http::StatusCode::OK))
+pub async fn save(session: Session) -> Result<impl warp::Reply, warp::Rejection> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Create a chunked response
+			let (mut chn, bdy) = Body::channel();
+			// Create a new bounded channel
+			let (snd, rcv) = surrealdb::channel::new(1);
+			// Spawn a new database backup
+			let task = tokio::spawn(db.backup(snd));
+			// Process all processed values
+			tokio::spawn(async move {
+				while let Ok(v) = rcv.recv().await {
+					if chn.send_data(Bytes::from(v)).await.is_err() {
+						break;
+					}
+				}
+				// Stop the backup if the client has disconnected
+				drop(rcv);
+				// Abort the response if the backup failed
+				if !matches!(task.await, Ok(Ok(_))) {
+					chn.abort();
+				}
+			});
+			// Return the chunked body
+			Ok(warp::reply::Response::new(bdy))
+		}
+		// There was an error with permissions
+		_ => Err(warp::reject::custom(Error::InvalidAuth)),
+	}
 }
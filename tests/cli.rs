@@ -171,14 +171,39 @@ mod cli_integration {
 			);
 		}
 
-		// Unfinished backup CLI
-		{
+		// Backup to file
+		let file = {
 			let file = tmp_file("backup.db");
 			let args = format!("backup --user root --pass {pass} http://{addr} {file}");
 			run(&args).output().expect("failed to run backup: {args}");
+			assert!(fs::read(&file).unwrap().starts_with(b"surrealdb-sync"));
+			file
+		};
 
-			// TODO: Once backups are functional, update this test.
-			assert_eq!(fs::read_to_string(file).unwrap(), "Save");
+		// Restoring into a datastore which is not empty fails
+		{
+			let args = format!("backup --user root --pass {pass} {file} http://{addr}");
+			run(&args).output().expect_err("restored into a datastore which is not empty");
+		}
+
+		// Restore the backup into another server
+		{
+			let other = format!("127.0.0.1:{}", port + 1000);
+			let start_args = format!(
+				"start --bind {other} --user root --pass {pass} memory --no-banner --log info"
+			);
+			let _other = run(&start_args);
+			std::thread::sleep(std::time::Duration::from_millis(5000));
+			let args = format!("backup --user root --pass {pass} {file} http://{other}");
+			run(&args).output().expect("failed to run restore: {args}");
+			let args = format!(
+				"sql --conn http://{other} --user root --pass {pass} --ns N --db D2 --pretty"
+			);
+			assert_eq!(
+				run(&args).input("SELECT * FROM thing;\n").output(),
+				Ok("[\n\t{\n\t\tid: thing:one\n\t}\n]\n\n".to_owned()),
+				"failed to send sql: {args}"
+			);
 		}
 
 		// Multi-statement (and multi-line) query including error(s) over WS